@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use arbor::indents::UnicodeIndent;
+use arbor::protocol::{ILazyTree, ITreeContent};
+use arbor::tui::{CrosstermBackend, Viewer};
+
+struct LazyDirTree {
+    pub path: std::path::PathBuf,
+}
+impl ITreeContent for LazyDirTree {
+    fn content(&self) -> impl AsRef<str> {
+        self.path
+            .file_name()
+            .unwrap_or(self.path.as_os_str())
+            .to_string_lossy()
+    }
+}
+impl ILazyTree for LazyDirTree {
+    type Leaf = LazyDirTree;
+    type Leaves = std::vec::IntoIter<LazyDirTree>;
+    fn leaves(&self) -> Self::Leaves {
+        std::fs::read_dir(&self.path)
+            .map(|entries| {
+                let mut children: Vec<LazyDirTree> = entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| LazyDirTree { path: entry.path() })
+                    .collect();
+                children.sort_by_key(|c| c.path.clone());
+                children
+            })
+            .unwrap_or_default()
+            .into_iter()
+    }
+}
+
+fn viewer() -> Viewer<UnicodeIndent, LazyDirTree> {
+    let tree = LazyDirTree {
+        path: PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+    };
+    Viewer::new(tree, UnicodeIndent)
+}
+
+fn main() -> std::io::Result<()> {
+    let mut viewer = viewer();
+    viewer.run(&mut CrosstermBackend::new(std::io::stdout())?)?;
+    println!("{}", viewer.selected().path.display());
+    Ok(())
+}
+#[test]
+fn test() {
+    use arbor::tui::{FakeBackend, Key};
+
+    let mut backend = FakeBackend::new(80, 24, [Key::Char('/'), Key::Char('s'), Key::Enter]);
+    let mut viewer = viewer();
+    viewer.run(&mut backend).unwrap();
+    println!("{}", backend.frames.last().unwrap().join("\n"));
+}
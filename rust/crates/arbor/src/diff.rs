@@ -0,0 +1,362 @@
+extern crate alloc;
+
+use alloc::collections::btree_map::BTreeMap;
+use alloc::collections::vec_deque::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+
+use crate::protocol::{IIndent, IOwnedTree, ITreeContent};
+use crate::renders::render_gutter_tree;
+
+/// How a node differs between the old and the new tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// The node is in both trees, with the same content.
+    Unchanged,
+
+    /// The node is only in the new tree.
+    Added,
+
+    /// The node is only in the old tree.
+    Removed,
+
+    /// The node is in both trees, but its content changed. Only happens for
+    /// the roots or when nodes are matched by a custom key.
+    Changed {
+        /// Content of the node in the old tree.
+        old: String,
+    },
+
+    /// The node moved to another parent.
+    Moved {
+        /// Path of the old parent, its ancestors' contents joined by `/`.
+        from: String,
+    },
+}
+
+/// A node of the diff between two trees.
+///
+/// The diff follows the shape of the new tree, with removed nodes kept at
+/// their old position among their siblings. Moved nodes are only shown at
+/// their new position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffTree {
+    /// How this node differs.
+    pub change: Change,
+
+    /// Content of the node, taken from the new tree unless it was removed.
+    pub content: String,
+
+    /// Children of the node.
+    pub leaves: Vec<DiffTree>,
+}
+
+impl DiffTree {
+    /// Copies `tree` with every node marked as `change`.
+    fn whole<T: IOwnedTree<Leaf = T>>(tree: &T, change: &Change) -> Self {
+        Self {
+            change: change.clone(),
+            content: String::from(tree.content().as_ref()),
+            leaves: tree
+                .leaves()
+                .map(|leaf| Self::whole(leaf, change))
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if this node or one of its descendants changed.
+    pub fn has_changes(&self) -> bool {
+        self.change != Change::Unchanged || self.leaves.iter().any(Self::has_changes)
+    }
+
+    fn at_mut(&mut self, path: &[usize]) -> &mut Self {
+        path.iter()
+            .fold(self, |node, &index| &mut node.leaves[index])
+    }
+}
+
+impl ITreeContent for DiffTree {
+    /// Returns the content, annotated with the old content of a changed node
+    /// or the old parent of a moved node.
+    fn content(&self) -> impl AsRef<str> {
+        match &self.change {
+            Change::Changed { old } => alloc::format!("{old} → {}", self.content),
+            Change::Moved { from } => alloc::format!("{} (moved from {from})", self.content),
+            _ => self.content.clone(),
+        }
+    }
+}
+
+impl IOwnedTree for DiffTree {
+    type Leaf = Self;
+    type Leaves<'a> = core::slice::Iter<'a, Self>;
+
+    fn leaves(&self) -> Self::Leaves<'_> { self.leaves.iter() }
+}
+
+/// Diffs two trees, matching nodes by content.
+///
+/// # Example
+///
+/// ```
+/// use arbor::diff::{Change, diff};
+/// use arbor::trees::OwnedTree;
+/// let old = OwnedTree::new("deps").with_leaves(["serde", "log"]);
+/// let new = OwnedTree::new("deps").with_leaves(["serde", "tracing"]);
+/// let diff = diff(&old, &new);
+/// assert_eq!(diff.leaves[1].change, Change::Removed);
+/// assert_eq!(diff.leaves[2].change, Change::Added);
+/// ```
+pub fn diff<T: IOwnedTree<Leaf = T>>(old: &T, new: &T) -> DiffTree {
+    diff_by_key(old, new, |node| String::from(node.content().as_ref()))
+}
+
+/// Diffs two trees, matching nodes by the key returned by `key`.
+///
+/// Children are matched among the children of matched parents, in order
+/// for repeated keys. The roots are always matched. A node whose key is
+/// only removed at one place and only added at another is reported as
+/// [`Change::Moved`] and diffed against its old subtree.
+///
+/// With a key other than the content, e.g. a package name for contents
+/// holding `name version`, matched nodes whose content differs are reported
+/// as [`Change::Changed`].
+pub fn diff_by_key<T, K, F>(old: &T, new: &T, key: F) -> DiffTree
+where
+    T: IOwnedTree<Leaf = T>,
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    let mut differ = Differ {
+        key: &key,
+        candidates: Some(BTreeMap::new()),
+    };
+    let mut tree = differ.node(old, new, &mut Vec::new(), &mut Vec::new());
+
+    let mut removals = Vec::new();
+    for (_, (added, removed)) in differ.candidates.unwrap_or_default() {
+        let ([(path, new)], [(old_path, old, from)]) = (added.as_slice(), removed.as_slice())
+        else {
+            continue;
+        };
+        let mut moved = Differ {
+            key: &key,
+            candidates: None,
+        }
+        .node(old, new, &mut path.clone(), &mut Vec::new());
+        moved.change = Change::Moved { from: from.clone() };
+        *tree.at_mut(path) = moved;
+        removals.push(old_path.clone());
+    }
+
+    // Removing the last paths first keeps the other paths valid.
+    removals.sort_unstable_by(|a, b| b.cmp(a));
+    for path in removals {
+        if let Some((index, parent)) = path.split_last() {
+            tree.at_mut(parent).leaves.remove(*index);
+        }
+    }
+    tree
+}
+
+/// Added and removed subtrees sharing a key: their path in the diff, their
+/// node and, for removed ones, the path of their old parent.
+type Candidates<'a, T> = (Vec<(Vec<usize>, &'a T)>, Vec<(Vec<usize>, &'a T, String)>);
+
+struct Differ<'a, 'k, T, K, F> {
+    key: &'k F,
+
+    /// Added and removed subtrees by key, collected to detect moves.
+    candidates: Option<BTreeMap<K, Candidates<'a, T>>>,
+}
+
+impl<'a, T, K, F> Differ<'a, '_, T, K, F>
+where
+    T: IOwnedTree<Leaf = T>,
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    /// Diffs two matched nodes.
+    ///
+    /// `path` is the position of the node in the diff and `ancestors` the
+    /// contents of its old ancestors.
+    fn node(
+        &mut self,
+        old: &'a T,
+        new: &'a T,
+        path: &mut Vec<usize>,
+        ancestors: &mut Vec<String>,
+    ) -> DiffTree {
+        let (old_content, new_content) = (old.content(), new.content());
+        let change = if old_content.as_ref() == new_content.as_ref() {
+            Change::Unchanged
+        } else {
+            Change::Changed {
+                old: String::from(old_content.as_ref()),
+            }
+        };
+
+        ancestors.push(String::from(old_content.as_ref()));
+        let leaves = self.leaves(old, new, path, ancestors);
+        ancestors.pop();
+
+        DiffTree {
+            change,
+            content: String::from(new_content.as_ref()),
+            leaves,
+        }
+    }
+
+    /// Diffs the children of two matched nodes.
+    fn leaves(
+        &mut self,
+        old: &'a T,
+        new: &'a T,
+        path: &mut Vec<usize>,
+        ancestors: &mut Vec<String>,
+    ) -> Vec<DiffTree> {
+        let old_leaves: Vec<&T> = old.leaves().collect();
+        let mut by_key: BTreeMap<K, VecDeque<usize>> = BTreeMap::new();
+        for (index, leaf) in old_leaves.iter().enumerate() {
+            by_key.entry((self.key)(leaf)).or_default().push_back(index);
+        }
+        let new_leaves: Vec<&T> = new.leaves().collect();
+        let matches: Vec<Option<usize>> = new_leaves
+            .iter()
+            .map(|leaf| {
+                by_key
+                    .get_mut(&(self.key)(leaf))
+                    .and_then(VecDeque::pop_front)
+            })
+            .collect();
+        let mut matched = alloc::vec![false; old_leaves.len()];
+        for &index in matches.iter().flatten() {
+            matched[index] = true;
+        }
+        // The old index of the next matched node, at or after each new one.
+        let mut next_match = alloc::vec![old_leaves.len(); new_leaves.len() + 1];
+        for position in (0..new_leaves.len()).rev() {
+            next_match[position] = matches[position].unwrap_or(next_match[position + 1]);
+        }
+
+        let mut leaves = Vec::new();
+        let mut next_old = 0;
+        for (position, new_leaf) in new_leaves.into_iter().enumerate() {
+            // Removed siblings come before the nodes added in their place, like
+            // in a unified diff.
+            while next_old < next_match[position] {
+                self.removed(
+                    &old_leaves,
+                    &matched,
+                    next_old,
+                    &mut leaves,
+                    path,
+                    ancestors,
+                );
+                next_old += 1;
+            }
+            path.push(leaves.len());
+            match matches[position] {
+                Some(index) => leaves.push(self.node(old_leaves[index], new_leaf, path, ancestors)),
+                None => {
+                    self.candidate(new_leaf, path, None);
+                    leaves.push(DiffTree::whole(new_leaf, &Change::Added));
+                }
+            }
+            path.pop();
+        }
+        for index in next_old..old_leaves.len() {
+            self.removed(&old_leaves, &matched, index, &mut leaves, path, ancestors);
+        }
+        leaves
+    }
+
+    /// Appends the old leaf at `index` as removed, unless it was matched.
+    fn removed(
+        &mut self,
+        old_leaves: &[&'a T],
+        matched: &[bool],
+        index: usize,
+        leaves: &mut Vec<DiffTree>,
+        path: &mut Vec<usize>,
+        ancestors: &[String],
+    ) {
+        if matched[index] {
+            return;
+        }
+        path.push(leaves.len());
+        self.candidate(old_leaves[index], path, Some(ancestors.join("/")));
+        path.pop();
+        leaves.push(DiffTree::whole(old_leaves[index], &Change::Removed));
+    }
+
+    /// Records an added subtree, or a removed one with the path of its old
+    /// parent, as a move candidate.
+    fn candidate(&mut self, node: &'a T, path: &[usize], from: Option<String>) {
+        let Some(candidates) = &mut self.candidates else {
+            return;
+        };
+        let (added, removed) = candidates.entry((self.key)(node)).or_default();
+        match from {
+            Some(from) => removed.push((path.to_vec(), node, from)),
+            None => added.push((path.to_vec(), node)),
+        }
+    }
+}
+
+/// Renders a [`DiffTree`] with a gutter of change markers.
+///
+/// Every line starts with `+` for added, `-` for removed, `~` for changed
+/// or moved nodes and a blank for unchanged ones, followed by the tree drawn
+/// with `indent`.
+///
+/// # Example
+///
+/// ```
+/// use arbor::diff::{DiffRender, diff};
+/// use arbor::indents::AsciiIndent;
+/// use arbor::trees::OwnedTree;
+/// let old = OwnedTree::new("deps").with_leaves(["serde", "log"]);
+/// let new = OwnedTree::new("deps").with_leaves(["serde", "tracing"]);
+/// let render = DiffRender {
+///     diff: &diff(&old, &new),
+///     indent: AsciiIndent,
+///     width: 0,
+/// };
+/// assert!(render.to_string().contains("+ `-- tracing"));
+/// ```
+pub struct DiffRender<'a, I> {
+    /// Diff to render.
+    pub diff: &'a DiffTree,
+
+    /// Indentation style used for all nodes.
+    pub indent: I,
+
+    /// Optional wrapping width used when the `textwrap` feature is enabled.
+    #[cfg(feature = "textwrap")]
+    pub width: usize,
+}
+
+impl<I: IIndent> Display for DiffRender<'_, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        render_gutter_tree(
+            f,
+            self.diff,
+            &self.indent,
+            |node: &DiffTree| gutter(&node.change),
+            #[cfg(feature = "textwrap")]
+            self.width,
+        )
+    }
+}
+
+/// Returns the marker of `change` followed by a space.
+const fn gutter(change: &Change) -> &'static str {
+    match change {
+        Change::Unchanged => "  ",
+        Change::Added => "+ ",
+        Change::Removed => "- ",
+        Change::Changed { .. } | Change::Moved { .. } => "~ ",
+    }
+}
@@ -0,0 +1,228 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Write};
+
+use crate::protocol::{ILazyTree, IOwnedTree, ITreeContent};
+use crate::renders::{IRenderNode, LazyNode, OwnedNode};
+
+/// Exports a tree implementing [`IOwnedTree`] as a Graphviz DOT digraph.
+///
+/// Nodes are numbered in pre-order (`n0` is the root) and labelled with
+/// their content.
+///
+/// # Example
+///
+/// ```
+/// use arbor::export::OwnedDotRender;
+/// use arbor::trees::OwnedTree;
+/// let tree = OwnedTree::new("foo").with_leaves(["bar"]);
+/// let dot = OwnedDotRender { tree: &tree }.to_string();
+/// assert!(dot.contains("n0 -> n1;"));
+/// ```
+pub struct OwnedDotRender<'a, T> {
+    /// Root tree node to export.
+    pub tree: &'a T,
+}
+
+impl<T: IOwnedTree<Leaf = T>> Display for OwnedDotRender<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_graph::<OwnedNode<'_, T>>(f, Graph::Dot, self.tree, OwnedNode::leaves_of(self.tree))
+    }
+}
+
+/// Exports a tree implementing [`ILazyTree`] as a Graphviz DOT digraph.
+pub struct LazyDotRender<T> {
+    /// Root tree node to export.
+    pub tree: T,
+}
+
+impl<T: ILazyTree<Leaf = T>> Display for LazyDotRender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_graph::<LazyNode<T>>(f, Graph::Dot, &self.tree, LazyNode::leaves_of(&self.tree))
+    }
+}
+
+/// Exports a tree implementing [`IOwnedTree`] as a Mermaid `graph TD`
+/// flowchart.
+///
+/// Nodes are numbered in pre-order (`n0` is the root) and labelled with
+/// their content.
+pub struct OwnedMermaidRender<'a, T> {
+    /// Root tree node to export.
+    pub tree: &'a T,
+}
+
+impl<T: IOwnedTree<Leaf = T>> Display for OwnedMermaidRender<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_graph::<OwnedNode<'_, T>>(
+            f,
+            Graph::Mermaid,
+            self.tree,
+            OwnedNode::leaves_of(self.tree),
+        )
+    }
+}
+
+/// Exports a tree implementing [`ILazyTree`] as a Mermaid `graph TD`
+/// flowchart.
+pub struct LazyMermaidRender<T> {
+    /// Root tree node to export.
+    pub tree: T,
+}
+
+impl<T: ILazyTree<Leaf = T>> Display for LazyMermaidRender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_graph::<LazyNode<T>>(
+            f,
+            Graph::Mermaid,
+            &self.tree,
+            LazyNode::leaves_of(&self.tree),
+        )
+    }
+}
+
+/// Graph description languages supported by the exporters.
+#[derive(Clone, Copy)]
+enum Graph {
+    Dot,
+    Mermaid,
+}
+
+impl Graph {
+    fn write_header(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dot => writeln!(f, "digraph {{"),
+            Self::Mermaid => writeln!(f, "graph TD"),
+        }
+    }
+
+    fn write_node(self, f: &mut fmt::Formatter<'_>, id: usize, label: &str) -> fmt::Result {
+        match self {
+            Self::Dot => write!(f, "    n{id} [label=\"")?,
+            Self::Mermaid => write!(f, "    n{id}[\"")?,
+        }
+        for c in label.chars() {
+            match (self, c) {
+                (_, '\r') => {}
+                (Self::Dot, '"') => f.write_str("\\\"")?,
+                (Self::Dot, '\\') => f.write_str("\\\\")?,
+                (Self::Dot, '\n') => f.write_str("\\n")?,
+                (Self::Mermaid, '"') => f.write_str("#quot;")?,
+                (Self::Mermaid, '<') => f.write_str("#lt;")?,
+                (Self::Mermaid, '>') => f.write_str("#gt;")?,
+                (Self::Mermaid, '\n') => f.write_str("<br/>")?,
+                (_, c) => f.write_char(c)?,
+            }
+        }
+        match self {
+            Self::Dot => writeln!(f, "\"];"),
+            Self::Mermaid => writeln!(f, "\"]"),
+        }
+    }
+
+    fn write_edge(self, f: &mut fmt::Formatter<'_>, parent: usize, child: usize) -> fmt::Result {
+        match self {
+            Self::Dot => writeln!(f, "    n{parent} -> n{child};"),
+            Self::Mermaid => writeln!(f, "    n{parent} --> n{child}"),
+        }
+    }
+
+    fn write_footer(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dot => writeln!(f, "}}"),
+            Self::Mermaid => Ok(()),
+        }
+    }
+}
+
+/// Writes every node and parent-child edge of a tree, depth-first.
+fn write_graph<'i, N: IRenderNode<'i, ()>>(
+    f: &mut fmt::Formatter<'_>,
+    graph: Graph,
+    root: &impl ITreeContent,
+    leaves: N::Leaves,
+) -> fmt::Result {
+    graph.write_header(f)?;
+    graph.write_node(f, 0, root.content().as_ref())?;
+
+    let mut next_id = 1;
+    let mut stack = Vec::from([(leaves, 0)]);
+    while let Some((leaves, parent)) = stack.last_mut() {
+        let parent = *parent;
+        let Some(leaf) = leaves.next() else {
+            stack.pop();
+            continue;
+        };
+        let id = next_id;
+        next_id += 1;
+        graph.write_node(f, id, leaf.content().as_ref())?;
+        graph.write_edge(f, parent, id)?;
+        stack.push((leaf.leaves(), id));
+    }
+
+    graph.write_footer(f)
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    use crate::protocol::{ILazyTree, IOwnedTree};
+
+    /// Serializes a tree implementing [`IOwnedTree`] as nested
+    /// `{"content": .., "children": [..]}` objects.
+    ///
+    /// The output deserializes into
+    /// [`OwnedTree<String>`](crate::trees::OwnedTree).
+    pub struct OwnedSerialize<'a, T> {
+        /// Root tree node to serialize.
+        pub tree: &'a T,
+    }
+
+    impl<T: IOwnedTree<Leaf = T>> Serialize for OwnedSerialize<'_, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut node = serializer.serialize_struct("OwnedTree", 2)?;
+            node.serialize_field("content", self.tree.content().as_ref())?;
+            node.serialize_field("children", &OwnedChildren(self.tree))?;
+            node.end()
+        }
+    }
+
+    struct OwnedChildren<'a, T>(&'a T);
+
+    impl<T: IOwnedTree<Leaf = T>> Serialize for OwnedChildren<'_, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.0.leaves().map(|tree| OwnedSerialize { tree }))
+        }
+    }
+
+    /// Serializes a tree implementing [`ILazyTree`] as nested
+    /// `{"content": .., "children": [..]}` objects.
+    ///
+    /// Children are generated while serializing. The output deserializes
+    /// into [`OwnedTree<String>`](crate::trees::OwnedTree).
+    pub struct LazySerialize<T> {
+        /// Root tree node to serialize.
+        pub tree: T,
+    }
+
+    impl<T: ILazyTree<Leaf = T>> Serialize for LazySerialize<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut node = serializer.serialize_struct("OwnedTree", 2)?;
+            node.serialize_field("content", self.tree.content().as_ref())?;
+            node.serialize_field("children", &LazyChildren(&self.tree))?;
+            node.end()
+        }
+    }
+
+    struct LazyChildren<'a, T>(&'a T);
+
+    impl<T: ILazyTree<Leaf = T>> Serialize for LazyChildren<'_, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.0.leaves().map(|tree| LazySerialize { tree }))
+        }
+    }
+}
+#[cfg(feature = "serde")]
+pub use serialize::{LazySerialize, OwnedSerialize};
@@ -1,5 +1,15 @@
 #![no_std]
+#[cfg(feature = "tui-crossterm")]
+extern crate std;
+
+pub mod diff;
+pub mod export;
 pub mod indents;
+pub mod parse;
 pub mod protocol;
 pub mod renders;
+pub mod style;
+pub mod traverse;
 pub mod trees;
+#[cfg(feature = "tui")]
+pub mod tui;
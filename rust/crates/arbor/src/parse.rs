@@ -0,0 +1,216 @@
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+
+use crate::indents::UniversalIndent;
+use crate::protocol::{IIndent, Layer, Line};
+use crate::trees::OwnedTree;
+
+/// Error returned when text cannot be parsed into a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The text holds no node.
+    Empty,
+
+    /// A node is indented more than one level below the previous one.
+    UnexpectedIndent {
+        /// Line number of the node, starting at `1`.
+        line: usize,
+    },
+
+    /// An outline has a second unindented line.
+    MultipleRoots {
+        /// Line number of the second root, starting at `1`.
+        line: usize,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no tree node found"),
+            Self::UnexpectedIndent { line } => {
+                write!(f, "line {line} is indented more than one level deeper")
+            }
+            Self::MultipleRoots { line } => write!(f, "line {line} is a second root"),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Parses the output of a renderer drawn with `indent` back into a tree.
+///
+/// This is the inverse of rendering with [`OwnedRender`] and any of the
+/// tree drawing styles, such as [`UnicodeIndent`] or [`AsciiIndent`]. Lines
+/// starting with a branch are nodes, other lines continue the content of the
+/// previous node. Content wrapped by the `textwrap` feature therefore comes
+/// back with a line break at each wrap.
+///
+/// With styles drawing the same prefix for the first and the other lines,
+/// such as [`SpaceIndent`], every line is a node and blank lines are
+/// skipped.
+///
+/// [`OwnedRender`]: crate::renders::OwnedRender
+/// [`UnicodeIndent`]: crate::indents::UnicodeIndent
+/// [`AsciiIndent`]: crate::indents::AsciiIndent
+/// [`SpaceIndent`]: crate::indents::SpaceIndent
+///
+/// # Example
+///
+/// ```
+/// use arbor::indents::UnicodeIndent;
+/// use arbor::parse::parse_indented;
+/// use arbor::protocol::{IOwnedTree, ITreeContent};
+/// let tree = parse_indented("foo\n├── bar\n╰── baz\n    qux", &UnicodeIndent).unwrap();
+/// let leaves: Vec<_> = tree.leaves().map(|leaf| leaf.content().as_ref().to_string()).collect();
+/// assert_eq!(leaves, ["bar", "baz\nqux"]);
+/// ```
+pub fn parse_indented<I: IIndent>(text: &str, indent: &I) -> Result<OwnedTree<String>, ParseError> {
+    let firsts = [Layer::Top, Layer::Middle, Layer::Bottom]
+        .map(|layer| indent.get_indent(layer, Line::First));
+    let others = [Layer::Top, Layer::Middle, Layer::Bottom]
+        .map(|layer| indent.get_indent(layer, Line::Other));
+    let outline = firsts.iter().all(|first| others.contains(first));
+
+    let mut stack: Vec<(String, Vec<OwnedTree<String>>)> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        if stack.is_empty() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let root = line
+                .strip_prefix(indent.get_indent(Layer::Root, Line::First))
+                .unwrap_or(line);
+            stack.push((String::from(root), Vec::new()));
+            continue;
+        }
+        if outline && line.trim().is_empty() {
+            continue;
+        }
+
+        let (depth, content) = match split_node(line, &firsts, &others, outline) {
+            Some((depth, content)) => (depth, content),
+            None if outline => return Err(ParseError::MultipleRoots { line: number }),
+            None => {
+                // A continuation line repeats the prefix of its node.
+                let content = strip_levels(line, &others, stack.len() - 1);
+                let (last, _) = stack.last_mut().expect("the stack is not empty");
+                last.push('\n');
+                last.push_str(content);
+                continue;
+            }
+        };
+        if depth > stack.len() {
+            return Err(ParseError::UnexpectedIndent { line: number });
+        }
+        fold(&mut stack, depth);
+        stack.push((String::from(content), Vec::new()));
+    }
+
+    fold(&mut stack, 1);
+    stack
+        .pop()
+        .map(|(content, leaves)| OwnedTree::new(content).with_leaves(leaves))
+        .ok_or(ParseError::Empty)
+}
+
+/// Parses an outline indented by `unit`, e.g. two spaces or a tab, into a
+/// tree.
+///
+/// The first line is the root and each level of indentation is one level
+/// deeper in the tree. Blank lines are skipped.
+///
+/// # Example
+///
+/// ```
+/// use arbor::parse::parse_outline;
+/// use arbor::traverse::IOwnedTreeExt;
+/// let tree = parse_outline("root\n\tchild\n\t\tgrandchild\n\tchild", "\t").unwrap();
+/// assert_eq!(tree.pre_order().count(), 4);
+/// ```
+pub fn parse_outline(text: &str, unit: &str) -> Result<OwnedTree<String>, ParseError> {
+    let unit = String::from(unit);
+    let indent = UniversalIndent {
+        root_first: String::new(),
+        root_other: String::new(),
+        top_first: unit.clone(),
+        top_other: unit.clone(),
+        mid_first: unit.clone(),
+        mid_other: unit.clone(),
+        bottom_first: unit.clone(),
+        bottom_other: unit,
+    };
+    parse_indented(text, &indent)
+}
+
+/// Splits a node line into its depth and content, or returns `None` for a
+/// line that does not start a node.
+fn split_node<'a>(
+    mut line: &'a str,
+    firsts: &[&str],
+    others: &[&str],
+    outline: bool,
+) -> Option<(usize, &'a str)> {
+    let mut depth = 0;
+    loop {
+        if !outline {
+            for first in firsts {
+                if let Some(content) = line.strip_prefix(first) {
+                    return Some((depth + 1, content));
+                }
+                // Trailing whitespace may have been trimmed from nodes
+                // without content.
+                if !line.is_empty() && line == first.trim_end() {
+                    return Some((depth + 1, ""));
+                }
+            }
+        }
+        match strip_other(line, others) {
+            Some(rest) => {
+                line = rest;
+                depth += 1;
+            }
+            None => break,
+        }
+    }
+    (outline && depth > 0).then_some((depth, line))
+}
+
+/// Strips one non-empty continuation prefix from `line`.
+fn strip_other<'a>(line: &'a str, others: &[&str]) -> Option<&'a str> {
+    others
+        .iter()
+        .filter(|other| !other.is_empty())
+        .find_map(|other| line.strip_prefix(other))
+}
+
+/// Strips up to `levels` prefixes from a continuation line.
+fn strip_levels<'a>(mut line: &'a str, others: &[&str], levels: usize) -> &'a str {
+    for _ in 0..levels {
+        match strip_other(line, others) {
+            Some(rest) => line = rest,
+            None => return line.trim_start(),
+        }
+    }
+    line
+}
+
+/// Attaches the nodes deeper than `depth` to their parents, leaving `depth`
+/// nodes on the stack.
+fn fold(stack: &mut Vec<(String, Vec<OwnedTree<String>>)>, depth: usize) {
+    while stack.len() > depth {
+        let (content, leaves) = stack.pop().expect("the stack is deeper than depth");
+        let node = OwnedTree::new(content).with_leaves(leaves);
+        match stack.last_mut() {
+            Some((_, parent)) => parent.push(node),
+            None => {
+                stack.push((String::new(), Vec::from([node])));
+                return;
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::style::Span;
+
 /// Represents the vertical position of a node relative to its parent
 /// in a hierarchical tree layout.
 ///
@@ -93,6 +95,30 @@ pub trait ITreeContent {
     fn content(&self) -> impl AsRef<str>;
 }
 
+/// Provides styled content for a tree node.
+///
+/// Styles are kept apart from the text instead of being written into
+/// [`ITreeContent::content`] as escape sequences, so renderers measure and
+/// wrap the plain text and apply the styles to each wrapped line afterwards.
+///
+/// The concatenated text of the spans is expected to equal the content.
+pub trait IStyledContent: ITreeContent {
+    /// Returns the content of this node split into styled spans.
+    fn spans(&self) -> impl IntoIterator<Item = Span<'_>>;
+}
+
+/// Provides extra cells rendered next to the content of a tree node.
+///
+/// Column-aware renderers align the cells of all nodes in columns to the
+/// right of the indented tree, similar to a long listing of a directory tree.
+///
+/// Cells are placed on the first line of the node. A node may expose fewer
+/// cells than others; the missing ones are left blank.
+pub trait ITreeColumns: ITreeContent {
+    /// Returns the cells associated with this node, from left to right.
+    fn columns(&self) -> impl IntoIterator<Item = impl AsRef<str>>;
+}
+
 /// A tree whose structure is owned and accessed through references.
 ///
 /// This trait models a hierarchical structure where each node owns its
@@ -1,19 +1,27 @@
 extern crate alloc;
 
-use alloc::collections::vec_deque::VecDeque;
-use alloc::rc::Rc;
+use alloc::collections::btree_set::BTreeSet;
 use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt::{self, Display};
+use core::iter::Map;
 
 use crate::protocol::{
-    IIndent, ILazyTree, IOwnedTree, IStyledOwnedTree, ITreeContent, Layer, Line,
+    IIndent, ILazyTree, IOwnedTree, IStyledContent, IStyledOwnedTree, ITreeColumns, ITreeContent,
+    Layer, Line,
 };
+use crate::style::{Span, StyledText};
+
+/// Separator written between the tree and each cell column.
+const COLUMN_GAP: &str = "  ";
 
 /// Renderer for trees implementing [`IOwnedTree`].
 ///
 /// This renderer traverses a tree whose nodes are referenced (`&T`) and whose
 /// children are accessed via iterators yielding references. Rendering is done
-/// in a breadth-first manner using an internal queue.
+/// depth-first with an explicit stack, and each line is written to the
+/// formatter as soon as its node is visited. Nodes are written in pre-order,
+/// parents before their children, like `tree(1)`.
 ///
 /// The indentation style is fixed for the entire tree and provided through
 /// the `indent` field.
@@ -32,55 +40,49 @@ pub struct OwnedRender<'a, I, T> {
     pub width: usize,
 }
 
-impl<I, T> Display for OwnedRender<'_, I, T>
+impl<I, T> OwnedRender<'_, I, T>
 where
     I: IIndent,
     T: IOwnedTree<Leaf = T>,
 {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut queue = VecDeque::new();
+    /// Applies `options` to the rendered tree.
+    pub const fn with_options(self, options: RenderOptions) -> WithOptions<Self> {
+        WithOptions {
+            render: self,
+            options,
+        }
+    }
 
-        render_content(
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, options: &RenderOptions) -> fmt::Result {
+        render_tree::<I, OwnedNode<'_, T>>(
             f,
             self.tree,
-            Layer::Root,
-            "",
             &self.indent,
+            OwnedNode::leaves_of(self.tree),
+            options,
             #[cfg(feature = "textwrap")]
             self.width,
-        )?;
-
-        enqueue(
-            &mut queue,
-            self.tree.leaves(),
-            &Rc::new(String::new()),
-            &self.indent,
-        );
+        )
+    }
+}
 
-        while let Some((leaf, layer, prefix, _)) = queue.pop_front() {
-            render_content(
-                f,
-                leaf,
-                layer,
-                &prefix,
-                &self.indent,
-                #[cfg(feature = "textwrap")]
-                self.width,
-            )?;
+impl<I, T> Display for OwnedRender<'_, I, T>
+where
+    I: IIndent,
+    T: IOwnedTree<Leaf = T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &RenderOptions::default())
+    }
+}
 
-            let mut iter = leaf.leaves().peekable();
-            if iter.peek().is_some() {
-                let mut leave_prefix = (*prefix).clone();
-                leave_prefix.push_str(self.indent.get_indent(layer, Line::Other));
-                enqueue(
-                    &mut queue,
-                    leaf.leaves(),
-                    &Rc::new(leave_prefix),
-                    &self.indent,
-                );
-            }
-        }
-        Ok(())
+impl<I, T> Display for WithOptions<OwnedRender<'_, I, T>>
+where
+    I: IIndent,
+    T: IOwnedTree<Leaf = T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render.fmt_with(f, &self.options)
     }
 }
 
@@ -101,59 +103,55 @@ pub struct StyledOwnedRender<'a, T> {
     pub width: usize,
 }
 
-impl<I, T> Display for StyledOwnedRender<'_, T>
+impl<I, T> StyledOwnedRender<'_, T>
 where
     I: IIndent,
     T: IStyledOwnedTree<Indent = I, Leaf = T>,
 {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut queue = VecDeque::new();
+    /// Applies `options` to the rendered tree.
+    pub const fn with_options(self, options: RenderOptions) -> WithOptions<Self> {
+        WithOptions {
+            render: self,
+            options,
+        }
+    }
 
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, options: &RenderOptions) -> fmt::Result {
         let default_indent = I::default();
         let indent = self.tree.indent().as_ref().unwrap_or(&default_indent);
 
-        render_content(
+        render_tree::<I, StyledNode<'_, T, I>>(
             f,
             self.tree,
-            Layer::Root,
-            "",
             indent,
+            StyledLeaves {
+                leaves: self.tree.leaves(),
+                indent,
+            },
+            options,
             #[cfg(feature = "textwrap")]
             self.width,
-        )?;
-
-        enqueue(
-            &mut queue,
-            self.tree.leaves(),
-            &Rc::new(String::new()),
-            indent,
-        );
+        )
+    }
+}
 
-        while let Some((leaf, layer, prefix, indent)) = queue.pop_front() {
-            render_content(
-                f,
-                leaf,
-                layer,
-                &prefix,
-                indent,
-                #[cfg(feature = "textwrap")]
-                self.width,
-            )?;
-            let mut iter = leaf.leaves().peekable();
-            if iter.peek().is_some() {
-                let mut leave_spaces = (*prefix).clone();
-                let leaf_indent = leaf.indent().as_ref().unwrap_or(indent);
-                leave_spaces.push_str(indent.get_indent(layer, Line::Other));
-                enqueue(
-                    &mut queue,
-                    leaf.leaves(),
-                    &Rc::new(leave_spaces),
-                    leaf_indent,
-                );
-            }
-        }
+impl<I, T> Display for StyledOwnedRender<'_, T>
+where
+    I: IIndent,
+    T: IStyledOwnedTree<Indent = I, Leaf = T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &RenderOptions::default())
+    }
+}
 
-        Ok(())
+impl<I, T> Display for WithOptions<StyledOwnedRender<'_, T>>
+where
+    I: IIndent,
+    T: IStyledOwnedTree<Indent = I, Leaf = T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render.fmt_with(f, &self.options)
     }
 }
 
@@ -163,8 +161,9 @@ where
 /// `leaves()` yields values rather than references. This enables lazy or
 /// streaming tree construction where nodes may be generated dynamically.
 ///
-/// Because nodes are owned, this renderer stores them directly in the queue
-/// during traversal.
+/// Rendering is depth-first and only keeps the children iterators along the
+/// path from the root to the current node, so arbitrarily large hierarchies
+/// can be rendered with memory proportional to their depth.
 pub struct LazyRender<I, T> {
     /// Root tree node to render.
     pub tree: T,
@@ -177,170 +176,1217 @@ pub struct LazyRender<I, T> {
     pub width: usize,
 }
 
-impl<I, T> Display for LazyRender<I, T>
+impl<I, T> LazyRender<I, T>
 where
-    I: IIndent + Clone,
+    I: IIndent,
     T: ILazyTree<Leaf = T>,
 {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut queue = VecDeque::new();
+    /// Applies `options` to the rendered tree.
+    pub const fn with_options(self, options: RenderOptions) -> WithOptions<Self> {
+        WithOptions {
+            render: self,
+            options,
+        }
+    }
 
-        render_content(
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, options: &RenderOptions) -> fmt::Result {
+        render_tree::<I, LazyNode<T>>(
             f,
             &self.tree,
-            Layer::Root,
-            "",
             &self.indent,
+            LazyNode::leaves_of(&self.tree),
+            options,
             #[cfg(feature = "textwrap")]
             self.width,
-        )?;
+        )
+    }
+}
 
-        enqueue(
-            &mut queue,
-            self.tree.leaves(),
-            &Rc::new(String::new()),
-            &self.indent,
-        );
+impl<I, T> Display for LazyRender<I, T>
+where
+    I: IIndent,
+    T: ILazyTree<Leaf = T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &RenderOptions::default())
+    }
+}
 
-        while let Some((leaf, layer, prefix, indent)) = queue.pop_front() {
-            render_content(
-                f,
-                &leaf,
-                layer,
-                &prefix,
-                indent,
-                #[cfg(feature = "textwrap")]
-                self.width,
-            )?;
+impl<I, T> Display for WithOptions<LazyRender<I, T>>
+where
+    I: IIndent,
+    T: ILazyTree<Leaf = T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render.fmt_with(f, &self.options)
+    }
+}
 
-            let mut iter = leaf.leaves().peekable();
-            if iter.peek().is_some() {
-                let mut leave_prefix = (*prefix).clone();
-                leave_prefix.push_str(self.indent.get_indent(layer, Line::Other));
-                enqueue(
-                    &mut queue,
-                    leaf.leaves(),
-                    &Rc::new(leave_prefix),
-                    &self.indent,
-                );
-            }
+/// Renderer for trees implementing [`IOwnedTree`] and [`IStyledContent`].
+///
+/// Each node is rendered from its styled spans. The plain text is wrapped
+/// first, so the width is measured without escape sequences, then the
+/// colors, attributes and hyperlinks of the spans are applied to every
+/// wrapped line. Indentation prefixes are never styled.
+///
+/// In a chain merged by [`RenderOptions::collapse_chains`], only the last
+/// node keeps its styles.
+pub struct OwnedSpanRender<'a, I, T> {
+    /// Root tree node to render.
+    pub tree: &'a T,
+
+    /// Indentation style used for all nodes.
+    pub indent: I,
+
+    /// Whether styles and hyperlinks are written.
+    ///
+    /// Disable it when the output is not a terminal, such as a pipe or a
+    /// file, to get the plain text.
+    pub color: bool,
+
+    /// Optional wrapping width used when the `textwrap` feature is enabled.
+    #[cfg(feature = "textwrap")]
+    pub width: usize,
+}
+
+impl<I, T> OwnedSpanRender<'_, I, T>
+where
+    I: IIndent,
+    T: IOwnedTree<Leaf = T> + IStyledContent,
+{
+    /// Applies `options` to the rendered tree.
+    pub const fn with_options(self, options: RenderOptions) -> WithOptions<Self> {
+        WithOptions {
+            render: self,
+            options,
         }
+    }
 
-        Ok(())
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, options: &RenderOptions) -> fmt::Result {
+        render_span_tree::<I, OwnedNode<'_, T>>(
+            f,
+            self.tree,
+            &self.indent,
+            OwnedNode::leaves_of(self.tree),
+            options,
+            self.color,
+            #[cfg(feature = "textwrap")]
+            self.width,
+        )
     }
 }
 
-/// Pushes a set of child nodes into the rendering queue.
-///
-/// The iterator of leaves is collected and then pushed in reverse order so
-/// that the first child is processed first when popping from the front of
-/// the queue.
-///
-/// Each child is annotated with a [`Layer`] describing whether it is the
-/// top, middle, or bottom branch within the current sibling group.
-fn enqueue<I, T, It>(
-    queue: &mut VecDeque<(T, Layer, Rc<String>, I)>,
-    leaves: It,
-    prefix: &Rc<String>,
-    indent: I,
-) where
-    It: DoubleEndedIterator<Item = T>,
-    I: Clone,
-{
-    let leaves: alloc::vec::Vec<T> = leaves.collect();
-    let last = leaves.len().saturating_sub(1);
-
-    for (i, leaf) in leaves.into_iter().rev().enumerate() {
-        let layer = match i {
-            0 => Layer::Bottom,
-            i if i == last => Layer::Top,
-            _ => Layer::Middle,
-        };
+impl<I, T> Display for OwnedSpanRender<'_, I, T>
+where
+    I: IIndent,
+    T: IOwnedTree<Leaf = T> + IStyledContent,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &RenderOptions::default())
+    }
+}
 
-        queue.push_front((leaf, layer, prefix.clone(), indent.clone()));
+impl<I, T> Display for WithOptions<OwnedSpanRender<'_, I, T>>
+where
+    I: IIndent,
+    T: IOwnedTree<Leaf = T> + IStyledContent,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render.fmt_with(f, &self.options)
     }
 }
 
-/// Renders the textual content of a node without line wrapping.
-///
-/// The node content may contain multiple lines. Each line is rendered with
-/// the appropriate prefix and indentation marker depending on whether it
-/// is the first line or a continuation line.
+/// Renderer for trees implementing [`ILazyTree`] and [`IStyledContent`].
 ///
-/// The `Layer` determines which branch marker is emitted by the indentation
-/// style implementation.
-fn render_content_no_wrap<I, T>(
-    f: &mut fmt::Formatter<'_>,
-    node: &T,
-    layer: Layer,
-    prefix: &str,
-    indent: &I,
-) -> fmt::Result
+/// See [`OwnedSpanRender`] for how styles are applied.
+pub struct LazySpanRender<I, T> {
+    /// Root tree node to render.
+    pub tree: T,
+
+    /// Indentation style used for all nodes.
+    pub indent: I,
+
+    /// Whether styles and hyperlinks are written.
+    pub color: bool,
+
+    /// Optional wrapping width used when the `textwrap` feature is enabled.
+    #[cfg(feature = "textwrap")]
+    pub width: usize,
+}
+
+impl<I, T> LazySpanRender<I, T>
 where
     I: IIndent,
-    T: ITreeContent,
+    T: ILazyTree<Leaf = T> + IStyledContent,
 {
-    for (line_index, text) in node.content().as_ref().lines().enumerate() {
-        f.write_str(prefix)?;
-
-        f.write_str(indent.get_indent(
-            layer,
-            if line_index == 0 {
-                Line::First
-            } else {
-                Line::Other
-            },
-        ))?;
+    /// Applies `options` to the rendered tree.
+    pub const fn with_options(self, options: RenderOptions) -> WithOptions<Self> {
+        WithOptions {
+            render: self,
+            options,
+        }
+    }
 
-        f.write_str(text)?;
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, options: &RenderOptions) -> fmt::Result {
+        render_span_tree::<I, LazyNode<T>>(
+            f,
+            &self.tree,
+            &self.indent,
+            LazyNode::leaves_of(&self.tree),
+            options,
+            self.color,
+            #[cfg(feature = "textwrap")]
+            self.width,
+        )
+    }
+}
 
-        writeln!(f)?;
+impl<I, T> Display for LazySpanRender<I, T>
+where
+    I: IIndent,
+    T: ILazyTree<Leaf = T> + IStyledContent,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, &RenderOptions::default())
     }
+}
 
-    Ok(())
+impl<I, T> Display for WithOptions<LazySpanRender<I, T>>
+where
+    I: IIndent,
+    T: ILazyTree<Leaf = T> + IStyledContent,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render.fmt_with(f, &self.options)
+    }
 }
 
-/// Renders the textual content of a node with optional line wrapping.
+/// Renderer for trees implementing [`IOwnedTree`] and [`ITreeColumns`].
 ///
-/// When the `textwrap` feature is disabled, this function delegates to
-/// [`render_content_no_wrap`].
+/// The cells of every node are aligned in columns to the right of the
+/// indented tree. Column widths are measured in terminal cells and include
+/// the indentation prefix of each node, so the tree is traversed twice:
+/// once to measure and once to render.
 ///
-/// When enabled, the content is wrapped to the configured width while
-/// preserving the indentation prefixes used for the first and subsequent
-/// lines.
-fn render_content<I, T>(
-    f: &mut fmt::Formatter<'_>,
-    node: &T,
-    layer: Layer,
-    prefix: &str,
-    indent: &I,
-    #[cfg(feature = "textwrap")] width: usize,
-) -> fmt::Result
+/// Content is not wrapped, since wrapping would break the column layout.
+pub struct OwnedColumnRender<'a, I, T> {
+    /// Root tree node to render.
+    pub tree: &'a T,
+
+    /// Indentation style used for all nodes.
+    pub indent: I,
+}
+
+impl<I, T> Display for OwnedColumnRender<'_, I, T>
 where
     I: IIndent,
-    T: ITreeContent,
+    T: IOwnedTree<Leaf = T> + ITreeColumns,
 {
-    #[cfg(not(feature = "textwrap"))]
-    render_content_no_wrap(f, node, layer, prefix, indent)?;
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        render_columns::<I, OwnedNode<'_, T>>(f, self.tree, &self.indent, || {
+            OwnedNode::leaves_of(self.tree)
+        })
+    }
+}
 
-    #[cfg(feature = "textwrap")]
-    if width == 0 {
-        render_content_no_wrap(f, node, layer, prefix, indent)?;
-    } else {
-        let initial_indent = alloc::format!("{}{}", prefix, indent.get_indent(layer, Line::First));
+/// Renderer for trees implementing [`ILazyTree`] and [`ITreeColumns`].
+///
+/// This is the lazy counterpart of [`OwnedColumnRender`]. Because column
+/// widths must be known before the first line is written, the children of
+/// every node are requested twice.
+pub struct LazyColumnRender<I, T> {
+    /// Root tree node to render.
+    pub tree: T,
 
-        let subsequent_indent =
-            alloc::format!("{}{}", prefix, indent.get_indent(layer, Line::Other));
+    /// Indentation style used for rendering.
+    pub indent: I,
+}
 
-        let wrap_option = textwrap::Options::new(width)
-            .initial_indent(&initial_indent)
-            .subsequent_indent(&subsequent_indent);
+impl<I, T> Display for LazyColumnRender<I, T>
+where
+    I: IIndent,
+    T: ILazyTree<Leaf = T> + ITreeColumns,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        render_columns::<I, LazyNode<T>>(f, &self.tree, &self.indent, || {
+            LazyNode::leaves_of(&self.tree)
+        })
+    }
+}
 
-        writeln!(
-            f,
-            "{}",
-            textwrap::fill(node.content().as_ref(), &wrap_option)
-        )?;
+/// Renderer for directed acyclic graphs exposed through [`IOwnedTree`].
+///
+/// Nodes are identified by the `key` function. The first occurrence of a key
+/// is rendered with its whole subtree; later occurrences are rendered as a
+/// single `(see above: <key>)` back-reference. A node whose key is already on
+/// the path from the root is rendered as `<key> (cycle)` and not descended
+/// into, so rendering terminates even if the input is not acyclic.
+///
+/// Traversal is depth-first, so a back-reference always points to a line
+/// printed earlier.
+pub struct OwnedDagRender<'a, I, T, F> {
+    /// Root tree node to render.
+    pub tree: &'a T,
+
+    /// Indentation style used for all nodes.
+    pub indent: I,
+
+    /// Function returning the identity of a node.
+    pub key: F,
+
+    /// Optional wrapping width used when the `textwrap` feature is enabled.
+    #[cfg(feature = "textwrap")]
+    pub width: usize,
+}
+
+impl<I, T, F, K> Display for OwnedDagRender<'_, I, T, F>
+where
+    I: IIndent,
+    T: IOwnedTree<Leaf = T>,
+    F: Fn(&T) -> K,
+    K: Ord + Clone + Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        render_dag::<I, OwnedNode<'_, T>, F, K>(
+            f,
+            self.tree,
+            (self.key)(self.tree),
+            OwnedNode::leaves_of(self.tree),
+            &self.key,
+            &self.indent,
+            #[cfg(feature = "textwrap")]
+            self.width,
+        )
+    }
+}
+
+/// Renderer for directed acyclic graphs exposed through [`ILazyTree`].
+///
+/// This is the lazy counterpart of [`OwnedDagRender`]: children are produced
+/// on demand, and the subtree of a repeated or cyclic node is never requested.
+pub struct LazyDagRender<I, T, F> {
+    /// Root tree node to render.
+    pub tree: T,
+
+    /// Indentation style used for rendering.
+    pub indent: I,
+
+    /// Function returning the identity of a node.
+    pub key: F,
+
+    /// Optional wrapping width used when the `textwrap` feature is enabled.
+    #[cfg(feature = "textwrap")]
+    pub width: usize,
+}
+
+impl<I, T, F, K> Display for LazyDagRender<I, T, F>
+where
+    I: IIndent,
+    T: ILazyTree<Leaf = T>,
+    F: Fn(&T) -> K,
+    K: Ord + Clone + Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        render_dag::<I, LazyNode<T>, F, K>(
+            f,
+            &self.tree,
+            (self.key)(&self.tree),
+            LazyNode::leaves_of(&self.tree),
+            &self.key,
+            &self.indent,
+            #[cfg(feature = "textwrap")]
+            self.width,
+        )
+    }
+}
+
+/// Node abstraction shared by the renderers.
+///
+/// It hides whether children are yielded by reference or by value, and which
+/// indentation style the children of a node are rendered with.
+pub(crate) trait IRenderNode<'i, I>: ITreeContent + Sized {
+    /// The wrapped tree type.
+    type Tree;
+
+    /// Iterator over the children of this node.
+    type Leaves: Iterator<Item = Self>;
+
+    /// Returns the wrapped tree node.
+    fn tree(&self) -> &Self::Tree;
+
+    /// Returns the children of this node.
+    fn leaves(&self) -> Self::Leaves;
+
+    /// Returns the indentation style of the children, given the one this
+    /// node is rendered with.
+    fn leaves_indent(&self, indent: &'i I) -> &'i I;
+}
+
+pub(crate) struct OwnedNode<'a, T>(&'a T);
+
+impl<'a, T: IOwnedTree<Leaf = T>> OwnedNode<'a, T> {
+    pub(crate) fn leaves_of(tree: &'a T) -> Map<T::Leaves<'a>, fn(&'a T) -> Self> {
+        tree.leaves().map(OwnedNode as fn(&'a T) -> Self)
+    }
+}
+
+impl<T: ITreeContent> ITreeContent for OwnedNode<'_, T> {
+    fn content(&self) -> impl AsRef<str> { self.0.content() }
+}
+
+impl<T: IStyledContent> IStyledContent for OwnedNode<'_, T> {
+    fn spans(&self) -> impl IntoIterator<Item = Span<'_>> { self.0.spans() }
+}
+
+impl<T: ITreeColumns> ITreeColumns for OwnedNode<'_, T> {
+    fn columns(&self) -> impl IntoIterator<Item = impl AsRef<str>> { self.0.columns() }
+}
+
+impl<'a, 'i, I, T: IOwnedTree<Leaf = T>> IRenderNode<'i, I> for OwnedNode<'a, T> {
+    type Leaves = Map<T::Leaves<'a>, fn(&'a T) -> Self>;
+    type Tree = T;
+
+    fn tree(&self) -> &T { self.0 }
+
+    fn leaves(&self) -> Self::Leaves { Self::leaves_of(self.0) }
+
+    fn leaves_indent(&self, indent: &'i I) -> &'i I { indent }
+}
+
+struct StyledNode<'a, T, I> {
+    tree: &'a T,
+    indent: &'a I,
+}
+
+/// Children of a styled node, paired with the indentation style they inherit.
+struct StyledLeaves<'a, T: IOwnedTree + 'a, I> {
+    leaves: T::Leaves<'a>,
+    indent: &'a I,
+}
+
+impl<'a, T, I> Iterator for StyledLeaves<'a, T, I>
+where
+    T: IOwnedTree<Leaf = T> + 'a,
+{
+    type Item = StyledNode<'a, T, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.leaves.next().map(|tree| StyledNode {
+            tree,
+            indent: self.indent,
+        })
+    }
+}
+
+impl<T: ITreeContent, I> ITreeContent for StyledNode<'_, T, I> {
+    fn content(&self) -> impl AsRef<str> { self.tree.content() }
+}
+
+impl<'a, I, T> IRenderNode<'a, I> for StyledNode<'a, T, I>
+where
+    I: IIndent,
+    T: IStyledOwnedTree<Indent = I, Leaf = T>,
+{
+    type Leaves = StyledLeaves<'a, T, I>;
+    type Tree = T;
+
+    fn tree(&self) -> &T { self.tree }
+
+    fn leaves(&self) -> Self::Leaves {
+        StyledLeaves {
+            leaves: self.tree.leaves(),
+            indent: self.leaves_indent(self.indent),
+        }
+    }
+
+    fn leaves_indent(&self, _indent: &'a I) -> &'a I {
+        self.tree.indent().as_ref().unwrap_or(self.indent)
+    }
+}
+
+pub(crate) struct LazyNode<T>(T);
+
+impl<T: ILazyTree<Leaf = T>> LazyNode<T> {
+    pub(crate) fn leaves_of(tree: &T) -> Map<T::Leaves, fn(T) -> Self> {
+        tree.leaves().map(LazyNode as fn(T) -> Self)
+    }
+}
+
+impl<T: ITreeContent> ITreeContent for LazyNode<T> {
+    fn content(&self) -> impl AsRef<str> { self.0.content() }
+}
+
+impl<T: IStyledContent> IStyledContent for LazyNode<T> {
+    fn spans(&self) -> impl IntoIterator<Item = Span<'_>> { self.0.spans() }
+}
+
+impl<T: ITreeColumns> ITreeColumns for LazyNode<T> {
+    fn columns(&self) -> impl IntoIterator<Item = impl AsRef<str>> { self.0.columns() }
+}
+
+impl<'i, I, T: ILazyTree<Leaf = T>> IRenderNode<'i, I> for LazyNode<T> {
+    type Leaves = Map<T::Leaves, fn(T) -> Self>;
+    type Tree = T;
+
+    fn tree(&self) -> &T { &self.0 }
+
+    fn leaves(&self) -> Self::Leaves { Self::leaves_of(&self.0) }
+
+    fn leaves_indent(&self, indent: &'i I) -> &'i I { indent }
+}
+
+/// An item yielded by [`Frame::next`].
+enum Entry<N> {
+    /// A child together with its [`Layer`].
+    Leaf(N, Layer),
+
+    /// The number of children left out by [`RenderOptions::max_children`].
+    More(usize),
+}
+
+/// The remaining children of a node on the current path.
+struct Frame<'i, It: Iterator, I> {
+    leaves: It,
+
+    /// Children taken from `leaves` but not yielded yet, at most two.
+    peeked: Vec<It::Item>,
+
+    /// Number of children yielded so far.
+    index: usize,
+
+    /// Depth of the children, the root has depth `0`.
+    depth: usize,
+
+    /// Indentation style the children are rendered with.
+    indent: &'i I,
+
+    /// Length of the prefix written before the children.
+    prefix_len: usize,
+}
+
+impl<'i, It: Iterator, I> Frame<'i, It, I> {
+    const fn new(leaves: It, depth: usize, indent: &'i I) -> Self {
+        Self {
+            leaves,
+            peeked: Vec::new(),
+            index: 0,
+            depth,
+            indent,
+            prefix_len: 0,
+        }
+    }
+
+    /// Buffers children until `count` of them are peeked or none is left.
+    fn fill(&mut self, count: usize) {
+        while self.peeked.len() < count {
+            match self.leaves.next() {
+                Some(leaf) => self.peeked.push(leaf),
+                None => break,
+            }
+        }
+    }
+
+    fn is_empty(&mut self) -> bool {
+        self.fill(1);
+        self.peeked.is_empty()
+    }
+
+    /// Takes the only child, if there is exactly one.
+    fn single(&mut self) -> Option<It::Item> {
+        self.fill(2);
+        if self.peeked.len() == 1 {
+            self.peeked.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the next child together with its [`Layer`].
+    ///
+    /// The last child is the bottom branch, the first of several is the top
+    /// branch and the others are middle branches. Once `max_children`
+    /// children have been yielded, the remaining ones are counted and
+    /// reported as [`Entry::More`], which is rendered as the bottom branch.
+    fn next(&mut self, max_children: Option<usize>) -> Option<Entry<It::Item>> {
+        if max_children == Some(self.index) {
+            self.index += 1;
+            let more = self.peeked.len() + self.leaves.by_ref().count();
+            self.peeked.clear();
+            return (more > 0).then_some(Entry::More(more));
+        }
+
+        let leaf = if self.peeked.is_empty() {
+            self.leaves.next()?
+        } else {
+            self.peeked.remove(0)
+        };
+        let layer = if self.is_empty() {
+            Layer::Bottom
+        } else if self.index == 0 {
+            Layer::Top
+        } else {
+            Layer::Middle
+        };
+        self.index += 1;
+        Some(Entry::Leaf(leaf, layer))
+    }
+}
+
+/// Options limiting and compacting what [`OwnedRender`],
+/// [`StyledOwnedRender`], [`LazyRender`], [`OwnedSpanRender`] and
+/// [`LazySpanRender`] print, applied with their `with_options` method.
+///
+/// The default renders the whole tree.
+///
+/// ```
+/// use arbor::indents::AsciiIndent;
+/// use arbor::renders::{OwnedRender, RenderOptions};
+/// use arbor::trees::OwnedTree;
+/// let tree = OwnedTree::new("foo").with_leaves(["bar", "baz"]);
+/// let render = OwnedRender {
+///     tree: &tree,
+///     indent: AsciiIndent,
+///     width: 0,
+/// }
+/// .with_options(RenderOptions {
+///     max_depth: Some(0),
+///     ..Default::default()
+/// });
+/// assert_eq!(render.to_string(), "foo\n`-- …\n");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Maximum number of children rendered per node, `None` renders all of
+    /// them.
+    ///
+    /// The omitted children are summarized by a `… and N more` line. Lazy
+    /// children beyond the limit are generated to be counted, but never
+    /// descended into.
+    pub max_children: Option<usize>,
+
+    /// Maximum depth rendered, `None` renders all levels. The root has depth
+    /// `0`, so `Some(0)` renders the root only.
+    ///
+    /// The children of a node at the limit are replaced by a single `…`
+    /// line. Every node merged by `collapse_chains` counts as a level.
+    pub max_depth: Option<usize>,
+
+    /// Whether a chain of nodes with a single child is rendered as one
+    /// `a/b/c` line.
+    pub collapse_chains: bool,
+}
+
+impl RenderOptions {
+    /// Whether nodes at `depth` are rendered.
+    fn within_depth(&self, depth: usize) -> bool { self.max_depth.is_none_or(|max| depth <= max) }
+}
+
+/// A renderer together with the [`RenderOptions`] it renders with.
+///
+/// Returned by the `with_options` method of the renderers.
+pub struct WithOptions<R> {
+    /// Renderer of the tree.
+    pub render: R,
+
+    /// Limits and compaction applied while rendering.
+    pub options: RenderOptions,
+}
+
+/// Merges the chain of single children at the top of `frame` into
+/// `content`, if [`RenderOptions::collapse_chains`] is set.
+///
+/// On return `frame` holds the children of the last node of the chain.
+/// Returns the merged content and that node, or `None` if the frame does not
+/// hold exactly one child. Nodes deeper than [`RenderOptions::max_depth`] are
+/// not merged, so an endless lazy chain stops at the depth limit.
+fn collapse_chain<'i, I, N>(
+    frame: &mut Frame<'i, N::Leaves, I>,
+    content: &str,
+    options: &RenderOptions,
+) -> Option<(String, N)>
+where
+    N: IRenderNode<'i, I>,
+{
+    let within_depth = |frame: &Frame<'i, N::Leaves, I>| options.within_depth(frame.depth);
+    if !options.collapse_chains || !within_depth(frame) {
+        return None;
+    }
+    let mut last = frame.single()?;
+    let mut merged = String::from(content);
+    loop {
+        merged.push('/');
+        merged.push_str(last.content().as_ref());
+        let indent = last.leaves_indent(frame.indent);
+        *frame = Frame::new(last.leaves(), frame.depth + 1, indent);
+        if !within_depth(frame) {
+            return Some((merged, last));
+        }
+        match frame.single() {
+            Some(leaf) => last = leaf,
+            None => return Some((merged, last)),
+        }
+    }
+}
+
+/// Visits the descendants of a node depth-first.
+///
+/// `visit` receives each line to write: the node it belongs to (`None` for
+/// the markers inserted by `options`), its content, its [`Layer`], the prefix
+/// written before it and the indentation style it is rendered with.
+///
+/// The stack holds one [`Frame`] per ancestor of the current node and the
+/// prefix is a single buffer truncated when a frame is left, so memory is
+/// proportional to the depth of the tree rather than its width.
+fn walk<'i, I, N>(
+    mut root: Frame<'i, N::Leaves, I>,
+    options: &RenderOptions,
+    mut visit: impl FnMut(Option<&N>, &str, Layer, &str, &I) -> fmt::Result,
+) -> fmt::Result
+where
+    I: IIndent,
+    N: IRenderNode<'i, I>,
+{
+    let mut prefix = String::new();
+    if !options.within_depth(root.depth) {
+        return if root.is_empty() {
+            Ok(())
+        } else {
+            visit(None, "…", Layer::Bottom, &prefix, root.indent)
+        };
+    }
+    let mut stack = Vec::from([root]);
+
+    while let Some(frame) = stack.last_mut() {
+        let (indent, depth) = (frame.indent, frame.depth);
+        let (leaf, layer) = match frame.next(options.max_children) {
+            Some(Entry::Leaf(leaf, layer)) => (leaf, layer),
+            Some(Entry::More(more)) => {
+                let marker = alloc::format!("… and {more} more");
+                visit(None, &marker, Layer::Bottom, &prefix, indent)?;
+                continue;
+            }
+            None => {
+                stack.pop();
+                if let Some(parent) = stack.last() {
+                    prefix.truncate(parent.prefix_len);
+                }
+                continue;
+            }
+        };
+
+        let mut leaves = Frame::new(leaf.leaves(), depth + 1, leaf.leaves_indent(indent));
+        let collapsed = collapse_chain(&mut leaves, leaf.content().as_ref(), options);
+        match &collapsed {
+            Some((content, last)) => visit(Some(last), content, layer, &prefix, indent)?,
+            None => visit(Some(&leaf), leaf.content().as_ref(), layer, &prefix, indent)?,
+        }
+
+        if leaves.is_empty() {
+            continue;
+        }
+        let prefix_len = prefix.len();
+        prefix.push_str(indent.get_indent(layer, Line::Other));
+        // A collapsed chain ends deeper than `leaf`.
+        if !options.within_depth(leaves.depth) {
+            visit(None, "…", Layer::Bottom, &prefix, leaves.indent)?;
+            prefix.truncate(prefix_len);
+            continue;
+        }
+        leaves.prefix_len = prefix.len();
+        stack.push(leaves);
+    }
+
+    Ok(())
+}
+
+/// Renders a tree depth-first, writing each line as its node is visited.
+fn render_tree<'i, I, N>(
+    f: &mut fmt::Formatter<'_>,
+    root: &impl ITreeContent,
+    indent: &'i I,
+    leaves: N::Leaves,
+    options: &RenderOptions,
+    #[cfg(feature = "textwrap")] width: usize,
+) -> fmt::Result
+where
+    I: IIndent,
+    N: IRenderNode<'i, I>,
+{
+    let content = root.content();
+    let mut frame = Frame::new(leaves, 1, indent);
+    let collapsed = collapse_chain::<I, N>(&mut frame, content.as_ref(), options);
+    render_content(
+        f,
+        collapsed
+            .as_ref()
+            .map_or(content.as_ref(), |(content, _)| content.as_str()),
+        Layer::Root,
+        "",
+        indent,
+        #[cfg(feature = "textwrap")]
+        width,
+    )?;
+
+    walk::<I, N>(frame, options, |_, content, layer, prefix, indent| {
+        render_content(
+            f,
+            content,
+            layer,
+            prefix,
+            indent,
+            #[cfg(feature = "textwrap")]
+            width,
+        )
+    })
+}
+
+/// Renders a tree of styled nodes depth-first.
+fn render_span_tree<'i, I, N>(
+    f: &mut fmt::Formatter<'_>,
+    root: &impl IStyledContent,
+    indent: &'i I,
+    leaves: N::Leaves,
+    options: &RenderOptions,
+    color: bool,
+    #[cfg(feature = "textwrap")] width: usize,
+) -> fmt::Result
+where
+    I: IIndent,
+    N: IRenderNode<'i, I> + IStyledContent,
+{
+    let content = root.content();
+    let mut frame = Frame::new(leaves, 1, indent);
+    let collapsed = collapse_chain::<I, N>(&mut frame, content.as_ref(), options);
+    let text = match &collapsed {
+        Some((content, last)) => StyledText::of(content, last),
+        None => StyledText::new("", root.spans()),
+    };
+    render_styled_content(
+        f,
+        &text,
+        Layer::Root,
+        "",
+        indent,
+        color,
+        #[cfg(feature = "textwrap")]
+        width,
+    )?;
+
+    walk::<I, N>(frame, options, |leaf, content, layer, prefix, indent| {
+        let text = match leaf {
+            Some(leaf) => StyledText::of(content, leaf),
+            None => StyledText::new(content, []),
+        };
+        render_styled_content(
+            f,
+            &text,
+            layer,
+            prefix,
+            indent,
+            color,
+            #[cfg(feature = "textwrap")]
+            width,
+        )
+    })
+}
+
+/// Renders a tree with a gutter column written before the tree prefix of
+/// every line, such as the change markers of a diff.
+pub(crate) fn render_gutter_tree<I, T>(
+    f: &mut fmt::Formatter<'_>,
+    root: &T,
+    indent: &I,
+    gutter: impl Fn(&T) -> &'static str,
+    #[cfg(feature = "textwrap")] width: usize,
+) -> fmt::Result
+where
+    I: IIndent,
+    T: IOwnedTree<Leaf = T>,
+{
+    render_content(
+        f,
+        root.content().as_ref(),
+        Layer::Root,
+        gutter(root),
+        indent,
+        #[cfg(feature = "textwrap")]
+        width,
+    )?;
+
+    let mut line_prefix = String::new();
+    walk::<I, OwnedNode<'_, T>>(
+        Frame::new(OwnedNode::leaves_of(root), 1, indent),
+        &RenderOptions::default(),
+        |leaf, content, layer, prefix, indent| {
+            line_prefix.clear();
+            line_prefix.push_str(leaf.map_or("", |leaf| gutter(leaf.0)));
+            line_prefix.push_str(prefix);
+            render_content(
+                f,
+                content,
+                layer,
+                &line_prefix,
+                indent,
+                #[cfg(feature = "textwrap")]
+                width,
+            )
+        },
+    )
+}
+
+/// Renders a tree with its cells aligned in columns.
+///
+/// The first pass records the widest tree part and the widest cell of each
+/// column, the second pass writes the padded rows.
+fn render_columns<'i, I, N>(
+    f: &mut fmt::Formatter<'_>,
+    root: &impl ITreeColumns,
+    indent: &'i I,
+    leaves: impl Fn() -> N::Leaves,
+) -> fmt::Result
+where
+    I: IIndent,
+    N: IRenderNode<'i, I> + ITreeColumns,
+{
+    let options = RenderOptions::default();
+    let mut widths = Vec::new();
+    measure_row(&mut widths, root, Layer::Root, "", indent);
+    walk::<I, N>(
+        Frame::new(leaves(), 1, indent),
+        &options,
+        |leaf, _, layer, prefix, indent| {
+            if let Some(leaf) = leaf {
+                measure_row(&mut widths, leaf, layer, prefix, indent);
+            }
+            Ok(())
+        },
+    )?;
+
+    render_row(f, &widths, root, Layer::Root, "", indent)?;
+    walk::<I, N>(
+        Frame::new(leaves(), 1, indent),
+        &options,
+        |leaf, content, layer, prefix, indent| match leaf {
+            Some(leaf) => render_row(f, &widths, leaf, layer, prefix, indent),
+            None => render_content_no_wrap(f, content, layer, prefix, indent),
+        },
+    )
+}
+
+/// Widens `widths` to fit the first line of `node` and its cells.
+///
+/// `widths[0]` is the width of the tree part, the others are the widths of
+/// the cell columns.
+fn measure_row<I, T>(widths: &mut Vec<usize>, node: &T, layer: Layer, prefix: &str, indent: &I)
+where
+    I: IIndent,
+    T: ITreeColumns,
+{
+    let content = node.content();
+    let first_line = content.as_ref().lines().next().unwrap_or_default();
+    let cells = node
+        .columns()
+        .into_iter()
+        .map(|cell| display_width(cell.as_ref()));
+    let row = core::iter::once(
+        display_width(prefix)
+            + display_width(indent.get_indent(layer, Line::First))
+            + display_width(first_line),
+    )
+    .chain(cells);
+
+    for (column, width) in row.enumerate() {
+        match widths.get_mut(column) {
+            Some(max) => *max = (*max).max(width),
+            None => widths.push(width),
+        }
+    }
+}
+
+/// Writes a node followed by its cells padded to the column widths.
+///
+/// Trailing empty cells are omitted so lines carry no trailing whitespace.
+fn render_row<I, T>(
+    f: &mut fmt::Formatter<'_>,
+    widths: &[usize],
+    node: &T,
+    layer: Layer,
+    prefix: &str,
+    indent: &I,
+) -> fmt::Result
+where
+    I: IIndent,
+    T: ITreeColumns,
+{
+    let content = node.content();
+    let mut lines = content.as_ref().lines();
+    let first_line = lines.next().unwrap_or_default();
+    let marker = indent.get_indent(layer, Line::First);
+    write!(f, "{prefix}{marker}{first_line}")?;
+
+    let cells: Vec<_> = node.columns().into_iter().collect();
+    let last = cells.iter().rposition(|cell| !cell.as_ref().is_empty());
+    let mut written = display_width(prefix) + display_width(marker) + display_width(first_line);
+    for (column, cell) in cells
+        .iter()
+        .take(last.map_or(0, |last| last + 1))
+        .enumerate()
+    {
+        let padding = widths[column].saturating_sub(written);
+        write!(f, "{:padding$}{COLUMN_GAP}{}", "", cell.as_ref())?;
+        written = display_width(cell.as_ref());
+    }
+    writeln!(f)?;
+
+    for text in lines {
+        writeln!(f, "{prefix}{}{text}", indent.get_indent(layer, Line::Other))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the number of terminal cells taken by `text`.
+///
+/// With the `textwrap` feature the width is Unicode aware and ignores ANSI
+/// escape sequences, otherwise every `char` counts as one cell.
+pub(crate) fn display_width(text: &str) -> usize {
+    #[cfg(feature = "textwrap")]
+    return textwrap::core::display_width(text);
+    #[cfg(not(feature = "textwrap"))]
+    return text.chars().count();
+}
+
+/// Renders a graph depth-first, printing every key only once.
+///
+/// The stack of frames holds exactly the ancestors of the node being
+/// visited, which is what cycle detection checks against.
+fn render_dag<'i, I, N, F, K>(
+    f: &mut fmt::Formatter<'_>,
+    root: &impl ITreeContent,
+    root_key: K,
+    leaves: N::Leaves,
+    key: &F,
+    indent: &'i I,
+    #[cfg(feature = "textwrap")] width: usize,
+) -> fmt::Result
+where
+    I: IIndent,
+    N: IRenderNode<'i, I>,
+    F: Fn(&N::Tree) -> K,
+    K: Ord + Clone + Display,
+{
+    render_content(
+        f,
+        root.content().as_ref(),
+        Layer::Root,
+        "",
+        indent,
+        #[cfg(feature = "textwrap")]
+        width,
+    )?;
+
+    let mut seen = BTreeSet::new();
+    seen.insert(root_key.clone());
+    let mut prefix = String::new();
+    let mut stack = Vec::from([(Frame::new(leaves, 1, indent), root_key)]);
+
+    while let Some((frame, _)) = stack.last_mut() {
+        let depth = frame.depth;
+        let Some(Entry::Leaf(leaf, layer)) = frame.next(None) else {
+            stack.pop();
+            if let Some((parent, _)) = stack.last() {
+                prefix.truncate(parent.prefix_len);
+            }
+            continue;
+        };
+        let leaf_key = key(leaf.tree());
+
+        let marker = if stack.iter().any(|(_, key)| *key == leaf_key) {
+            Some(alloc::format!("{leaf_key} (cycle)"))
+        } else if seen.contains(&leaf_key) {
+            Some(alloc::format!("(see above: {leaf_key})"))
+        } else {
+            None
+        };
+        if let Some(marker) = marker {
+            render_content(
+                f,
+                &marker,
+                layer,
+                &prefix,
+                indent,
+                #[cfg(feature = "textwrap")]
+                width,
+            )?;
+            continue;
+        }
+
+        render_content(
+            f,
+            leaf.content().as_ref(),
+            layer,
+            &prefix,
+            indent,
+            #[cfg(feature = "textwrap")]
+            width,
+        )?;
+        seen.insert(leaf_key.clone());
+
+        let mut leaves = Frame::new(leaf.leaves(), depth + 1, indent);
+        if !leaves.is_empty() {
+            prefix.push_str(indent.get_indent(layer, Line::Other));
+            leaves.prefix_len = prefix.len();
+            stack.push((leaves, leaf_key));
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the textual content of a node without line wrapping.
+///
+/// The node content may contain multiple lines. Each line is rendered with
+/// the appropriate prefix and indentation marker depending on whether it
+/// is the first line or a continuation line.
+///
+/// The `Layer` determines which branch marker is emitted by the indentation
+/// style implementation.
+fn render_content_no_wrap<I>(
+    f: &mut fmt::Formatter<'_>,
+    content: &str,
+    layer: Layer,
+    prefix: &str,
+    indent: &I,
+) -> fmt::Result
+where
+    I: IIndent,
+{
+    for (line_index, text) in content.lines().enumerate() {
+        f.write_str(prefix)?;
+
+        f.write_str(indent.get_indent(
+            layer,
+            if line_index == 0 {
+                Line::First
+            } else {
+                Line::Other
+            },
+        ))?;
+
+        f.write_str(text)?;
+
+        writeln!(f)?;
+    }
+
+    Ok(())
+}
+
+/// Renders the textual content of a node with optional line wrapping.
+///
+/// When the `textwrap` feature is disabled, this function delegates to
+/// [`render_content_no_wrap`].
+///
+/// When enabled, the content is wrapped to the configured width while
+/// preserving the indentation prefixes used for the first and subsequent
+/// lines.
+fn render_content<I>(
+    f: &mut fmt::Formatter<'_>,
+    content: &str,
+    layer: Layer,
+    prefix: &str,
+    indent: &I,
+    #[cfg(feature = "textwrap")] width: usize,
+) -> fmt::Result
+where
+    I: IIndent,
+{
+    #[cfg(not(feature = "textwrap"))]
+    render_content_no_wrap(f, content, layer, prefix, indent)?;
+
+    #[cfg(feature = "textwrap")]
+    if width == 0 {
+        render_content_no_wrap(f, content, layer, prefix, indent)?;
+    } else {
+        let initial_indent = alloc::format!("{}{}", prefix, indent.get_indent(layer, Line::First));
+
+        let subsequent_indent =
+            alloc::format!("{}{}", prefix, indent.get_indent(layer, Line::Other));
+
+        let wrap_option = textwrap::Options::new(width)
+            .initial_indent(&initial_indent)
+            .subsequent_indent(&subsequent_indent);
+
+        writeln!(f, "{}", textwrap::fill(content, &wrap_option))?;
+    }
+
+    Ok(())
+}
+
+/// Renders styled content with optional line wrapping.
+///
+/// Lines are produced from the plain text exactly like [`render_content`]
+/// does, then written with the styles of the spans they come from.
+fn render_styled_content<I>(
+    f: &mut fmt::Formatter<'_>,
+    text: &StyledText<'_>,
+    layer: Layer,
+    prefix: &str,
+    indent: &I,
+    color: bool,
+    #[cfg(feature = "textwrap")] width: usize,
+) -> fmt::Result
+where
+    I: IIndent,
+{
+    let mut cursor = 0;
+
+    #[cfg(feature = "textwrap")]
+    if width != 0 {
+        let initial_indent = alloc::format!("{}{}", prefix, indent.get_indent(layer, Line::First));
+
+        let subsequent_indent =
+            alloc::format!("{}{}", prefix, indent.get_indent(layer, Line::Other));
+
+        let wrap_option = textwrap::Options::new(width)
+            .initial_indent(&initial_indent)
+            .subsequent_indent(&subsequent_indent);
+
+        for (line_index, line) in textwrap::wrap(text.plain(), &wrap_option)
+            .iter()
+            .enumerate()
+        {
+            let line_indent = if line_index == 0 {
+                &initial_indent
+            } else {
+                &subsequent_indent
+            };
+            match line.strip_prefix(line_indent.as_str()) {
+                Some(line) => {
+                    f.write_str(line_indent)?;
+                    text.write_line(f, line, &mut cursor, color)?;
+                }
+                None => f.write_str(line)?,
+            }
+            writeln!(f)?;
+        }
+        return Ok(());
+    }
+
+    for (line_index, line) in text.plain().lines().enumerate() {
+        f.write_str(prefix)?;
+
+        f.write_str(indent.get_indent(
+            layer,
+            if line_index == 0 {
+                Line::First
+            } else {
+                Line::Other
+            },
+        ))?;
+
+        text.write_line(f, line, &mut cursor, color)?;
+
+        writeln!(f)?;
     }
 
     Ok(())
@@ -0,0 +1,292 @@
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::protocol::IStyledContent;
+
+/// A terminal color.
+///
+/// The named colors map to the 16 standard ANSI colors, whose exact shade is
+/// chosen by the terminal theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+
+    /// A color of the 256-color palette.
+    Fixed(u8),
+
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Writes the SGR parameters selecting this color, for the foreground or
+    /// the background.
+    fn write_sgr(self, f: &mut fmt::Formatter<'_>, background: bool) -> fmt::Result {
+        let (base, bright, extended) = if background {
+            (40, 100, 48)
+        } else {
+            (30, 90, 38)
+        };
+        let named = |index: u8| match index {
+            0..8 => base + index,
+            _ => bright + index - 8,
+        };
+        match self {
+            Self::Black => write!(f, "{}", named(0)),
+            Self::Red => write!(f, "{}", named(1)),
+            Self::Green => write!(f, "{}", named(2)),
+            Self::Yellow => write!(f, "{}", named(3)),
+            Self::Blue => write!(f, "{}", named(4)),
+            Self::Magenta => write!(f, "{}", named(5)),
+            Self::Cyan => write!(f, "{}", named(6)),
+            Self::White => write!(f, "{}", named(7)),
+            Self::BrightBlack => write!(f, "{}", named(8)),
+            Self::BrightRed => write!(f, "{}", named(9)),
+            Self::BrightGreen => write!(f, "{}", named(10)),
+            Self::BrightYellow => write!(f, "{}", named(11)),
+            Self::BrightBlue => write!(f, "{}", named(12)),
+            Self::BrightMagenta => write!(f, "{}", named(13)),
+            Self::BrightCyan => write!(f, "{}", named(14)),
+            Self::BrightWhite => write!(f, "{}", named(15)),
+            Self::Fixed(index) => write!(f, "{extended};5;{index}"),
+            Self::Rgb(r, g, b) => write!(f, "{extended};2;{r};{g};{b}"),
+        }
+    }
+}
+
+/// Text attributes applied to a [`Span`].
+///
+/// The default style leaves the text unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    /// Foreground color.
+    pub fg: Option<Color>,
+
+    /// Background color.
+    pub bg: Option<Color>,
+
+    /// Whether the text is bold.
+    pub bold: bool,
+
+    /// Whether the text is dimmed.
+    pub dim: bool,
+}
+
+impl Style {
+    /// Returns `true` if the style does not change the text.
+    pub fn is_plain(&self) -> bool { *self == Self::default() }
+
+    /// Writes the SGR escape sequence enabling this style.
+    fn write_start(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\x1b[")?;
+        let mut separator = "";
+        for (enabled, code) in [(self.bold, "1"), (self.dim, "2")] {
+            if enabled {
+                f.write_str(separator)?;
+                f.write_str(code)?;
+                separator = ";";
+            }
+        }
+        for (color, background) in [(self.fg, false), (self.bg, true)] {
+            if let Some(color) = color {
+                f.write_str(separator)?;
+                color.write_sgr(f, background)?;
+                separator = ";";
+            }
+        }
+        f.write_char('m')
+    }
+}
+
+/// A piece of node content sharing one [`Style`] and hyperlink.
+///
+/// # Example
+///
+/// ```
+/// use arbor::style::{Color, Span, Style};
+/// let span = Span::new("Cargo.toml")
+///     .with_style(Style {
+///         fg: Some(Color::Green),
+///         ..Default::default()
+///     })
+///     .with_link("file:///repo/Cargo.toml");
+/// assert_eq!(span.text, "Cargo.toml");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span<'a> {
+    /// The plain text of the span.
+    pub text: Cow<'a, str>,
+
+    /// Attributes the text is rendered with.
+    pub style: Style,
+
+    /// Target of an OSC-8 hyperlink wrapping the text.
+    pub link: Option<Cow<'a, str>>,
+}
+
+impl<'a> Span<'a> {
+    /// Creates an unstyled span.
+    pub fn new(text: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            text: text.into(),
+            style: Style::default(),
+            link: None,
+        }
+    }
+
+    /// Sets the style of the span.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Turns the span into a hyperlink to `link`.
+    pub fn with_link(mut self, link: impl Into<Cow<'a, str>>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+}
+
+impl<'a> From<&'a str> for Span<'a> {
+    fn from(text: &'a str) -> Self { Self::new(text) }
+}
+
+impl From<String> for Span<'_> {
+    fn from(text: String) -> Self { Self::new(text) }
+}
+
+/// The content of a node, flattened to plain text with the byte ranges of
+/// its styled spans.
+///
+/// Wrapping works on the plain text, so widths never include escape
+/// sequences. Styles are applied back when each wrapped line is written.
+pub(crate) struct StyledText<'a> {
+    plain: String,
+
+    /// End offset in `plain`, style and hyperlink of each span.
+    runs: Vec<(usize, Style, Option<Cow<'a, str>>)>,
+}
+
+impl<'a> StyledText<'a> {
+    /// Collects `spans`, preceded by the unstyled `lead`.
+    pub(crate) fn new(lead: &str, spans: impl IntoIterator<Item = Span<'a>>) -> Self {
+        let mut plain = String::from(lead);
+        let mut runs = Vec::from([(plain.len(), Style::default(), None)]);
+        for span in spans {
+            plain.push_str(&span.text);
+            runs.push((plain.len(), span.style, span.link));
+        }
+        Self { plain, runs }
+    }
+
+    /// Collects the spans of `node`, rendered as the last part of `content`.
+    ///
+    /// `content` is longer than the content of `node` when it is the end of
+    /// a collapsed chain, in which case the leading nodes are left unstyled.
+    pub(crate) fn of(content: &str, node: &'a impl IStyledContent) -> Self {
+        let own = node.content().as_ref().len();
+        let lead = content
+            .get(..content.len().saturating_sub(own))
+            .unwrap_or("");
+        Self::new(lead, node.spans())
+    }
+
+    /// Returns the text without styling.
+    pub(crate) fn plain(&self) -> &str { &self.plain }
+
+    /// Writes `line`, a line of the wrapped plain text, with the styles of
+    /// the characters it was taken from.
+    ///
+    /// `cursor` is the offset in the plain text where the line starts
+    /// looking for its characters. Whitespace dropped by the wrapping is
+    /// skipped and characters that are not found, such as hyphens added by
+    /// hyphenation, are written unstyled. Without `color` the line is written
+    /// as is.
+    pub(crate) fn write_line(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        line: &str,
+        cursor: &mut usize,
+        color: bool,
+    ) -> fmt::Result {
+        if !color {
+            return f.write_str(line);
+        }
+
+        let mut open: Option<usize> = None;
+        for c in line.chars() {
+            let run = match self.find(c, cursor) {
+                Some(offset) => self.runs.iter().position(|(end, ..)| offset < *end),
+                None => None,
+            };
+            if open != run {
+                self.write_end(f, open)?;
+                self.write_start(f, run)?;
+                open = run;
+            }
+            f.write_char(c)?;
+        }
+        self.write_end(f, open)
+    }
+
+    /// Advances `cursor` past `c`, skipping whitespace, and returns the
+    /// offset of `c` in the plain text.
+    fn find(&self, c: char, cursor: &mut usize) -> Option<usize> {
+        for (offset, found) in self.plain[*cursor..].char_indices() {
+            if found == c {
+                let offset = *cursor + offset;
+                *cursor = offset + c.len_utf8();
+                return Some(offset);
+            }
+            if !found.is_whitespace() {
+                return None;
+            }
+        }
+        None
+    }
+
+    fn write_start(&self, f: &mut fmt::Formatter<'_>, run: Option<usize>) -> fmt::Result {
+        let Some((_, style, link)) = run.map(|run| &self.runs[run]) else {
+            return Ok(());
+        };
+        if let Some(link) = link {
+            write!(f, "\x1b]8;;{link}\x1b\\")?;
+        }
+        if !style.is_plain() {
+            style.write_start(f)?;
+        }
+        Ok(())
+    }
+
+    fn write_end(&self, f: &mut fmt::Formatter<'_>, run: Option<usize>) -> fmt::Result {
+        let Some((_, style, link)) = run.map(|run| &self.runs[run]) else {
+            return Ok(());
+        };
+        if !style.is_plain() {
+            f.write_str("\x1b[0m")?;
+        }
+        if link.is_some() {
+            f.write_str("\x1b]8;;\x1b\\")?;
+        }
+        Ok(())
+    }
+}
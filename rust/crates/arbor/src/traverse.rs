@@ -0,0 +1,306 @@
+extern crate alloc;
+
+use alloc::collections::vec_deque::VecDeque;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::protocol::{ILazyTree, IOwnedTree, ITreeContent};
+use crate::trees::OwnedTree;
+
+/// A node yielded by a traversal, with its position in the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Visit<N> {
+    /// The visited node.
+    pub node: N,
+
+    /// Distance from the root, which has depth `0`.
+    pub depth: usize,
+
+    /// Child indices leading from the root to this node.
+    ///
+    /// The root has an empty path, its second child has the path `[1]`.
+    pub path: Vec<usize>,
+}
+
+impl<N> Visit<N> {
+    const fn root(node: N) -> Self {
+        Self {
+            node,
+            depth: 0,
+            path: Vec::new(),
+        }
+    }
+}
+
+/// Returns the children of `visit`, paired with their own [`Visit`].
+fn visit_leaves<N>(visit: &Visit<N>, leaves: fn(&N) -> Vec<N>) -> Vec<Visit<N>> {
+    leaves(&visit.node)
+        .into_iter()
+        .enumerate()
+        .map(|(index, node)| {
+            let mut path = visit.path.clone();
+            path.push(index);
+            Visit {
+                node,
+                depth: visit.depth + 1,
+                path,
+            }
+        })
+        .collect()
+}
+
+/// Pre-order (parent before children) depth-first traversal.
+///
+/// Created by [`IOwnedTreeExt::pre_order`] or [`ILazyTreeExt::pre_order`].
+pub struct PreOrder<N> {
+    stack: Vec<Visit<N>>,
+    leaves: fn(&N) -> Vec<N>,
+}
+
+impl<N> Iterator for PreOrder<N> {
+    type Item = Visit<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visit = self.stack.pop()?;
+        self.stack
+            .extend(visit_leaves(&visit, self.leaves).into_iter().rev());
+        Some(visit)
+    }
+}
+
+/// Post-order (children before parent) depth-first traversal.
+///
+/// Created by [`IOwnedTreeExt::post_order`] or [`ILazyTreeExt::post_order`].
+pub struct PostOrder<N> {
+    /// Pending visits, flagged once their children have been pushed.
+    stack: Vec<(Visit<N>, bool)>,
+    leaves: fn(&N) -> Vec<N>,
+    /// Nodes deeper than this are not expanded.
+    max_depth: usize,
+}
+
+impl<N> PostOrder<N> {
+    fn new(root: N, leaves: fn(&N) -> Vec<N>, max_depth: usize) -> Self {
+        Self {
+            stack: alloc::vec![(Visit::root(root), false)],
+            leaves,
+            max_depth,
+        }
+    }
+}
+
+impl<N> Iterator for PostOrder<N> {
+    type Item = Visit<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (visit, expanded) = self.stack.pop()?;
+            if expanded || visit.depth >= self.max_depth {
+                return Some(visit);
+            }
+            let leaves = visit_leaves(&visit, self.leaves);
+            self.stack.push((visit, true));
+            self.stack
+                .extend(leaves.into_iter().rev().map(|leaf| (leaf, false)));
+        }
+    }
+}
+
+/// Level-order (breadth-first) traversal.
+///
+/// Created by [`IOwnedTreeExt::level_order`] or [`ILazyTreeExt::level_order`].
+pub struct LevelOrder<N> {
+    queue: VecDeque<Visit<N>>,
+    leaves: fn(&N) -> Vec<N>,
+}
+
+impl<N> Iterator for LevelOrder<N> {
+    type Item = Visit<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visit = self.queue.pop_front()?;
+        self.queue.extend(visit_leaves(&visit, self.leaves));
+        Some(visit)
+    }
+}
+
+/// Rebuilds an [`OwnedTree`] bottom-up from a post-order traversal.
+///
+/// `build` receives each node with the already built children it kept and
+/// returns the new node, or `None` to drop it.
+fn rebuild<N, D>(
+    post_order: PostOrder<N>,
+    mut build: impl FnMut(&N, Vec<OwnedTree<D>>) -> Option<OwnedTree<D>>,
+) -> Option<OwnedTree<D>>
+where
+    D: AsRef<str>,
+{
+    let mut built: Vec<(usize, OwnedTree<D>)> = Vec::new();
+    for visit in post_order {
+        // In post-order the kept children of a node are the deeper entries on
+        // top of the stack.
+        let first = built
+            .iter()
+            .rposition(|(depth, _)| *depth <= visit.depth)
+            .map_or(0, |i| i + 1);
+        let leaves = built.drain(first..).map(|(_, leaf)| leaf).collect();
+        if let Some(tree) = build(&visit.node, leaves) {
+            built.push((visit.depth, tree));
+        }
+    }
+    built.pop().map(|(_, tree)| tree)
+}
+
+/// Returns the nodes from the root to the first node matching `predicate`
+/// in pre-order.
+fn find_path<N>(pre_order: PreOrder<N>, mut predicate: impl FnMut(&N) -> bool) -> Option<Vec<N>> {
+    let mut path = Vec::new();
+    for visit in pre_order {
+        path.truncate(visit.depth);
+        path.push(visit.node);
+        if path.last().is_some_and(&mut predicate) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+fn content_string(node: &impl ITreeContent) -> String { node.content().as_ref().to_string() }
+
+fn owned_leaves<'a, T: IOwnedTree<Leaf = T>>(node: &&'a T) -> Vec<&'a T> { node.leaves().collect() }
+
+fn lazy_leaves<T: ILazyTree<Leaf = T>>(node: &T) -> Vec<T> { node.leaves().collect() }
+
+/// Traversal and transformation utilities for [`IOwnedTree`].
+///
+/// Implemented for every tree whose children share its type. Traversals
+/// borrow the tree and yield references to its nodes.
+///
+/// # Example
+///
+/// ```
+/// use arbor::traverse::IOwnedTreeExt;
+/// use arbor::trees::OwnedTree;
+/// let tree = OwnedTree::new("foo").with_leaves([OwnedTree::new("bar").with_leaves(["baz"])]);
+/// let names: Vec<_> = tree.pre_order().map(|v| (v.depth, v.path)).collect();
+/// assert_eq!(names, [(0, vec![]), (1, vec![0]), (2, vec![0, 0])]);
+/// ```
+pub trait IOwnedTreeExt: IOwnedTree<Leaf = Self> + Sized {
+    /// Visits the tree depth-first, parents before their children.
+    fn pre_order(&self) -> PreOrder<&Self> {
+        PreOrder {
+            stack: alloc::vec![Visit::root(self)],
+            leaves: owned_leaves,
+        }
+    }
+
+    /// Visits the tree depth-first, children before their parent.
+    fn post_order(&self) -> PostOrder<&Self> { PostOrder::new(self, owned_leaves, usize::MAX) }
+
+    /// Visits the tree breadth-first, one level after the other.
+    fn level_order(&self) -> LevelOrder<&Self> {
+        LevelOrder {
+            queue: VecDeque::from([Visit::root(self)]),
+            leaves: owned_leaves,
+        }
+    }
+
+    /// Copies the nodes matching `predicate` and their ancestors.
+    ///
+    /// Returns `None` if no node matches.
+    fn filter(&self, mut predicate: impl FnMut(&Self) -> bool) -> Option<OwnedTree<String>> {
+        rebuild(self.post_order(), |node, leaves| {
+            (predicate(node) || !leaves.is_empty())
+                .then(|| OwnedTree::new(content_string(*node)).with_leaves(leaves))
+        })
+    }
+
+    /// Copies the nodes whose depth is at most `max_depth`.
+    fn prune_depth(&self, max_depth: usize) -> OwnedTree<String> {
+        rebuild(
+            PostOrder::new(self, owned_leaves, max_depth),
+            |node, leaves| Some(OwnedTree::new(content_string(*node)).with_leaves(leaves)),
+        )
+        .expect("the root is always kept")
+    }
+
+    /// Builds a tree of the same shape with the content produced by `f`.
+    fn map<D: AsRef<str>>(&self, mut f: impl FnMut(&Self) -> D) -> OwnedTree<D> {
+        rebuild(self.post_order(), |node, leaves| {
+            Some(OwnedTree::new(f(node)).with_leaves(leaves))
+        })
+        .expect("the root is always kept")
+    }
+
+    /// Returns the nodes from the root to the first node matching
+    /// `predicate` in pre-order.
+    fn find_path(&self, mut predicate: impl FnMut(&Self) -> bool) -> Option<Vec<&Self>> {
+        find_path(self.pre_order(), |node| predicate(node))
+    }
+}
+
+impl<T: IOwnedTree<Leaf = T>> IOwnedTreeExt for T {}
+
+/// Traversal and transformation utilities for [`ILazyTree`].
+///
+/// Implemented for every lazy tree whose children share its type. The tree
+/// is consumed and traversals yield the nodes produced by
+/// [`ILazyTree::leaves`], so children are only generated when reached.
+pub trait ILazyTreeExt: ILazyTree<Leaf = Self> + Sized {
+    /// Visits the tree depth-first, parents before their children.
+    fn pre_order(self) -> PreOrder<Self> {
+        PreOrder {
+            stack: alloc::vec![Visit::root(self)],
+            leaves: lazy_leaves,
+        }
+    }
+
+    /// Visits the tree depth-first, children before their parent.
+    fn post_order(self) -> PostOrder<Self> { PostOrder::new(self, lazy_leaves, usize::MAX) }
+
+    /// Visits the tree breadth-first, one level after the other.
+    fn level_order(self) -> LevelOrder<Self> {
+        LevelOrder {
+            queue: VecDeque::from([Visit::root(self)]),
+            leaves: lazy_leaves,
+        }
+    }
+
+    /// Copies the nodes matching `predicate` and their ancestors.
+    ///
+    /// Returns `None` if no node matches.
+    fn filter(self, mut predicate: impl FnMut(&Self) -> bool) -> Option<OwnedTree<String>> {
+        rebuild(self.post_order(), |node, leaves| {
+            (predicate(node) || !leaves.is_empty())
+                .then(|| OwnedTree::new(content_string(node)).with_leaves(leaves))
+        })
+    }
+
+    /// Copies the nodes whose depth is at most `max_depth`.
+    ///
+    /// Children of nodes at `max_depth` are never generated.
+    fn prune_depth(self, max_depth: usize) -> OwnedTree<String> {
+        rebuild(
+            PostOrder::new(self, lazy_leaves, max_depth),
+            |node, leaves| Some(OwnedTree::new(content_string(node)).with_leaves(leaves)),
+        )
+        .expect("the root is always kept")
+    }
+
+    /// Builds an owned tree of the same shape with the content produced by
+    /// `f`.
+    fn map<D: AsRef<str>>(self, mut f: impl FnMut(&Self) -> D) -> OwnedTree<D> {
+        rebuild(self.post_order(), |node, leaves| {
+            Some(OwnedTree::new(f(node)).with_leaves(leaves))
+        })
+        .expect("the root is always kept")
+    }
+
+    /// Returns the nodes from the root to the first node matching
+    /// `predicate` in pre-order.
+    fn find_path(self, predicate: impl FnMut(&Self) -> bool) -> Option<Vec<Self>> {
+        find_path(self.pre_order(), predicate)
+    }
+}
+
+impl<T: ILazyTree<Leaf = T>> ILazyTreeExt for T {}
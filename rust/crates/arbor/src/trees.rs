@@ -13,6 +13,9 @@ use crate::protocol::{IIndent, IOwnedTree, IStyledOwnedTree, ITreeContent};
 /// implement [`AsRef<str>`], allowing flexibility such as `String`,
 /// `&str`, or `Cow<str>`.
 ///
+/// With the `serde` feature the tree (de)serializes as nested
+/// `{"content": .., "children": [..]}` objects.
+///
 /// # Examples
 ///
 /// ```
@@ -28,8 +31,10 @@ use crate::protocol::{IIndent, IOwnedTree, IStyledOwnedTree, ITreeContent};
 /// println!("{}", render);
 /// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OwnedTree<D: AsRef<str>> {
     content: D,
+    #[cfg_attr(feature = "serde", serde(rename = "children", default))]
     leaves: Vec<Self>,
 }
 
@@ -0,0 +1,553 @@
+extern crate alloc;
+
+use alloc::collections::vec_deque::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+use crate::protocol::{IIndent, ILazyTree, Layer, Line};
+use crate::renders::display_width;
+
+/// A key handled by [`Viewer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Enter,
+    Backspace,
+    Esc,
+    Char(char),
+
+    /// The terminal was resized. The viewer only redraws.
+    Resize,
+}
+
+/// A terminal the [`Viewer`] draws on and reads keys from.
+///
+/// Implemented by `CrosstermBackend` with the `tui-crossterm` feature and
+/// by [`FakeBackend`], which replays scripted keys and records every frame
+/// for headless tests.
+pub trait IBackend {
+    /// Error returned by the terminal.
+    type Error;
+
+    /// Returns the size of the terminal as `(columns, rows)`.
+    fn size(&mut self) -> Result<(usize, usize), Self::Error>;
+
+    /// Replaces the content of the terminal with `lines`, one per row.
+    fn draw(&mut self, lines: &[String]) -> Result<(), Self::Error>;
+
+    /// Waits for the next key, or returns `None` once the input is closed.
+    fn read_key(&mut self) -> Result<Option<Key>, Self::Error>;
+}
+
+/// A node of the tree being browsed.
+struct Node<T> {
+    tree: T,
+    parent: Option<usize>,
+    layer: Layer,
+
+    /// Indices of the children, `None` until they are loaded.
+    leaves: Option<Vec<usize>>,
+    expanded: bool,
+}
+
+/// Interactive browser for trees implementing [`ILazyTree`].
+///
+/// Only the root children are loaded up front. The children of any other
+/// node are loaded by [`ILazyTree::leaves`] the first time it is expanded,
+/// so huge or unbounded trees can be explored.
+///
+/// | Key                       | Action                                    |
+/// | ------------------------- | ----------------------------------------- |
+/// | `↑` `↓` / `k` `j`         | Move the selection                        |
+/// | `→` / `l`                 | Expand the node, or go to its first child |
+/// | `←` / `h`                 | Collapse the node, or go to its parent    |
+/// | `Enter` / `Space`         | Toggle the node                           |
+/// | `PgUp` `PgDn` `Home` `End` | Move by pages or to either end           |
+/// | `/`                       | Search, `Enter` keeps the match           |
+/// | `n`                       | Go to the next match                      |
+/// | `q` / `Esc`               | Quit                                      |
+///
+/// Search is incremental and case-insensitive. It only looks at nodes that
+/// are already loaded and expands the collapsed ancestors of the match.
+///
+/// Each node shows the first line of its content, truncated to the terminal
+/// width.
+///
+/// # Example
+///
+/// ```
+/// use arbor::indents::AsciiIndent;
+/// use arbor::tui::{FakeBackend, Key, Viewer};
+/// # use arbor::protocol::{ILazyTree, ITreeContent};
+/// # struct Numbers(u32);
+/// # impl ITreeContent for Numbers {
+/// #     fn content(&self) -> impl AsRef<str> { self.0.to_string() }
+/// # }
+/// # impl ILazyTree for Numbers {
+/// #     type Leaf = Numbers;
+/// #     type Leaves = std::vec::IntoIter<Numbers>;
+/// #     fn leaves(&self) -> Self::Leaves { vec![Numbers(self.0 * 2), Numbers(self.0 * 2 + 1)].into_iter() }
+/// # }
+/// let mut viewer = Viewer::new(Numbers(1), AsciiIndent);
+/// let mut backend = FakeBackend::new(40, 10, [Key::Down, Key::Right, Key::Down]);
+/// viewer.run(&mut backend).unwrap();
+/// assert_eq!(viewer.selected().0, 4);
+/// ```
+pub struct Viewer<I, T> {
+    indent: I,
+    nodes: Vec<Node<T>>,
+
+    /// Indices of the visible nodes, from top to bottom.
+    rows: Vec<usize>,
+    cursor: usize,
+    scroll: usize,
+
+    /// Number of tree rows shown by the last render.
+    page: usize,
+    query: String,
+
+    /// Node selected when the search started, while typing a query.
+    search_origin: Option<usize>,
+    quit: bool,
+}
+
+impl<I, T> Viewer<I, T>
+where
+    I: IIndent,
+    T: ILazyTree<Leaf = T>,
+{
+    /// Creates a viewer with the root expanded and selected.
+    pub fn new(tree: T, indent: I) -> Self {
+        let mut viewer = Self {
+            indent,
+            nodes: Vec::from([Node {
+                tree,
+                parent: None,
+                layer: Layer::Root,
+                leaves: None,
+                expanded: false,
+            }]),
+            rows: Vec::new(),
+            cursor: 0,
+            scroll: 0,
+            page: 1,
+            query: String::new(),
+            search_origin: None,
+            quit: false,
+        };
+        viewer.set_expanded(0, true);
+        viewer
+    }
+
+    /// Returns the selected node.
+    pub fn selected(&self) -> &T { &self.nodes[self.rows[self.cursor]].tree }
+
+    /// Draws the viewer and handles keys until the user quits or the input
+    /// is closed.
+    pub fn run<B: IBackend>(&mut self, backend: &mut B) -> Result<(), B::Error> {
+        while !self.quit {
+            let (width, height) = backend.size()?;
+            backend.draw(&self.render(width, height))?;
+            match backend.read_key()? {
+                Some(key) => self.handle_key(key),
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the viewer for a key press.
+    pub fn handle_key(&mut self, key: Key) {
+        if self.search_origin.is_some() {
+            self.handle_search_key(key);
+            return;
+        }
+        let node = self.rows[self.cursor];
+        match key {
+            Key::Up | Key::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            Key::Down | Key::Char('j') => self.cursor = (self.cursor + 1).min(self.rows.len() - 1),
+            Key::PageUp => self.cursor = self.cursor.saturating_sub(self.page),
+            Key::PageDown => self.cursor = (self.cursor + self.page).min(self.rows.len() - 1),
+            Key::Home => self.cursor = 0,
+            Key::End => self.cursor = self.rows.len() - 1,
+            Key::Right | Key::Char('l') => {
+                if self.nodes[node].expanded {
+                    self.select_first_child(node);
+                } else {
+                    self.set_expanded(node, true);
+                }
+            }
+            Key::Left | Key::Char('h') => {
+                if self.nodes[node].expanded {
+                    self.set_expanded(node, false);
+                } else if let Some(parent) = self.nodes[node].parent {
+                    self.select(parent);
+                }
+            }
+            Key::Enter | Key::Char(' ') => self.set_expanded(node, !self.nodes[node].expanded),
+            Key::Char('/') => {
+                self.query.clear();
+                self.search_origin = Some(node);
+            }
+            Key::Char('n') => self.search(node, false),
+            Key::Char('q') | Key::Esc => self.quit = true,
+            _ => {}
+        }
+    }
+
+    fn handle_search_key(&mut self, key: Key) {
+        let Some(origin) = self.search_origin else {
+            return;
+        };
+        match key {
+            Key::Char(c) => self.query.push(c),
+            Key::Backspace => {
+                self.query.pop();
+            }
+            Key::Enter => {
+                self.search_origin = None;
+                return;
+            }
+            Key::Esc => {
+                self.query.clear();
+                self.search_origin = None;
+                self.select(origin);
+                return;
+            }
+            _ => return,
+        }
+        self.search(origin, true);
+    }
+
+    /// Returns the lines of the screen: the visible tree rows followed by a
+    /// status line.
+    pub fn render(&mut self, width: usize, height: usize) -> Vec<String> {
+        self.page = height.saturating_sub(1).max(1);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.page {
+            self.scroll = self.cursor + 1 - self.page;
+        }
+
+        let mut lines: Vec<String> = self.rows[self.scroll..]
+            .iter()
+            .take(self.page)
+            .enumerate()
+            .map(|(row, &node)| truncate(self.render_row(node, self.scroll + row), width))
+            .collect();
+        lines.resize(self.page, String::new());
+        lines.push(truncate(self.status(), width));
+        lines
+    }
+
+    fn render_row(&self, node: usize, row: usize) -> String {
+        let mut line = String::from(if row == self.cursor { "> " } else { "  " });
+
+        let mut ancestors = Vec::new();
+        let mut parent = self.nodes[node].parent;
+        while let Some(ancestor) = parent {
+            ancestors.push(ancestor);
+            parent = self.nodes[ancestor].parent;
+        }
+        // The root is not indented, so its children have no prefix.
+        for &ancestor in ancestors.iter().rev().skip(1) {
+            line.push_str(
+                self.indent
+                    .get_indent(self.nodes[ancestor].layer, Line::Other),
+            );
+        }
+        line.push_str(self.indent.get_indent(self.nodes[node].layer, Line::First));
+
+        line.push_str(match &self.nodes[node] {
+            Node {
+                leaves: Some(leaves),
+                ..
+            } if leaves.is_empty() => "  ",
+            Node { expanded: true, .. } => "- ",
+            _ => "+ ",
+        });
+        let content = self.nodes[node].tree.content();
+        line.push_str(content.as_ref().lines().next().unwrap_or(""));
+        line
+    }
+
+    fn status(&self) -> String {
+        if self.search_origin.is_some() {
+            return alloc::format!("/{}", self.query);
+        }
+        if !self.query.is_empty() {
+            return alloc::format!("/{}  n: next  q: quit", self.query);
+        }
+        String::from("arrows: move/fold  /: search  q: quit")
+    }
+
+    /// Loads the children of `node` if needed and expands or collapses it.
+    fn set_expanded(&mut self, node: usize, expanded: bool) {
+        if expanded && self.nodes[node].leaves.is_none() {
+            let leaves: Vec<T> = self.nodes[node].tree.leaves().collect();
+            let count = leaves.len();
+            let first = self.nodes.len();
+            self.nodes
+                .extend(leaves.into_iter().enumerate().map(|(index, tree)| Node {
+                    tree,
+                    parent: Some(node),
+                    layer: if index + 1 == count {
+                        Layer::Bottom
+                    } else if index == 0 {
+                        Layer::Top
+                    } else {
+                        Layer::Middle
+                    },
+                    leaves: None,
+                    expanded: false,
+                }));
+            self.nodes[node].leaves = Some((first..first + count).collect());
+        }
+        // Nodes without children are never expanded, so `←` goes straight to
+        // their parent.
+        self.nodes[node].expanded = expanded
+            && self.nodes[node]
+                .leaves
+                .as_ref()
+                .is_some_and(|leaves| !leaves.is_empty());
+        self.refresh(node);
+    }
+
+    /// Recomputes the visible rows, keeping `selected` under the cursor.
+    fn refresh(&mut self, selected: usize) {
+        self.rows.clear();
+        let mut stack = Vec::from([0]);
+        while let Some(node) = stack.pop() {
+            self.rows.push(node);
+            if let Node {
+                expanded: true,
+                leaves: Some(leaves),
+                ..
+            } = &self.nodes[node]
+            {
+                stack.extend(leaves.iter().rev());
+            }
+        }
+        self.cursor = self
+            .rows
+            .iter()
+            .position(|&row| row == selected)
+            .unwrap_or(0);
+    }
+
+    /// Selects `node`, expanding its ancestors.
+    fn select(&mut self, node: usize) {
+        let mut parent = self.nodes[node].parent;
+        while let Some(ancestor) = parent {
+            self.nodes[ancestor].expanded = true;
+            parent = self.nodes[ancestor].parent;
+        }
+        self.refresh(node);
+    }
+
+    fn select_first_child(&mut self, node: usize) {
+        if let Some(&first) = self.nodes[node]
+            .leaves
+            .as_ref()
+            .and_then(|leaves| leaves.first())
+        {
+            self.select(first);
+        }
+    }
+
+    /// Selects the first loaded node matching the query in pre-order,
+    /// starting at `from` and wrapping around.
+    fn search(&mut self, from: usize, inclusive: bool) {
+        if self.query.is_empty() {
+            self.select(from);
+            return;
+        }
+        let query = self.query.to_lowercase();
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut stack = Vec::from([0]);
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            if let Some(leaves) = &self.nodes[node].leaves {
+                stack.extend(leaves.iter().rev());
+            }
+        }
+        let start = order.iter().position(|&node| node == from).unwrap_or(0);
+        let skip = if inclusive { 0 } else { 1 };
+        let found = order
+            .iter()
+            .cycle()
+            .skip(start + skip)
+            .take(order.len())
+            .copied()
+            .find(|&node| {
+                let content = self.nodes[node].tree.content();
+                content.as_ref().to_lowercase().contains(&query)
+            });
+        if let Some(node) = found {
+            self.select(node);
+        }
+    }
+}
+
+/// Shortens `line` to at most `width` terminal cells.
+///
+/// Cells are measured like the renderers measure them, so a wide character
+/// such as a CJK ideograph or an emoji counts as two cells and is dropped
+/// whole if only one cell is left.
+fn truncate(mut line: String, width: usize) -> String {
+    let mut used = 0;
+    let mut buf = [0; 4];
+    let end = line.char_indices().find_map(|(index, c)| {
+        used += display_width(c.encode_utf8(&mut buf));
+        (used > width).then_some(index)
+    });
+    if let Some(index) = end {
+        line.truncate(index);
+    }
+    line
+}
+
+/// Backend replaying scripted keys and recording the frames drawn.
+///
+/// The input is closed once all keys have been read.
+#[derive(Debug, Clone, Default)]
+pub struct FakeBackend {
+    /// Number of columns reported to the viewer.
+    pub width: usize,
+
+    /// Number of rows reported to the viewer.
+    pub height: usize,
+
+    /// Keys left to read.
+    pub keys: VecDeque<Key>,
+
+    /// Every frame drawn so far.
+    pub frames: Vec<Vec<String>>,
+}
+
+impl FakeBackend {
+    /// Creates a backend of the given size that reads `keys`.
+    pub fn new(width: usize, height: usize, keys: impl IntoIterator<Item = Key>) -> Self {
+        Self {
+            width,
+            height,
+            keys: keys.into_iter().collect(),
+            frames: Vec::new(),
+        }
+    }
+}
+
+impl IBackend for FakeBackend {
+    type Error = Infallible;
+
+    fn size(&mut self) -> Result<(usize, usize), Self::Error> { Ok((self.width, self.height)) }
+
+    fn draw(&mut self, lines: &[String]) -> Result<(), Self::Error> {
+        self.frames.push(lines.to_vec());
+        Ok(())
+    }
+
+    fn read_key(&mut self) -> Result<Option<Key>, Self::Error> { Ok(self.keys.pop_front()) }
+}
+
+#[cfg(feature = "tui-crossterm")]
+mod terminal {
+    use std::io::{self, Write};
+    use std::string::String;
+
+    use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+    use crossterm::style::Print;
+    use crossterm::terminal::{self, ClearType};
+    use crossterm::{cursor, execute, queue};
+
+    use super::{IBackend, Key};
+
+    /// Backend drawing on a real terminal through `crossterm`.
+    ///
+    /// The terminal is switched to raw mode on the alternate screen while the
+    /// backend is alive and restored when it is dropped. `Ctrl+C` closes the
+    /// input.
+    pub struct CrosstermBackend<W: Write> {
+        out: W,
+    }
+
+    impl<W: Write> CrosstermBackend<W> {
+        /// Prepares the terminal written to by `out`, usually
+        /// [`std::io::stdout`].
+        pub fn new(mut out: W) -> io::Result<Self> {
+            terminal::enable_raw_mode()?;
+            execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+            Ok(Self { out })
+        }
+    }
+
+    impl<W: Write> Drop for CrosstermBackend<W> {
+        fn drop(&mut self) {
+            let _ = execute!(self.out, cursor::Show, terminal::LeaveAlternateScreen);
+            let _ = terminal::disable_raw_mode();
+        }
+    }
+
+    impl<W: Write> IBackend for CrosstermBackend<W> {
+        type Error = io::Error;
+
+        fn size(&mut self) -> io::Result<(usize, usize)> {
+            let (columns, rows) = terminal::size()?;
+            Ok((columns.into(), rows.into()))
+        }
+
+        fn draw(&mut self, lines: &[String]) -> io::Result<()> {
+            queue!(self.out, terminal::Clear(ClearType::All))?;
+            for (row, line) in lines.iter().enumerate() {
+                let row = u16::try_from(row).unwrap_or(u16::MAX);
+                queue!(self.out, cursor::MoveTo(0, row), Print(line))?;
+            }
+            self.out.flush()
+        }
+
+        fn read_key(&mut self) -> io::Result<Option<Key>> {
+            loop {
+                let (code, modifiers) = match event::read()? {
+                    Event::Key(KeyEvent {
+                        code,
+                        modifiers,
+                        kind: KeyEventKind::Press,
+                        ..
+                    }) => (code, modifiers),
+                    Event::Resize(..) => return Ok(Some(Key::Resize)),
+                    _ => continue,
+                };
+                let key = match code {
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok(None);
+                    }
+                    KeyCode::Up => Key::Up,
+                    KeyCode::Down => Key::Down,
+                    KeyCode::Left => Key::Left,
+                    KeyCode::Right => Key::Right,
+                    KeyCode::PageUp => Key::PageUp,
+                    KeyCode::PageDown => Key::PageDown,
+                    KeyCode::Home => Key::Home,
+                    KeyCode::End => Key::End,
+                    KeyCode::Enter => Key::Enter,
+                    KeyCode::Backspace => Key::Backspace,
+                    KeyCode::Esc => Key::Esc,
+                    KeyCode::Char(c) => Key::Char(c),
+                    _ => continue,
+                };
+                return Ok(Some(key));
+            }
+        }
+    }
+}
+#[cfg(feature = "tui-crossterm")]
+pub use terminal::CrosstermBackend;
@@ -1,6 +1,10 @@
+use std::cell::Cell;
+use std::fmt::Write;
+use std::rc::Rc;
+
 use arbor::indents::{AsciiIndent, DebugIndent, SpaceIndent, UnicodeIndent, UniversalIndent};
-use arbor::protocol::IIndent;
-use arbor::renders::{OwnedRender, StyledOwnedRender};
+use arbor::protocol::{IIndent, ILazyTree, ITreeContent};
+use arbor::renders::{LazyRender, OwnedRender, StyledOwnedRender};
 use arbor::trees::{OwnedTree, StyledOwnedTree};
 use rstest::rstest;
 
@@ -144,3 +148,105 @@ fn render_tree_with_complex(
         format!("{}", render)
     );
 }
+
+/// Lazy tree of `width^depth` leaves that tracks how many nodes are alive.
+struct CountedTree {
+    depth: usize,
+    width: usize,
+    live: Rc<Cell<usize>>,
+    peak: Rc<Cell<usize>>,
+}
+impl CountedTree {
+    fn spawn(&self, depth: usize) -> Self {
+        self.live.set(self.live.get() + 1);
+        self.peak.set(self.peak.get().max(self.live.get()));
+        Self {
+            depth,
+            width: self.width,
+            live: self.live.clone(),
+            peak: self.peak.clone(),
+        }
+    }
+}
+impl Drop for CountedTree {
+    fn drop(&mut self) { self.live.set(self.live.get().saturating_sub(1)); }
+}
+impl ITreeContent for CountedTree {
+    fn content(&self) -> impl AsRef<str> { format!("depth {}", self.depth) }
+}
+/// Children of a [`CountedTree`], created one at a time.
+struct CountedLeaves {
+    parent: CountedTree,
+    remaining: std::ops::Range<usize>,
+}
+impl Iterator for CountedLeaves {
+    type Item = CountedTree;
+    fn next(&mut self) -> Option<CountedTree> {
+        self.remaining
+            .next()
+            .map(|_| self.parent.spawn(self.parent.depth - 1))
+    }
+}
+impl DoubleEndedIterator for CountedLeaves {
+    fn next_back(&mut self) -> Option<CountedTree> {
+        self.remaining
+            .next_back()
+            .map(|_| self.parent.spawn(self.parent.depth - 1))
+    }
+}
+impl ILazyTree for CountedTree {
+    type Leaf = CountedTree;
+    type Leaves = CountedLeaves;
+    fn leaves(&self) -> Self::Leaves {
+        CountedLeaves {
+            parent: self.spawn(self.depth),
+            remaining: 0..if self.depth == 0 { 0 } else { self.width },
+        }
+    }
+}
+#[test]
+fn render_lazy_tree_memory_proportional_to_depth() {
+    let live = Rc::new(Cell::new(0));
+    let peak = Rc::new(Cell::new(0));
+    let root = CountedTree {
+        depth: 6,
+        width: 8,
+        live: live.clone(),
+        peak: peak.clone(),
+    };
+    let render = LazyRender {
+        tree: root.spawn(6),
+        indent: UnicodeIndent,
+        width: 0,
+    };
+    let mut output = String::new();
+    write!(output, "{}", render).unwrap();
+    // 1 + 8 + 8^2 + ... + 8^6 nodes are rendered, while at most a parent,
+    // a current and a peeked node are alive per level.
+    assert_eq!(
+        output.lines().count(),
+        (0..=6).map(|d| 8_usize.pow(d)).sum::<usize>()
+    );
+    assert!(peak.get() <= 1 + 3 * 7, "peak live nodes: {}", peak.get());
+    drop(root);
+}
+#[test]
+fn render_tree_in_pre_order() {
+    let tree = OwnedTree::new("root").with_leaves([
+        OwnedTree::new("a").with_leaves([OwnedTree::new("a1").with_leaves(["a1x"]), "a2".into()]),
+        OwnedTree::new("b").with_leaves(["b1"]),
+    ]);
+    let render = OwnedRender {
+        tree: &tree,
+        indent: AsciiIndent,
+        width: 0,
+    };
+    let names: Vec<_> = format!("{}", render)
+        .lines()
+        .map(|line| {
+            line.trim_start_matches(|c: char| !c.is_alphanumeric())
+                .to_string()
+        })
+        .collect();
+    assert_eq!(names, ["root", "a", "a1", "a1x", "a2", "b", "b1"]);
+}
@@ -0,0 +1,109 @@
+use arbor::indents::{AsciiIndent, UnicodeIndent};
+use arbor::protocol::{ILazyTree, IOwnedTree, ITreeColumns, ITreeContent};
+use arbor::renders::{LazyColumnRender, OwnedColumnRender};
+
+/// A file entry with its size and permissions.
+#[derive(Clone)]
+struct Entry {
+    name: &'static str,
+    size: Option<&'static str>,
+    mode: &'static str,
+    leaves: Vec<Entry>,
+}
+fn entry(name: &'static str, size: Option<&'static str>, mode: &'static str) -> Entry {
+    Entry {
+        name,
+        size,
+        mode,
+        leaves: Vec::new(),
+    }
+}
+impl Entry {
+    fn with_leaves(mut self, leaves: impl IntoIterator<Item = Entry>) -> Self {
+        self.leaves.extend(leaves);
+        self
+    }
+}
+impl ITreeContent for Entry {
+    fn content(&self) -> impl AsRef<str> { self.name }
+}
+impl ITreeColumns for Entry {
+    fn columns(&self) -> impl IntoIterator<Item = impl AsRef<str>> {
+        [self.size.unwrap_or("-"), self.mode]
+    }
+}
+impl IOwnedTree for Entry {
+    type Leaf = Entry;
+    type Leaves<'a> = std::slice::Iter<'a, Entry>;
+    fn leaves(&self) -> Self::Leaves<'_> { self.leaves.iter() }
+}
+impl ILazyTree for Entry {
+    type Leaf = Entry;
+    type Leaves = std::vec::IntoIter<Entry>;
+    fn leaves(&self) -> Self::Leaves { self.leaves.clone().into_iter() }
+}
+
+fn sample() -> Entry {
+    entry("src", None, "drwxr-xr-x").with_leaves([
+        entry("lib.rs", Some("1.2k"), "-rw-r--r--"),
+        entry("renders", None, "drwxr-xr-x").with_leaves([
+            entry("表示.rs", Some("12k"), "-rw-r--r--"),
+            entry("emoji_🌲.rs", Some("256"), "-rw-------"),
+        ]),
+        entry("a_rather_long_file_name.rs", Some("98k"), "-rwxr-xr-x"),
+    ])
+}
+
+#[test]
+fn render_columns_owned() {
+    let tree = sample();
+    let render = OwnedColumnRender {
+        tree: &tree,
+        indent: UnicodeIndent,
+    };
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+#[test]
+fn render_columns_lazy() {
+    let render = LazyColumnRender {
+        tree: sample(),
+        indent: AsciiIndent,
+    };
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+/// A node whose cells are partly empty.
+struct Sparse(&'static str, &'static [&'static str], Vec<Sparse>);
+impl ITreeContent for Sparse {
+    fn content(&self) -> impl AsRef<str> { self.0 }
+}
+impl ITreeColumns for Sparse {
+    fn columns(&self) -> impl IntoIterator<Item = impl AsRef<str>> { self.1.iter() }
+}
+impl IOwnedTree for Sparse {
+    type Leaf = Sparse;
+    type Leaves<'a> = std::slice::Iter<'a, Sparse>;
+    fn leaves(&self) -> Self::Leaves<'_> { self.2.iter() }
+}
+
+#[test]
+fn render_columns_sparse() {
+    let tree = Sparse(
+        "jobs",
+        &[],
+        vec![
+            Sparse("build\nrelease", &["running", "", "12s"], Vec::new()),
+            Sparse("test", &["", "", ""], Vec::new()),
+            Sparse("lint", &["done", "ok"], Vec::new()),
+        ],
+    );
+    let render = OwnedColumnRender {
+        tree: &tree,
+        indent: UnicodeIndent,
+    };
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
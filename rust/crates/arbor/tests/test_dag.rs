@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use arbor::indents::UnicodeIndent;
+use arbor::protocol::{ILazyTree, ITreeContent};
+use arbor::renders::{LazyDagRender, OwnedDagRender};
+use arbor::trees::OwnedTree;
+
+/// A node of a dependency graph given as an adjacency map.
+#[derive(Clone)]
+struct Graph<'a> {
+    name: &'a str,
+    edges: &'a BTreeMap<&'a str, Vec<&'a str>>,
+}
+impl ITreeContent for Graph<'_> {
+    fn content(&self) -> impl AsRef<str> { self.name }
+}
+impl<'a> ILazyTree for Graph<'a> {
+    type Leaf = Graph<'a>;
+    type Leaves = std::vec::IntoIter<Graph<'a>>;
+    fn leaves(&self) -> Self::Leaves {
+        self.edges
+            .get(self.name)
+            .map(|names| {
+                names
+                    .iter()
+                    .map(|name| Graph {
+                        name,
+                        edges: self.edges,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+            .into_iter()
+    }
+}
+
+#[test]
+fn render_dag_owned_repeated() {
+    let shared = OwnedTree::new("foo.dll").with_leaves(["kernel32.dll", "bar.dll"]);
+    let tree = OwnedTree::new("app.exe").with_leaves([
+        shared.clone(),
+        OwnedTree::new("baz.dll").with_leaves([shared.clone(), "kernel32.dll".into()]),
+        shared,
+    ]);
+    let render = OwnedDagRender {
+        tree: &tree,
+        indent: UnicodeIndent,
+        key: |t: &OwnedTree<&str>| t.content().as_ref().to_string(),
+        width: 0,
+    };
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+#[test]
+fn render_dag_lazy_cycle() {
+    let edges = BTreeMap::from([
+        ("app.exe", vec!["a.dll", "b.dll"]),
+        ("a.dll", vec!["c.dll"]),
+        ("b.dll", vec!["c.dll", "app.exe"]),
+        ("c.dll", vec!["a.dll", "c.dll"]),
+    ]);
+    let render = LazyDagRender {
+        tree: Graph {
+            name: "app.exe",
+            edges: &edges,
+        },
+        indent: UnicodeIndent,
+        key: |g: &Graph| g.name.to_string(),
+        width: 0,
+    };
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
@@ -0,0 +1,99 @@
+use arbor::diff::{Change, DiffRender, diff, diff_by_key};
+use arbor::indents::{AsciiIndent, UnicodeIndent};
+use arbor::protocol::ITreeContent;
+use arbor::trees::OwnedTree;
+
+fn before() -> OwnedTree<&'static str> {
+    OwnedTree::new("app 1.0").with_leaves([
+        OwnedTree::new("serde 1.0.200").with_leaves(["serde_derive 1.0.200"]),
+        OwnedTree::new("log 0.4.20"),
+        OwnedTree::new("tokio 1.37.0").with_leaves(["bytes 1.5.0", "mio 0.8.11"]),
+        OwnedTree::new("regex 1.10.0").with_leaves(["aho-corasick 1.1.2"]),
+    ])
+}
+fn after() -> OwnedTree<&'static str> {
+    OwnedTree::new("app 1.1").with_leaves([
+        OwnedTree::new("serde 1.0.229").with_leaves(["serde_derive 1.0.229"]),
+        OwnedTree::new("tokio 1.53.1").with_leaves([
+            OwnedTree::new("bytes 1.5.0"),
+            OwnedTree::new("mio 1.0.4"),
+            OwnedTree::new("regex 1.10.0").with_leaves(["aho-corasick 1.1.2"]),
+        ]),
+        OwnedTree::new("tracing 0.1.44"),
+    ])
+}
+
+#[test]
+fn diff_by_content() {
+    let old = OwnedTree::new("pkg").with_leaves([
+        OwnedTree::new("bin").with_leaves(["tool"]),
+        OwnedTree::new("lib").with_leaves(["a.so", "b.so"]),
+        OwnedTree::new("README.md"),
+    ]);
+    let new = OwnedTree::new("pkg").with_leaves([
+        OwnedTree::new("bin").with_leaves(["tool", "b.so"]),
+        OwnedTree::new("lib").with_leaves(["a.so", "c.so"]),
+        OwnedTree::new("LICENSE"),
+        OwnedTree::new("README.md"),
+    ]);
+    let diff = diff(&old, &new);
+    let render = DiffRender {
+        diff: &diff,
+        indent: UnicodeIndent,
+        width: 0,
+    };
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+#[test]
+fn diff_by_package_name() {
+    let diff = diff_by_key(&before(), &after(), |node| {
+        node.content()
+            .as_ref()
+            .split(' ')
+            .next()
+            .unwrap()
+            .to_string()
+    });
+    assert_eq!(
+        diff.leaves[2].leaves[2].change,
+        Change::Moved {
+            from: "app 1.0".to_string()
+        }
+    );
+    let render = DiffRender {
+        diff: &diff,
+        indent: AsciiIndent,
+        width: 0,
+    };
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+#[test]
+fn diff_identical() {
+    let diff = diff(&before(), &before());
+    assert!(!diff.has_changes());
+    assert_eq!(diff.change, Change::Unchanged);
+}
+
+#[test]
+fn diff_repeated_keys() {
+    let old = OwnedTree::new("root").with_leaves(["a", "b", "a"]);
+    let new = OwnedTree::new("root").with_leaves(["b", "a", "c"]);
+    let changes: Vec<_> = diff(&old, &new)
+        .leaves
+        .into_iter()
+        .map(|leaf| (leaf.content, leaf.change))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            ("b".to_string(), Change::Unchanged),
+            ("a".to_string(), Change::Unchanged),
+            ("a".to_string(), Change::Removed),
+            ("c".to_string(), Change::Added),
+        ]
+    );
+}
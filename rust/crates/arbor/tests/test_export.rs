@@ -0,0 +1,48 @@
+use arbor::export::{LazyDotRender, LazyMermaidRender, OwnedDotRender, OwnedMermaidRender};
+use arbor::protocol::{ILazyTree, ITreeContent};
+use arbor::trees::OwnedTree;
+
+fn sample() -> OwnedTree<&'static str> {
+    OwnedTree::new("app.exe").with_leaves([
+        OwnedTree::new("foo.dll").with_leaves(["kernel32.dll"]),
+        OwnedTree::new("say \"hi\"\nC:\\bar.dll"),
+    ])
+}
+
+#[test]
+fn export_dot() {
+    let tree = sample();
+    insta::assert_snapshot!(OwnedDotRender { tree: &tree }.to_string());
+}
+
+#[test]
+fn export_mermaid() {
+    let tree = sample();
+    insta::assert_snapshot!(OwnedMermaidRender { tree: &tree }.to_string());
+}
+
+#[test]
+fn export_mermaid_escapes_angle_brackets() {
+    let tree = OwnedTree::new("Vec<u8>").with_leaves(["a -> b"]);
+    assert_eq!(
+        OwnedMermaidRender { tree: &tree }.to_string(),
+        "graph TD\n    n0[\"Vec#lt;u8#gt;\"]\n    n1[\"a -#gt; b\"]\n    n0 --> n1\n"
+    );
+}
+
+/// Lazy chain of `n` nodes.
+struct Chain(u8);
+impl ITreeContent for Chain {
+    fn content(&self) -> impl AsRef<str> { format!("level {}", self.0) }
+}
+impl ILazyTree for Chain {
+    type Leaf = Chain;
+    type Leaves = std::option::IntoIter<Chain>;
+    fn leaves(&self) -> Self::Leaves { self.0.checked_sub(1).map(Chain).into_iter() }
+}
+
+#[test]
+fn export_lazy() {
+    insta::assert_snapshot!(LazyDotRender { tree: Chain(2) }.to_string());
+    insta::assert_snapshot!(LazyMermaidRender { tree: Chain(2) }.to_string());
+}
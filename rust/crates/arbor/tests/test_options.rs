@@ -0,0 +1,177 @@
+use arbor::indents::{AsciiIndent, UnicodeIndent};
+use arbor::protocol::{ILazyTree, ITreeContent};
+use arbor::renders::{LazyRender, OwnedRender, RenderOptions, StyledOwnedRender};
+use arbor::trees::{OwnedTree, StyledOwnedTree};
+
+fn sample() -> OwnedTree<&'static str> {
+    OwnedTree::new("repo").with_leaves([
+        OwnedTree::new("src")
+            .with_leaves([OwnedTree::new("renders").with_leaves([OwnedTree::new("mod.rs")])]),
+        OwnedTree::new("tests").with_leaves(["a.rs", "b.rs", "c.rs", "d.rs", "e.rs"]),
+        OwnedTree::new("docs").with_leaves([OwnedTree::new("guide").with_leaves(["intro.md"])]),
+        OwnedTree::new("Cargo.toml"),
+    ])
+}
+
+#[test]
+fn render_options_max_children() {
+    let tree = sample();
+    let render = OwnedRender {
+        tree: &tree,
+        indent: UnicodeIndent,
+        width: 0,
+    }
+    .with_options(RenderOptions {
+        max_children: Some(2),
+        ..Default::default()
+    });
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+#[test]
+fn render_options_max_depth() {
+    let tree = sample();
+    let render = OwnedRender {
+        tree: &tree,
+        indent: UnicodeIndent,
+        width: 0,
+    }
+    .with_options(RenderOptions {
+        max_depth: Some(1),
+        ..Default::default()
+    });
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+#[test]
+fn render_options_collapse_chains() {
+    let tree = OwnedTree::new("a").with_leaves([OwnedTree::new("b").with_leaves([sample()])]);
+    let render = OwnedRender {
+        tree: &tree,
+        indent: UnicodeIndent,
+        width: 0,
+    }
+    .with_options(RenderOptions {
+        collapse_chains: true,
+        ..Default::default()
+    });
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+#[test]
+fn render_options_styled() {
+    let tree = StyledOwnedTree::new_with_indent("root", AsciiIndent).with_leaves([
+        StyledOwnedTree::new("first").with_leaves(["one", "two", "three"]),
+        StyledOwnedTree::new("second").with_leaves([StyledOwnedTree::new("chain")
+            .with_leaves([StyledOwnedTree::new("deep")
+                .with_leaves([StyledOwnedTree::new("deeper").with_leaves(["deepest"])])])]),
+    ]);
+    let render = StyledOwnedRender {
+        tree: &tree,
+        width: 0,
+    }
+    .with_options(RenderOptions {
+        max_children: Some(2),
+        max_depth: Some(2),
+        collapse_chains: true,
+    });
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+/// Lazy tree with `fan_out` children per node, down to depth `3`.
+struct Numbers {
+    name: String,
+    depth: usize,
+    fan_out: usize,
+}
+impl ITreeContent for Numbers {
+    fn content(&self) -> impl AsRef<str> { &self.name }
+}
+impl ILazyTree for Numbers {
+    type Leaf = Numbers;
+    type Leaves = std::vec::IntoIter<Numbers>;
+    fn leaves(&self) -> Self::Leaves {
+        if self.depth == 3 {
+            return Vec::new().into_iter();
+        }
+        (0..self.fan_out)
+            .map(|i| Numbers {
+                name: format!("{}.{i}", self.name),
+                depth: self.depth + 1,
+                fan_out: self.fan_out,
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+#[test]
+fn render_options_lazy() {
+    let render = LazyRender {
+        tree: Numbers {
+            name: "n".to_string(),
+            depth: 0,
+            fan_out: 150,
+        },
+        indent: UnicodeIndent,
+        width: 0,
+    }
+    .with_options(RenderOptions {
+        max_children: Some(3),
+        max_depth: Some(2),
+        collapse_chains: false,
+    });
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+#[test]
+fn render_options_lazy_chain() {
+    let render = LazyRender {
+        tree: Numbers {
+            name: "n".to_string(),
+            depth: 0,
+            fan_out: 1,
+        },
+        indent: AsciiIndent,
+        width: 0,
+    }
+    .with_options(RenderOptions {
+        collapse_chains: true,
+        ..Default::default()
+    });
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
+
+#[test]
+fn render_options_zero_limits() {
+    let tree = sample();
+    let render = |options| {
+        OwnedRender {
+            tree: &tree,
+            indent: AsciiIndent,
+            width: 0,
+        }
+        .with_options(options)
+        .to_string()
+    };
+    assert_eq!(
+        render(RenderOptions {
+            max_depth: Some(0),
+            ..Default::default()
+        }),
+        "repo\n`-- …\n"
+    );
+    assert_eq!(
+        render(RenderOptions {
+            max_children: Some(0),
+            ..Default::default()
+        }),
+        "repo\n`-- … and 4 more\n"
+    );
+}
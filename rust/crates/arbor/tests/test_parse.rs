@@ -0,0 +1,78 @@
+use arbor::indents::{AsciiIndent, SpaceIndent, UnicodeIndent};
+use arbor::parse::{ParseError, parse_indented, parse_outline};
+use arbor::protocol::{IIndent, IOwnedTree, ITreeContent};
+use arbor::renders::OwnedRender;
+use arbor::trees::OwnedTree;
+use rstest::rstest;
+
+fn sample() -> OwnedTree<&'static str> {
+    OwnedTree::new("workspace").with_leaves([
+        OwnedTree::new("crates").with_leaves([
+            OwnedTree::new("arbor").with_leaves(["src", "tests"]),
+            OwnedTree::new("mischief\nerror reports"),
+        ]),
+        OwnedTree::new("Cargo.toml"),
+    ])
+}
+
+fn render<I: IIndent, D: AsRef<str>>(tree: &OwnedTree<D>, indent: I) -> String {
+    OwnedRender {
+        tree,
+        indent,
+        width: 0,
+    }
+    .to_string()
+}
+
+#[rstest]
+#[case::unicode(UnicodeIndent)]
+#[case::ascii(AsciiIndent)]
+fn parse_round_trip<I: IIndent + Clone>(#[case] indent: I) {
+    let text = render(&sample(), indent.clone());
+    let tree = parse_indented(&text, &indent).unwrap();
+    assert_eq!(render(&tree, indent), text);
+}
+
+#[test]
+fn parse_round_trip_space() {
+    let tree =
+        OwnedTree::new("a").with_leaves([OwnedTree::new("b").with_leaves(["c"]), "d".into()]);
+    let text = render(&tree, SpaceIndent);
+    let parsed = parse_indented(&text, &SpaceIndent).unwrap();
+    assert_eq!(render(&parsed, SpaceIndent), text);
+}
+
+#[test]
+fn parse_trimmed_empty_nodes() {
+    let tree = parse_indented("root\n├──\n╰── leaf", &UnicodeIndent).unwrap();
+    let leaves: Vec<_> = tree
+        .leaves()
+        .map(|leaf| leaf.content().as_ref().to_string())
+        .collect();
+    assert_eq!(leaves, ["", "leaf"]);
+}
+
+#[rstest]
+#[case::two_spaces("  ", "root\n  a\n    a1\n\n    a2\n  b\n")]
+#[case::tab("\t", "root\n\ta\n\t\ta1\n\n\t\ta2\n\tb\n")]
+fn parse_outlines(#[case] unit: &str, #[case] text: &str) {
+    let tree = parse_outline(text, unit).unwrap();
+    insta::assert_snapshot!("parse_outlines", render(&tree, UnicodeIndent));
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(parse_outline("\n\n", "  ").unwrap_err(), ParseError::Empty);
+    assert_eq!(
+        parse_outline("root\n  a\n      b", "  ").unwrap_err(),
+        ParseError::UnexpectedIndent { line: 3 }
+    );
+    assert_eq!(
+        parse_outline("root\n  a\nother", "  ").unwrap_err(),
+        ParseError::MultipleRoots { line: 3 }
+    );
+    assert_eq!(
+        parse_indented("root\n│   ╰── a", &UnicodeIndent).unwrap_err(),
+        ParseError::UnexpectedIndent { line: 2 }
+    );
+}
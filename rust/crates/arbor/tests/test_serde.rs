@@ -0,0 +1,66 @@
+#![cfg(feature = "serde")]
+use arbor::export::{LazySerialize, OwnedSerialize};
+use arbor::indents::UnicodeIndent;
+use arbor::protocol::{ILazyTree, ITreeContent};
+use arbor::renders::OwnedRender;
+use arbor::trees::{OwnedTree, StyledOwnedTree};
+
+#[test]
+fn serialize_owned() {
+    let tree = StyledOwnedTree::new_with_indent("foo", UnicodeIndent).with_leaves([
+        StyledOwnedTree::new("bar").with_leaves(["baz"]),
+        "qux".into(),
+    ]);
+    insta::assert_json_snapshot!(OwnedSerialize { tree: &tree });
+}
+
+/// Lazy tree of the multiples of a number below a limit.
+struct Multiples(u32);
+impl ITreeContent for Multiples {
+    fn content(&self) -> impl AsRef<str> { self.0.to_string() }
+}
+impl ILazyTree for Multiples {
+    type Leaf = Multiples;
+    type Leaves = std::vec::IntoIter<Multiples>;
+    fn leaves(&self) -> Self::Leaves {
+        (2..)
+            .map(|k| self.0 * k)
+            .take_while(|n| *n <= 12)
+            .map(Multiples)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+#[test]
+fn serialize_lazy() {
+    insta::assert_json_snapshot!(LazySerialize { tree: Multiples(3) });
+}
+
+#[test]
+fn deserialize_round_trip() {
+    let json = serde_json::to_string(&LazySerialize { tree: Multiples(2) }).unwrap();
+    let tree: OwnedTree<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&tree).unwrap(), json);
+    insta::assert_snapshot!(
+        OwnedRender {
+            tree: &tree,
+            indent: UnicodeIndent,
+            width: 0,
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn deserialize_missing_children() {
+    let tree: OwnedTree<String> =
+        serde_json::from_str(r#"{"content": "root", "children": [{"content": "leaf"}]}"#).unwrap();
+    assert_eq!(
+        serde_json::to_value(&tree).unwrap(),
+        serde_json::json!({
+            "content": "root",
+            "children": [{"content": "leaf", "children": []}],
+        })
+    );
+}
@@ -0,0 +1,120 @@
+use arbor::indents::UnicodeIndent;
+use arbor::protocol::{ILazyTree, IOwnedTree, IStyledContent, ITreeContent};
+use arbor::renders::{LazySpanRender, OwnedSpanRender, RenderOptions};
+use arbor::style::{Color, Span, Style};
+use rstest::rstest;
+
+/// A file whose name is green and links to its path, followed by a dimmed
+/// description.
+#[derive(Clone)]
+struct File {
+    name: &'static str,
+    description: &'static str,
+    leaves: Vec<File>,
+}
+fn file(name: &'static str, description: &'static str) -> File {
+    File {
+        name,
+        description,
+        leaves: Vec::new(),
+    }
+}
+impl File {
+    fn with_leaves(mut self, leaves: impl IntoIterator<Item = File>) -> Self {
+        self.leaves.extend(leaves);
+        self
+    }
+}
+impl ITreeContent for File {
+    fn content(&self) -> impl AsRef<str> { format!("{} {}", self.name, self.description) }
+}
+impl IStyledContent for File {
+    fn spans(&self) -> impl IntoIterator<Item = Span<'_>> {
+        [
+            Span::new(self.name)
+                .with_style(Style {
+                    fg: Some(Color::Green),
+                    bold: true,
+                    ..Default::default()
+                })
+                .with_link(format!("file:///repo/{}", self.name)),
+            Span::new(" "),
+            Span::new(self.description).with_style(Style {
+                dim: true,
+                bg: Some(Color::Rgb(40, 40, 40)),
+                ..Default::default()
+            }),
+        ]
+    }
+}
+impl IOwnedTree for File {
+    type Leaf = File;
+    type Leaves<'a> = std::slice::Iter<'a, File>;
+    fn leaves(&self) -> Self::Leaves<'_> { self.leaves.iter() }
+}
+impl ILazyTree for File {
+    type Leaf = File;
+    type Leaves = std::vec::IntoIter<File>;
+    fn leaves(&self) -> Self::Leaves { self.leaves.clone().into_iter() }
+}
+
+fn sample() -> File {
+    file("repo", "workspace root").with_leaves([
+        file("Cargo.toml", "manifest of the workspace members"),
+        file("src", "sources").with_leaves([file("lib.rs", "crate root\nwith two lines")]),
+    ])
+}
+
+#[rstest]
+#[case("color", true, 0)]
+#[case("no_color", false, 0)]
+#[case("color_wrapped", true, 24)]
+#[case("no_color_wrapped", false, 24)]
+fn render_spans_owned(#[case] name: &str, #[case] color: bool, #[case] width: usize) {
+    let tree = sample();
+    let render = OwnedSpanRender {
+        tree: &tree,
+        indent: UnicodeIndent,
+        color,
+        width,
+    };
+    println!("{}", render);
+    insta::assert_snapshot!(format!("render_spans_owned_{name}"), format!("{}", render));
+}
+
+#[test]
+fn render_spans_wrap_ignores_escapes() {
+    let tree = sample();
+    let plain = OwnedSpanRender {
+        tree: &tree,
+        indent: UnicodeIndent,
+        color: false,
+        width: 24,
+    }
+    .to_string();
+    let styled = OwnedSpanRender {
+        tree: &tree,
+        indent: UnicodeIndent,
+        color: true,
+        width: 24,
+    }
+    .to_string();
+    assert_eq!(plain.lines().count(), styled.lines().count());
+}
+
+#[test]
+fn render_spans_lazy_collapsed() {
+    let render = LazySpanRender {
+        tree: file("repo", "root").with_leaves([file("src", "sources")
+            .with_leaves([file("bin", "binaries").with_leaves([file("main.rs", "entry point")])])]),
+        indent: UnicodeIndent,
+        color: true,
+        width: 0,
+    }
+    .with_options(RenderOptions {
+        collapse_chains: true,
+        ..Default::default()
+    });
+    println!("{}", render);
+    insta::assert_snapshot!(format!("{}", render));
+}
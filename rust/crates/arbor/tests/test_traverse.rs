@@ -0,0 +1,156 @@
+use arbor::indents::UnicodeIndent;
+use arbor::protocol::{ILazyTree, ITreeContent};
+use arbor::renders::OwnedRender;
+use arbor::traverse::{ILazyTreeExt, IOwnedTreeExt};
+use arbor::trees::OwnedTree;
+
+fn sample() -> OwnedTree<&'static str> {
+    OwnedTree::new("root").with_leaves([
+        OwnedTree::new("a").with_leaves(["a1", "a2"]),
+        OwnedTree::new("b").with_leaves([OwnedTree::new("b1").with_leaves(["b1x"])]),
+        OwnedTree::new("c"),
+    ])
+}
+
+fn names<'a>(
+    visits: impl Iterator<Item = arbor::traverse::Visit<&'a OwnedTree<&'static str>>>,
+) -> Vec<String> {
+    visits
+        .map(|v| format!("{}@{}{:?}", v.node.content().as_ref(), v.depth, v.path))
+        .collect()
+}
+
+fn render(tree: &OwnedTree<impl AsRef<str>>) -> String {
+    OwnedRender {
+        tree,
+        indent: UnicodeIndent,
+        width: 0,
+    }
+    .to_string()
+}
+
+#[test]
+fn traverse_orders() {
+    let tree = sample();
+    assert_eq!(
+        names(tree.pre_order()),
+        [
+            "root@0[]",
+            "a@1[0]",
+            "a1@2[0, 0]",
+            "a2@2[0, 1]",
+            "b@1[1]",
+            "b1@2[1, 0]",
+            "b1x@3[1, 0, 0]",
+            "c@1[2]"
+        ]
+    );
+    assert_eq!(
+        names(tree.post_order()),
+        [
+            "a1@2[0, 0]",
+            "a2@2[0, 1]",
+            "a@1[0]",
+            "b1x@3[1, 0, 0]",
+            "b1@2[1, 0]",
+            "b@1[1]",
+            "c@1[2]",
+            "root@0[]"
+        ]
+    );
+    assert_eq!(
+        names(tree.level_order()),
+        [
+            "root@0[]",
+            "a@1[0]",
+            "b@1[1]",
+            "c@1[2]",
+            "a1@2[0, 0]",
+            "a2@2[0, 1]",
+            "b1@2[1, 0]",
+            "b1x@3[1, 0, 0]"
+        ]
+    );
+}
+
+#[test]
+fn traverse_filter() {
+    let tree = sample();
+    let filtered = tree
+        .filter(|node| node.content().as_ref().ends_with('x') || *node.content().as_ref() == *"a2")
+        .unwrap();
+    insta::assert_snapshot!(render(&filtered));
+    assert!(tree.filter(|_| false).is_none());
+}
+
+#[test]
+fn traverse_prune_depth() {
+    let tree = sample();
+    insta::assert_snapshot!(render(&tree.prune_depth(1)));
+    assert_eq!(render(&tree.prune_depth(0)), "root\n");
+}
+
+#[test]
+fn traverse_map() {
+    let tree = sample();
+    let mapped = tree.map(|node| node.content().as_ref().to_uppercase());
+    insta::assert_snapshot!(render(&mapped));
+}
+
+#[test]
+fn traverse_find_path() {
+    let tree = sample();
+    let path: Vec<_> = tree
+        .find_path(|node| node.content().as_ref() == "b1x")
+        .unwrap()
+        .into_iter()
+        .map(|node| node.content().as_ref().to_string())
+        .collect();
+    assert_eq!(path, ["root", "b", "b1", "b1x"]);
+    assert!(
+        tree.find_path(|node| node.content().as_ref() == "z")
+            .is_none()
+    );
+}
+
+/// Lazy binary tree counting down to zero.
+struct Countdown(u32);
+impl ITreeContent for Countdown {
+    fn content(&self) -> impl AsRef<str> { self.0.to_string() }
+}
+impl ILazyTree for Countdown {
+    type Leaf = Countdown;
+    type Leaves = std::vec::IntoIter<Countdown>;
+    fn leaves(&self) -> Self::Leaves {
+        match self.0 {
+            0 => Vec::new(),
+            n => vec![Countdown(n - 1), Countdown(n - 1)],
+        }
+        .into_iter()
+    }
+}
+
+#[test]
+fn traverse_lazy() {
+    assert_eq!(Countdown(3).pre_order().count(), 15);
+    let depths: Vec<_> = Countdown(2).level_order().map(|v| v.depth).collect();
+    assert_eq!(depths, [0, 1, 1, 2, 2, 2, 2]);
+    let post: Vec<_> = Countdown(1).post_order().map(|v| v.node.0).collect();
+    assert_eq!(post, [0, 0, 1]);
+
+    // Only the first three levels of a very deep tree are ever generated.
+    let pruned = Countdown(u32::MAX).prune_depth(2);
+    assert_eq!(pruned.pre_order().count(), 7);
+
+    let path: Vec<_> = Countdown(3)
+        .find_path(|node| node.0 == 0)
+        .unwrap()
+        .into_iter()
+        .map(|node| node.0)
+        .collect();
+    assert_eq!(path, [3, 2, 1, 0]);
+
+    let filtered = Countdown(2).filter(|node| node.0 == 1).unwrap();
+    insta::assert_snapshot!(render(&filtered));
+    insta::assert_snapshot!(render(&Countdown(2).map(|node| format!("n{}", node.0))));
+}
@@ -0,0 +1,183 @@
+#![cfg(feature = "tui")]
+use std::cell::Cell;
+use std::rc::Rc;
+
+use arbor::indents::UnicodeIndent;
+use arbor::protocol::{ILazyTree, ITreeContent};
+use arbor::tui::{FakeBackend, Key, Viewer};
+
+/// Lazy tree of the binary numbers up to 4 digits, counting how many times
+/// children are generated.
+struct Binary {
+    digits: String,
+    loads: Rc<Cell<usize>>,
+}
+impl ITreeContent for Binary {
+    fn content(&self) -> impl AsRef<str> { &self.digits }
+}
+impl ILazyTree for Binary {
+    type Leaf = Binary;
+    type Leaves = std::vec::IntoIter<Binary>;
+    fn leaves(&self) -> Self::Leaves {
+        self.loads.set(self.loads.get() + 1);
+        if self.digits.len() == 4 {
+            return Vec::new().into_iter();
+        }
+        ["0", "1"]
+            .map(|digit| Binary {
+                digits: format!("{}{digit}", self.digits),
+                loads: self.loads.clone(),
+            })
+            .into_iter()
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+fn binary() -> (Binary, Rc<Cell<usize>>) {
+    let loads = Rc::new(Cell::new(0));
+    let tree = Binary {
+        digits: "1".to_string(),
+        loads: loads.clone(),
+    };
+    (tree, loads)
+}
+
+fn run(
+    keys: impl IntoIterator<Item = Key>,
+    height: usize,
+) -> (Viewer<UnicodeIndent, Binary>, FakeBackend) {
+    let (tree, _) = binary();
+    let mut viewer = Viewer::new(tree, UnicodeIndent);
+    let mut backend = FakeBackend::new(40, height, keys);
+    viewer.run(&mut backend).unwrap();
+    (viewer, backend)
+}
+
+#[test]
+fn tui_expand_and_collapse() {
+    let (viewer, backend) = run(
+        [
+            Key::Down,
+            Key::Right,
+            Key::Right,
+            Key::Right,
+            Key::Down,
+            Key::Down,
+            Key::Enter,
+            Key::Left,
+            Key::Left,
+        ],
+        12,
+    );
+    assert_eq!(viewer.selected().digits, "100");
+    let frames: Vec<_> = backend
+        .frames
+        .iter()
+        .map(|frame| frame.join("\n"))
+        .collect();
+    insta::assert_snapshot!(frames.join("\n\n"));
+}
+
+#[test]
+fn tui_loads_children_on_demand() {
+    let (tree, loads) = binary();
+    let mut viewer = Viewer::new(tree, UnicodeIndent);
+    assert_eq!(loads.get(), 1);
+
+    viewer.handle_key(Key::Down);
+    viewer.handle_key(Key::Right);
+    assert_eq!(loads.get(), 2);
+
+    // Collapsing and expanding again reuses the loaded children.
+    viewer.handle_key(Key::Left);
+    viewer.handle_key(Key::Right);
+    assert_eq!(loads.get(), 2);
+}
+
+#[test]
+fn tui_search() {
+    let (viewer, backend) = run(
+        [
+            Key::Down,
+            Key::Right,
+            Key::Up,
+            Key::Left,
+            Key::Char('/'),
+            Key::Char('1'),
+            Key::Char('0'),
+            Key::Enter,
+            Key::Char('n'),
+            Key::Char('n'),
+        ],
+        8,
+    );
+    assert_eq!(viewer.selected().digits, "101");
+    let frames: Vec<_> = backend
+        .frames
+        .iter()
+        .map(|frame| frame.join("\n"))
+        .collect();
+    insta::assert_snapshot!(frames[5..].join("\n\n"));
+}
+
+#[test]
+fn tui_search_cancel() {
+    let (viewer, _) = run(
+        [
+            Key::Down,
+            Key::Right,
+            Key::Char('/'),
+            Key::Char('1'),
+            Key::Char('1'),
+            Key::Esc,
+        ],
+        8,
+    );
+    assert_eq!(viewer.selected().digits, "10");
+}
+
+#[test]
+fn tui_scroll() {
+    let (viewer, backend) = run(
+        [
+            Key::Down,
+            Key::Right,
+            Key::Down,
+            Key::Right,
+            Key::Down,
+            Key::Right,
+            Key::End,
+            Key::PageUp,
+        ],
+        5,
+    );
+    assert_eq!(viewer.selected().digits, "100");
+    insta::assert_snapshot!(
+        backend.frames[6..]
+            .iter()
+            .map(|frame| frame.join("\n"))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    );
+}
+
+#[test]
+#[cfg(feature = "textwrap-unicode-width")]
+fn tui_truncates_wide_characters() {
+    /// Leaf whose content is made of wide characters.
+    struct Wide;
+    impl ITreeContent for Wide {
+        fn content(&self) -> impl AsRef<str> { "日本語のファイル名🎉" }
+    }
+    impl ILazyTree for Wide {
+        type Leaf = Wide;
+        type Leaves = std::iter::Empty<Wide>;
+        fn leaves(&self) -> Self::Leaves { std::iter::empty() }
+    }
+
+    let mut viewer = Viewer::new(Wide, UnicodeIndent);
+    let lines = viewer.render(11, 2);
+    // The cursor and the leaf marker take 4 cells and each ideograph 2, so
+    // a fourth ideograph would end in the 12th cell.
+    assert_eq!(lines[0], ">   日本語");
+}
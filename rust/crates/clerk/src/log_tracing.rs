@@ -3,14 +3,20 @@ mod fast_setup;
 mod filter;
 mod formatter;
 mod layer;
+mod rolling;
 
 pub use error::ClerkError;
 pub use fast_setup::init_log_with_level;
 pub use filter::NotInSpanFilter;
-pub use formatter::{ClerkFormatter, FormatEventToWriter, TIME_FORMAT};
-pub use layer::{file_layer, terminal_layer};
+pub use formatter::{
+    ClerkFormat, ClerkFormatter, ClerkJsonFields, ClerkJsonFormatter, ClerkLogfmtFormatter,
+    FormatEventToWriter, TIME_FORMAT,
+};
+pub use layer::{ClerkLayerBuilder, file_layer, terminal_layer};
+pub use rolling::{RollingFileWriter, Rotation, RotationPolicy};
 // re-export tracing
 pub use tracing;
 pub use tracing_core;
 pub use tracing_subscriber;
 pub use tracing_subscriber::filter::LevelFilter;
+pub use tracing_subscriber::fmt::format::FmtSpan;
@@ -1,15 +1,38 @@
+mod json;
+mod logfmt;
 use std::io;
 
+pub use json::{ClerkJsonFields, ClerkJsonFormatter};
+pub use logfmt::ClerkLogfmtFormatter;
 use owo_colors::OwoColorize;
 use tracing::{Event, Level};
 use tracing_core::Subscriber;
 use tracing_core::field::Visit;
-use tracing_subscriber::fmt::FmtContext;
+#[cfg(feature = "mischief")]
+use tracing_subscriber::field::VisitOutput;
+#[cfg(feature = "mischief")]
+use tracing_subscriber::fmt::format::DefaultVisitor;
 use tracing_subscriber::fmt::format::{FormatEvent, FormatFields, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormattedFields};
 use tracing_subscriber::registry::LookupSpan;
 
+/// Format of the timestamps of events, which are in UTC.
 pub const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6fZ";
 
+/// Output format of the layers built by [`crate::ClerkLayerBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ClerkFormat {
+    /// Human-readable lines of [`ClerkFormatter`].
+    #[default]
+    Text,
+
+    /// Newline-delimited JSON of [`ClerkJsonFormatter`].
+    Json,
+
+    /// logfmt lines of [`ClerkLogfmtFormatter`].
+    Logfmt,
+}
+
 /// Formatter for `tracing` events used by `tracing_subscriber::fmt`.
 ///
 /// Output format:
@@ -25,12 +48,71 @@ pub const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6fZ";
 /// ```
 ///
 /// If `color` is enabled, log levels are colorized using `owo_colors`.
+///
+/// With `span_breadcrumb`, the spans of the event are written before the
+/// message, with their fields, and with `inherit_span_fields` the fields of
+/// the spans are appended to the event fields:
+///
+/// ```text
+/// [timestamp][INFO][kioyu::job] kioyu-job{job.id=7 job.name="build"}: started
+/// [timestamp][INFO][kioyu::job] started job.id=7 job.name="build"
+/// ```
+///
+/// Span events enabled with `with_span_events` on the layer, such as the
+/// `close` event carrying the `time.busy` and `time.idle` durations of a
+/// span, are formatted as events of that span, see
+/// [`crate::ClerkLayerBuilder::with_span_events`]. Events formatted with
+/// [`FormatEventToWriter`] have no span context, so spans are not written.
+///
+/// Errors are written as their message and the messages of their sources.
+/// With the `mischief` feature, the `mischief` errors of colored events,
+/// such as `error = report.as_error()`, are rendered as diagnoses instead,
+/// so terminals get the rendered report and uncolored log files its plain
+/// chain.
+///
+/// Fields may be added in future versions, so the formatter is created with
+/// [`ClerkFormatter::new`] and its `with_*` methods:
+///
+/// ```
+/// let formatter = clerk::ClerkFormatter::new(true).with_span_breadcrumb(true);
+/// assert!(formatter.color && formatter.span_breadcrumb);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
 pub struct ClerkFormatter {
     /// Enable colored level output.
     pub color: bool,
+
+    /// Write the spans of the event before its message.
+    pub span_breadcrumb: bool,
+
+    /// Append the fields of the spans of the event to its fields.
+    pub inherit_span_fields: bool,
 }
 
 impl ClerkFormatter {
+    /// Creates a formatter without span rendering.
+    pub const fn new(color: bool) -> Self {
+        Self {
+            color,
+            span_breadcrumb: false,
+            inherit_span_fields: false,
+        }
+    }
+
+    /// Sets whether to write the spans of the event before its message.
+    pub const fn with_span_breadcrumb(mut self, span_breadcrumb: bool) -> Self {
+        self.span_breadcrumb = span_breadcrumb;
+        self
+    }
+
+    /// Sets whether to append the fields of the spans of the event to its
+    /// fields.
+    pub const fn with_inherit_span_fields(mut self, inherit_span_fields: bool) -> Self {
+        self.inherit_span_fields = inherit_span_fields;
+        self
+    }
+
     fn color_level(&self, level: Level) -> String {
         if !self.color {
             return level.to_string();
@@ -51,7 +133,7 @@ macro_rules! write_header {
         let _ = write!(
             $writer,
             "[{}][{}][{}]",
-            jiff::Zoned::now().strftime(TIME_FORMAT),
+            jiff::Timestamp::now().strftime(TIME_FORMAT),
             $self.color_level(*$meta.level()),
             $meta.target(),
         );
@@ -60,7 +142,7 @@ macro_rules! write_header {
         let _ = write!(
             $writer,
             "[{}][{}][{}][{}:{}] ",
-            jiff::Zoned::now().strftime(TIME_FORMAT),
+            jiff::Timestamp::now().strftime(TIME_FORMAT),
             $self.color_level(*$meta.level()),
             $meta.target(),
             $meta.file().unwrap_or("<file>"),
@@ -68,6 +150,29 @@ macro_rules! write_header {
         );
     }};
 }
+impl ClerkFormatter {
+    /// Writes the fields of an event with the field formatter of the layer,
+    /// or rendering its `mischief` errors if colored.
+    fn write_fields<S, N>(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> core::fmt::Result
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+        N: for<'a> FormatFields<'a> + 'static,
+    {
+        #[cfg(feature = "mischief")]
+        if self.color {
+            let mut visitor = DiagnosisVisitor(DefaultVisitor::new(writer, true));
+            event.record(&mut visitor);
+            return visitor.0.finish();
+        }
+        ctx.field_format().format_fields(writer, event)
+    }
+}
+
 impl<S, N> FormatEvent<S, N> for ClerkFormatter
 where
     S: Subscriber + for<'a> LookupSpan<'a>,
@@ -80,7 +185,38 @@ where
         event: &Event<'_>,
     ) -> core::fmt::Result {
         write_header!(self, writer, event.metadata());
-        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        if self.span_breadcrumb
+            && let Some(scope) = ctx.event_scope()
+        {
+            for span in scope.from_root() {
+                if self.color {
+                    write!(writer, "{}", span.name().bold())?;
+                } else {
+                    write!(writer, "{}", span.name())?;
+                }
+                let extensions = span.extensions();
+                if let Some(fields) = extensions.get::<FormattedFields<N>>()
+                    && !fields.is_empty()
+                {
+                    write!(writer, "{{{fields}}}")?;
+                }
+                write!(writer, ":")?;
+            }
+            write!(writer, " ")?;
+        }
+        self.write_fields(ctx, writer.by_ref(), event)?;
+        if self.inherit_span_fields
+            && let Some(scope) = ctx.event_scope()
+        {
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                if let Some(fields) = extensions.get::<FormattedFields<N>>()
+                    && !fields.is_empty()
+                {
+                    write!(writer, " {fields}")?;
+                }
+            }
+        }
         writeln!(writer)
     }
 }
@@ -98,7 +234,11 @@ impl FormatEventToWriter for ClerkFormatter {
         W: io::Write,
     {
         write_header!(self, writer, event.metadata());
-        let mut visitor = WriterFieldVisitor { writer };
+        let mut visitor = WriterFieldVisitor {
+            writer,
+            #[cfg(feature = "mischief")]
+            color: self.color,
+        };
         event.record(&mut visitor);
         let _ = writeln!(writer);
     }
@@ -106,6 +246,8 @@ impl FormatEventToWriter for ClerkFormatter {
 
 struct WriterFieldVisitor<'a, W: io::Write> {
     writer: &'a mut W,
+    #[cfg(feature = "mischief")]
+    color: bool,
 }
 
 impl<W: io::Write> Visit for WriterFieldVisitor<'_, W> {
@@ -124,4 +266,230 @@ impl<W: io::Write> Visit for WriterFieldVisitor<'_, W> {
             let _ = write!(self.writer, " {}={:?}", field.name(), value);
         }
     }
+
+    fn record_error(
+        &mut self,
+        field: &tracing_core::Field,
+        value: &(dyn std::error::Error + 'static),
+    ) {
+        #[cfg(feature = "mischief")]
+        if self.color
+            && let Some(error) = value.downcast_ref::<mischief::MischiefError>()
+        {
+            let rendered = Diagnosis(error).to_string();
+            let _ = write!(self.writer, " {}=\n{}", field.name(), rendered.trim_end());
+            return;
+        }
+        let _ = write!(self.writer, " {}={}", field.name(), value);
+        let sources = error_sources(value);
+        if !sources.is_empty() {
+            let _ = write!(
+                self.writer,
+                " {}.sources=[{}]",
+                field.name(),
+                sources.join(", ")
+            );
+        }
+    }
+}
+
+/// Visitor writing fields like `DefaultFields`, but rendering `mischief`
+/// errors as diagnoses.
+#[cfg(feature = "mischief")]
+struct DiagnosisVisitor<'a>(DefaultVisitor<'a>);
+
+#[cfg(feature = "mischief")]
+impl Visit for DiagnosisVisitor<'_> {
+    fn record_str(&mut self, field: &tracing_core::Field, value: &str) {
+        self.0.record_str(field, value);
+    }
+
+    fn record_debug(&mut self, field: &tracing_core::Field, value: &dyn core::fmt::Debug) {
+        self.0.record_debug(field, value);
+    }
+
+    fn record_error(
+        &mut self,
+        field: &tracing_core::Field,
+        value: &(dyn std::error::Error + 'static),
+    ) {
+        match value.downcast_ref::<mischief::MischiefError>() {
+            Some(error) => {
+                let rendered = Diagnosis(error).to_string();
+                self.0
+                    .record_debug(field, &format_args!("\n{}", rendered.trim_end()));
+            }
+            None => self.0.record_error(field, value),
+        }
+    }
+}
+
+/// Displays a `mischief` error with its default renderer, ending with blank
+/// lines that are trimmed before writing it into a line.
+#[cfg(feature = "mischief")]
+struct Diagnosis<'a>(&'a mischief::MischiefError);
+
+#[cfg(feature = "mischief")]
+impl core::fmt::Display for Diagnosis<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        mischief::render_diagnosis(self.0, f)
+    }
+}
+
+/// Returns the messages of the sources of an error, from its cause.
+///
+/// Errors are recorded as their message and these sources, like
+/// `tracing_subscriber::fmt` does, rather than as their `Debug` output.
+pub(crate) fn error_sources(error: &(dyn std::error::Error + 'static)) -> Vec<String> {
+    core::iter::successors(error.source(), |error| error.source())
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::Layer;
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+
+    use super::*;
+
+    /// Writer collecting formatted events.
+    #[derive(Clone, Default)]
+    pub(crate) struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Buffer {
+        pub(crate) fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    fn format_with_spans(formatter: ClerkFormatter) -> String {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+                .with_ansi(false)
+                .event_format(formatter)
+                .with_writer(move || writer.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let _run = tracing::info_span!("run").entered();
+            let _job = tracing::info_span!("kioyu-job", job.id = 7, job.name = "build").entered();
+            tracing::info!(attempt = 1, "started");
+        });
+        buffer.contents()
+    }
+
+    #[test]
+    fn test_span_breadcrumb() {
+        let lines = format_with_spans(ClerkFormatter::new(false).with_span_breadcrumb(true));
+        let lines: Vec<_> = lines.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(
+            lines[0].ends_with(" run:kioyu-job{job.id=7 job.name=\"build\"}: started attempt=1")
+        );
+        assert!(lines[1].contains(" run:kioyu-job{job.id=7 job.name=\"build\"}: close time.busy="));
+        assert!(lines[2].contains(" run: close time.busy="));
+    }
+
+    #[test]
+    fn test_inherit_span_fields() {
+        let lines = format_with_spans(ClerkFormatter::new(false).with_inherit_span_fields(true));
+        let first = lines.lines().next().unwrap();
+        assert!(first.ends_with("] started attempt=1 job.id=7 job.name=\"build\""));
+        assert!(!first.contains("kioyu-job"));
+    }
+
+    /// Error with an optional cause.
+    #[derive(Debug)]
+    pub(crate) struct Chain(pub(crate) &'static str, pub(crate) Option<Box<Chain>>);
+
+    impl core::fmt::Display for Chain {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { f.write_str(self.0) }
+    }
+
+    impl std::error::Error for Chain {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            self.1
+                .as_deref()
+                .map(|e| e as &(dyn std::error::Error + 'static))
+        }
+    }
+
+    #[test]
+    fn test_error_to_writer() {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(WriterLayer(ClerkFormatter::new(false), buffer.clone()));
+        let error = Chain(
+            "job failed",
+            Some(Box::new(Chain(
+                "connection lost",
+                Some(Box::new(Chain("port closed", None))),
+            ))),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::error!(error = &error as &dyn std::error::Error, "execute failed");
+        });
+        assert!(buffer.contents().ends_with(
+            " execute failed error=job failed error.sources=[connection lost, port closed]\n"
+        ));
+    }
+
+    #[cfg(feature = "mischief")]
+    #[test]
+    fn test_mischief_error_rendered_when_colored() {
+        let format = |color: bool| {
+            let buffer = Buffer::default();
+            let writer = buffer.clone();
+            let subscriber = tracing_subscriber::registry().with(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(color)
+                    .event_format(ClerkFormatter::new(color))
+                    .with_writer(move || writer.clone()),
+            );
+            let report = mischief::Report::from_error(Chain(
+                "job failed",
+                Some(Box::new(Chain("connection lost", None))),
+            ));
+            tracing::subscriber::with_default(subscriber, || {
+                tracing::error!(error = report.as_error(), "execute failed");
+            });
+            buffer.contents()
+        };
+        let plain = format(false);
+        assert!(
+            plain.ends_with(" execute failed error=job failed error.sources=[connection lost]\n")
+        );
+        let colored = format(true);
+        assert!(colored.contains(" execute failed "));
+        assert!(colored.ends_with("connection lost\n"));
+        assert!(!colored.contains("error.sources"));
+    }
+
+    /// Layer formatting events with [`FormatEventToWriter`], as kioyu's job
+    /// files do.
+    pub(crate) struct WriterLayer<F>(pub(crate) F, pub(crate) Buffer);
+
+    impl<S, F> Layer<S> for WriterLayer<F>
+    where
+        S: Subscriber,
+        F: FormatEventToWriter + 'static,
+    {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            self.0.format_to_writer(&mut self.1.clone(), event);
+        }
+    }
 }
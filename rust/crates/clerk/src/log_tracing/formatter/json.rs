@@ -0,0 +1,252 @@
+use std::io;
+
+use serde_json::{Map, Value, json};
+use tracing::Event;
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::Record;
+use tracing_core::{Metadata, Subscriber};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::{FormatEvent, FormatFields, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+
+use super::{FormatEventToWriter, TIME_FORMAT, error_sources};
+
+/// Formatter writing `tracing` events as newline-delimited JSON.
+///
+/// Each event is one object:
+///
+/// ```text
+/// {"fields":{"job":42},"file":"src/main.rs","level":"INFO","line":12,
+///  "message":"started","spans":[{"fields":{"id":7},"name":"request"}],
+///  "target":"app","timestamp":"2024-01-15T14:30:00.123456Z"}
+/// ```
+///
+/// `spans` lists the spans of the event from the root one. Span fields are
+/// objects when the layer formats them with [`ClerkJsonFields`], as the
+/// layers of [`crate::ClerkLayerBuilder`] do, and strings otherwise. Events
+/// formatted with [`FormatEventToWriter`] have no span context, so `spans` is
+/// omitted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClerkJsonFormatter;
+
+impl ClerkJsonFormatter {
+    /// Builds the object of an event, without its spans.
+    fn event_json(event: &Event<'_>) -> Value {
+        let meta: &Metadata<'_> = event.metadata();
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+        let message = fields.remove("message").unwrap_or(Value::Null);
+        json!({
+            "timestamp": jiff::Timestamp::now().strftime(TIME_FORMAT).to_string(),
+            "level": meta.level().as_str(),
+            "target": meta.target(),
+            "file": meta.file(),
+            "line": meta.line(),
+            "message": message,
+            "fields": fields,
+        })
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for ClerkJsonFormatter
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> core::fmt::Result {
+        let mut line = Self::event_json(event);
+        if let Some(scope) = ctx.event_scope() {
+            let spans = scope
+                .from_root()
+                .map(|span| {
+                    let extensions = span.extensions();
+                    let fields = extensions
+                        .get::<FormattedFields<N>>()
+                        .map_or_else(|| Value::Object(Map::new()), |f| span_fields(&f.fields));
+                    json!({ "name": span.name(), "fields": fields })
+                })
+                .collect();
+            line["spans"] = Value::Array(spans);
+        }
+        writeln!(writer, "{line}")
+    }
+}
+
+impl FormatEventToWriter for ClerkJsonFormatter {
+    fn format_to_writer<W>(&self, writer: &mut W, event: &Event<'_>)
+    where
+        W: io::Write,
+    {
+        let _ = writeln!(writer, "{}", Self::event_json(event));
+    }
+}
+
+/// Parses span fields formatted by a layer, keeping them as a string if
+/// they are not a JSON object.
+fn span_fields(fields: &str) -> Value {
+    if fields.is_empty() {
+        return Value::Object(Map::new());
+    }
+    serde_json::from_str(fields)
+        .ok()
+        .filter(Value::is_object)
+        .unwrap_or_else(|| Value::from(fields))
+}
+
+/// Field formatter storing span fields as a JSON object, so that
+/// [`ClerkJsonFormatter`] can nest them in its output.
+///
+/// # Example
+///
+/// ```
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let subscriber = tracing_subscriber::registry().with(
+///     tracing_subscriber::fmt::layer()
+///         .fmt_fields(clerk::ClerkJsonFields)
+///         .event_format(clerk::ClerkJsonFormatter),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClerkJsonFields;
+
+impl<'writer> FormatFields<'writer> for ClerkJsonFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> core::fmt::Result {
+        let mut map = Map::new();
+        fields.record(&mut JsonVisitor(&mut map));
+        write!(writer, "{}", Value::Object(map))
+    }
+
+    fn add_fields(
+        &self,
+        current: &mut FormattedFields<Self>,
+        fields: &Record<'_>,
+    ) -> core::fmt::Result {
+        let mut map = match serde_json::from_str(&current.fields) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        fields.record(&mut JsonVisitor(&mut map));
+        current.fields = Value::Object(map).to_string();
+        Ok(())
+    }
+}
+
+/// Collects fields into a JSON object, keeping numbers and booleans.
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_owned(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_owned(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_owned(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_owned(), Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), Value::from(value));
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.0
+            .insert(field.name().to_owned(), Value::from(value.to_string()));
+        let sources = error_sources(value);
+        if !sources.is_empty() {
+            self.0
+                .insert(format!("{}.sources", field.name()), Value::from(sources));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn core::fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), Value::from(format!("{value:?}")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::log_tracing::formatter::tests::{Buffer, Chain, WriterLayer};
+
+    #[test]
+    fn test_json_event() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .fmt_fields(ClerkJsonFields)
+                .event_format(ClerkJsonFormatter)
+                .with_writer(move || writer.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request", id = 7, path = "/jobs");
+            span.record("id", 8);
+            span.in_scope(|| tracing::info!(job = 42, done = false, "started {}", "job"));
+        });
+        let line: Value = serde_json::from_str(&buffer.contents()).unwrap();
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["target"], "clerk::log_tracing::formatter::json::tests");
+        assert_eq!(line["message"], "started job");
+        assert_eq!(line["fields"], json!({ "job": 42, "done": false }));
+        assert_eq!(
+            line["spans"],
+            json!([{ "name": "request", "fields": { "id": 8, "path": "/jobs" } }])
+        );
+        assert!(line["file"].as_str().unwrap().ends_with("json.rs"));
+        assert!(line["line"].is_u64());
+        let timestamp: jiff::Timestamp = line["timestamp"].as_str().unwrap().parse().unwrap();
+        assert!(jiff::Timestamp::now().duration_since(timestamp).as_secs() < 60);
+    }
+
+    #[test]
+    fn test_json_to_writer() {
+        let buffer = Buffer::default();
+        let subscriber =
+            tracing_subscriber::registry().with(WriterLayer(ClerkJsonFormatter, buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(path = "a \"b\"", "disk full");
+        });
+        let line: Value = serde_json::from_str(&buffer.contents()).unwrap();
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["message"], "disk full");
+        assert_eq!(line["fields"], json!({ "path": "a \"b\"" }));
+        assert!(line.get("spans").is_none());
+    }
+
+    #[test]
+    fn test_json_error() {
+        let buffer = Buffer::default();
+        let subscriber =
+            tracing_subscriber::registry().with(WriterLayer(ClerkJsonFormatter, buffer.clone()));
+        let error = Chain("job failed", Some(Box::new(Chain("port closed", None))));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::error!(error = &error as &dyn std::error::Error, "execute failed");
+        });
+        let line: Value = serde_json::from_str(&buffer.contents()).unwrap();
+        assert_eq!(
+            line["fields"],
+            json!({ "error": "job failed", "error.sources": ["port closed"] })
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::io;
+
+use tracing::Event;
+use tracing_core::Subscriber;
+use tracing_core::field::{Field, Visit};
+use tracing_subscriber::fmt::FmtContext;
+use tracing_subscriber::fmt::format::{FormatEvent, FormatFields, Writer};
+use tracing_subscriber::registry::LookupSpan;
+
+use super::{FormatEventToWriter, TIME_FORMAT, error_sources};
+
+/// Formatter writing `tracing` events as [logfmt](https://brandur.org/logfmt)
+/// lines.
+///
+/// Output format:
+///
+/// ```text
+/// ts=2024-01-15T14:30:00.123456Z level=INFO target=app file=src/main.rs line=12 span=request:job msg="job started" job=42
+/// ```
+///
+/// `span` is the breadcrumb of the spans of the event, from the root one.
+/// Events formatted with [`FormatEventToWriter`] have no span context, so
+/// `span` is omitted. Values are quoted when they contain spaces, quotes,
+/// `=` or control characters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClerkLogfmtFormatter;
+
+impl ClerkLogfmtFormatter {
+    /// Builds the line of an event, without trailing newline.
+    fn event_line(event: &Event<'_>, span: Option<&str>) -> String {
+        let meta = event.metadata();
+        let mut line = String::new();
+        write_pair(
+            &mut line,
+            "ts",
+            &jiff::Timestamp::now().strftime(TIME_FORMAT).to_string(),
+        );
+        write_pair(&mut line, "level", meta.level().as_str());
+        write_pair(&mut line, "target", meta.target());
+        if let Some(file) = meta.file() {
+            write_pair(&mut line, "file", file);
+        }
+        if let Some(number) = meta.line() {
+            write_pair(&mut line, "line", &number.to_string());
+        }
+        if let Some(span) = span {
+            write_pair(&mut line, "span", span);
+        }
+
+        let mut visitor = LogfmtVisitor {
+            message: None,
+            fields: String::new(),
+        };
+        event.record(&mut visitor);
+        if let Some(message) = visitor.message {
+            write_pair(&mut line, "msg", &message);
+        }
+        if !visitor.fields.is_empty() {
+            line.push(' ');
+            line.push_str(&visitor.fields);
+        }
+        line
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for ClerkLogfmtFormatter
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> core::fmt::Result {
+        let span = ctx.event_scope().map(|scope| {
+            scope
+                .from_root()
+                .map(|span| span.name())
+                .collect::<Vec<_>>()
+                .join(":")
+        });
+        writeln!(writer, "{}", Self::event_line(event, span.as_deref()))
+    }
+}
+
+impl FormatEventToWriter for ClerkLogfmtFormatter {
+    fn format_to_writer<W>(&self, writer: &mut W, event: &Event<'_>)
+    where
+        W: io::Write,
+    {
+        let _ = writeln!(writer, "{}", Self::event_line(event, None));
+    }
+}
+
+/// Appends a `key=value` pair, separated from the previous one by a
+/// space.
+fn write_pair(line: &mut String, key: &str, value: &str) {
+    if !line.is_empty() {
+        line.push(' ');
+    }
+    line.push_str(key);
+    line.push('=');
+    let quote = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c == ' ' || c == '"' || c == '=' || c.is_control());
+    if !quote {
+        line.push_str(value);
+        return;
+    }
+    line.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            c => line.push(c),
+        }
+    }
+    line.push('"');
+}
+
+/// Collects the message and the other fields of an event.
+struct LogfmtVisitor {
+    message: Option<String>,
+    fields: String,
+}
+
+impl Visit for LogfmtVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_owned());
+        } else {
+            write_pair(&mut self.fields, field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn core::fmt::Debug) {
+        self.record_str(field, &format!("{value:?}"));
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        write_pair(&mut self.fields, field.name(), &value.to_string());
+        let sources = error_sources(value);
+        if !sources.is_empty() {
+            write_pair(
+                &mut self.fields,
+                &format!("{}.sources", field.name()),
+                &format!("[{}]", sources.join(", ")),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::log_tracing::formatter::tests::{Buffer, Chain, WriterLayer};
+
+    #[test]
+    fn test_logfmt_event() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .event_format(ClerkLogfmtFormatter)
+                .with_writer(move || writer.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let _request = tracing::info_span!("request").entered();
+            let _job = tracing::info_span!("job").entered();
+            tracing::info!(job = 42, name = "nightly build", "job started");
+        });
+        let line = buffer.contents();
+        assert!(line.starts_with("ts="));
+        assert!(line.contains(" level=INFO target=clerk::log_tracing::formatter::logfmt::tests "));
+        assert!(line.contains(" file=crates/clerk/src/log_tracing/formatter/logfmt.rs line="));
+        assert!(
+            line.ends_with(" span=request:job msg=\"job started\" job=42 name=\"nightly build\"\n")
+        );
+    }
+
+    #[test]
+    fn test_logfmt_to_writer() {
+        let buffer = Buffer::default();
+        let subscriber =
+            tracing_subscriber::registry().with(WriterLayer(ClerkLogfmtFormatter, buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::error!(output = "a=\"b\"\n", empty = "", "failed");
+        });
+        let line = buffer.contents();
+        assert!(!line.contains(" span="));
+        assert!(line.ends_with(" msg=failed output=\"a=\\\"b\\\"\\n\" empty=\"\"\n"));
+    }
+
+    #[test]
+    fn test_logfmt_error() {
+        let buffer = Buffer::default();
+        let subscriber =
+            tracing_subscriber::registry().with(WriterLayer(ClerkLogfmtFormatter, buffer.clone()));
+        let error = Chain("job failed", Some(Box::new(Chain("port closed", None))));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::error!(error = &error as &dyn std::error::Error, "execute failed");
+        });
+        assert!(buffer.contents().ends_with(
+            " msg=\"execute failed\" error=\"job failed\" error.sources=\"[port closed]\"\n"
+        ));
+    }
+}
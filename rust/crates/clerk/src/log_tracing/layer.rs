@@ -1,9 +1,13 @@
 use std::path::Path;
 
 use tracing_subscriber::Layer;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::registry::LookupSpan;
 
 use crate::log_tracing::error::ClerkError;
+use crate::log_tracing::rolling::{RollingFileWriter, RotationPolicy};
+use crate::{ClerkFormat, ClerkFormatter};
 
 /// Generate a terminal log layer for tracing.
 ///
@@ -35,9 +39,7 @@ where
     S: tracing_core::Subscriber,
     for<'a> S: LookupSpan<'a>,
 {
-    tracing_subscriber::fmt::layer()
-        .event_format(crate::ClerkFormatter { color })
-        .with_writer(std::io::stderr)
+    ClerkLayerBuilder::new().with_color(color).terminal()
 }
 
 /// Generate a file log layer for tracing.
@@ -88,7 +90,182 @@ where
     S: tracing_core::Subscriber,
     for<'a> S: LookupSpan<'a>,
 {
-    let filepath = filepath.as_ref();
+    ClerkLayerBuilder::new().file(filepath, overwrite)
+}
+
+/// Builder of a log layer for tracing, choosing the format of the events,
+/// how their spans are written, and where the events are written.
+///
+/// [`terminal_layer`] and [`file_layer`] are shorthands for the default
+/// text format.
+///
+/// # Example
+///
+/// ```
+/// use clerk::{ClerkFormat, ClerkLayerBuilder, FmtSpan, LevelFilter, Rotation, RotationPolicy};
+/// use tracing::info;
+/// use tracing_subscriber::Layer;
+/// use tracing_subscriber::layer::SubscriberExt;
+/// use tracing_subscriber::util::SubscriberInitExt;
+///
+/// let policy = RotationPolicy {
+///     rotation: Rotation::Size(10 * 1024 * 1024),
+///     keep: Some(5),
+///     compress: true,
+/// };
+/// tracing_subscriber::registry()
+///     .with(
+///         ClerkLayerBuilder::new()
+///             .with_color(true)
+///             .with_span_breadcrumb(true)
+///             .terminal()
+///             .with_filter(LevelFilter::INFO),
+///     )
+///     .with(
+///         ClerkLayerBuilder::new()
+///             .with_format(ClerkFormat::Json)
+///             .with_span_events(FmtSpan::CLOSE)
+///             .rolling_file("./temp/rolling.jsonl", policy)
+///             .unwrap()
+///             .with_filter(LevelFilter::TRACE),
+///     )
+///     .init();
+///
+/// tracing::info_span!("job", id = 42).in_scope(|| info!("Informational message"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClerkLayerBuilder {
+    format: ClerkFormat,
+    formatter: ClerkFormatter,
+    span_events: FmtSpan,
+}
+
+impl Default for ClerkLayerBuilder {
+    fn default() -> Self { Self::new() }
+}
+
+impl ClerkLayerBuilder {
+    /// Creates a builder of uncolored [`ClerkFormat::Text`] layers without
+    /// spans nor span events.
+    pub const fn new() -> Self {
+        Self {
+            format: ClerkFormat::Text,
+            formatter: ClerkFormatter::new(false),
+            span_events: FmtSpan::NONE,
+        }
+    }
+
+    /// Sets the output format of the events.
+    ///
+    /// With the `mischief` feature, the span traces of reports keep the span
+    /// fields of [`ClerkFormat::Json`] layers, see
+    /// [`mischief::SpanTrace::add_field_format`].
+    pub const fn with_format(mut self, format: ClerkFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Colorizes the levels of [`ClerkFormat::Text`] events and the span
+    /// names of their breadcrumbs.
+    pub const fn with_color(mut self, color: bool) -> Self {
+        self.formatter.color = color;
+        self
+    }
+
+    /// Writes the spans of [`ClerkFormat::Text`] events before their
+    /// message, see [`ClerkFormatter`]. JSON and logfmt events always list
+    /// their spans.
+    pub const fn with_span_breadcrumb(mut self, span_breadcrumb: bool) -> Self {
+        self.formatter = self.formatter.with_span_breadcrumb(span_breadcrumb);
+        self
+    }
+
+    /// Appends the fields of the spans of [`ClerkFormat::Text`] events to
+    /// their fields, see [`ClerkFormatter`].
+    pub const fn with_inherit_span_fields(mut self, inherit_span_fields: bool) -> Self {
+        self.formatter = self.formatter.with_inherit_span_fields(inherit_span_fields);
+        self
+    }
+
+    /// Sets the span lifecycle events to log, e.g. [`FmtSpan::CLOSE`] to
+    /// log the duration of each span.
+    pub fn with_span_events(mut self, span_events: FmtSpan) -> Self {
+        self.span_events = span_events;
+        self
+    }
+
+    /// Builds a layer writing events to stderr.
+    pub fn terminal<S>(self) -> impl Layer<S> + Send + Sync
+    where
+        S: tracing_core::Subscriber,
+        for<'a> S: LookupSpan<'a>,
+    {
+        self.writer(std::io::stderr)
+    }
+
+    /// Builds a layer writing events into the file at `filepath`, creating
+    /// its parent directory if needed.
+    ///
+    /// The file is truncated if `overwrite` is set, and appended to
+    /// otherwise.
+    pub fn file<F, S>(
+        self,
+        filepath: F,
+        overwrite: bool,
+    ) -> Result<impl Layer<S> + Send + Sync, ClerkError>
+    where
+        F: AsRef<Path>,
+        S: tracing_core::Subscriber,
+        for<'a> S: LookupSpan<'a>,
+    {
+        let file = open_log_file(filepath.as_ref(), overwrite)?;
+        Ok(self.writer(file))
+    }
+
+    /// Builds a layer writing events into the file at `filepath`, rotated
+    /// according to `policy`, see [`RollingFileWriter`].
+    pub fn rolling_file<F, S>(
+        self,
+        filepath: F,
+        policy: RotationPolicy,
+    ) -> Result<impl Layer<S> + Send + Sync, ClerkError>
+    where
+        F: AsRef<Path>,
+        S: tracing_core::Subscriber,
+        for<'a> S: LookupSpan<'a>,
+    {
+        let writer = RollingFileWriter::new(filepath, policy)?;
+        Ok(self.writer(writer))
+    }
+
+    /// Builds a layer writing events into `writer`.
+    pub fn writer<S, W>(self, writer: W) -> impl Layer<S> + Send + Sync
+    where
+        S: tracing_core::Subscriber,
+        for<'a> S: LookupSpan<'a>,
+        W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+    {
+        let layer = tracing_subscriber::fmt::layer()
+            .with_span_events(self.span_events)
+            .with_ansi(self.formatter.color)
+            .with_writer(writer);
+        match self.format {
+            ClerkFormat::Text => layer.event_format(self.formatter).boxed(),
+            ClerkFormat::Json => {
+                #[cfg(feature = "mischief")]
+                mischief::SpanTrace::add_field_format::<crate::ClerkJsonFields>();
+                layer
+                    .fmt_fields(crate::ClerkJsonFields)
+                    .event_format(crate::ClerkJsonFormatter)
+                    .boxed()
+            }
+            ClerkFormat::Logfmt => layer.event_format(crate::ClerkLogfmtFormatter).boxed(),
+        }
+    }
+}
+
+/// Opens a log file, creating its parent directory if needed.
+pub(crate) fn open_log_file(filepath: &Path, overwrite: bool) -> Result<std::fs::File, ClerkError> {
     if !filepath
         .parent()
         .ok_or_else(|| ClerkError::ParentDirectoryNotFound(filepath.to_owned()))?
@@ -106,11 +283,7 @@ where
         .append(!overwrite)
         .create(true)
         .open(filepath)?;
-
-    let layer = tracing_subscriber::fmt::layer()
-        .event_format(crate::ClerkFormatter { color: false })
-        .with_writer(file);
-    Ok(layer)
+    Ok(file)
 }
 #[cfg(test)]
 mod tests {
@@ -127,6 +300,18 @@ mod tests {
         tracing_subscriber::registry()
             .with(file_layer(f1, true)?.with_filter(crate::LevelFilter::TRACE))
             .with(file_layer(f2, false)?.with_filter(crate::LevelFilter::TRACE))
+            .with(
+                ClerkLayerBuilder::new()
+                    .with_format(ClerkFormat::Json)
+                    .file("./temp/c.jsonl", true)?
+                    .with_filter(crate::LevelFilter::TRACE),
+            )
+            .with(
+                ClerkLayerBuilder::new()
+                    .with_format(ClerkFormat::Logfmt)
+                    .file("./temp/d.log", true)?
+                    .with_filter(crate::LevelFilter::TRACE),
+            )
             .init();
         trace!("Trace message");
         debug!("Debug message");
@@ -146,4 +331,68 @@ mod tests {
         warn!("Warning message");
         error!("Error message");
     }
+
+    #[test]
+    fn test_builder_json_span_events() {
+        use crate::log_tracing::formatter::tests::Buffer;
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            ClerkLayerBuilder::new()
+                .with_format(ClerkFormat::Json)
+                .with_span_events(FmtSpan::CLOSE)
+                .writer(move || writer.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("job", id = 7).in_scope(|| info!("started"));
+        });
+        let lines: Vec<serde_json::Value> = buffer
+            .contents()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["message"], "started");
+        assert_eq!(lines[1]["message"], "close");
+        assert_eq!(lines[1]["spans"][0]["name"], "job");
+        assert!(lines[1]["fields"]["time.busy"].is_string());
+    }
+
+    #[cfg(feature = "mischief")]
+    #[test]
+    fn test_builder_json_span_trace_fields() {
+        use crate::log_tracing::formatter::tests::Buffer;
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            ClerkLayerBuilder::new()
+                .with_format(ClerkFormat::Json)
+                .writer(move || writer.clone()),
+        );
+        let report = tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("job", id = 7).in_scope(|| mischief::mischief!("job failed"))
+        });
+        let span_trace = report.span_trace().unwrap().to_string();
+        assert!(span_trace.contains("::job with {\"id\":7}"));
+    }
+
+    #[test]
+    fn test_builder_text_spans() {
+        use crate::log_tracing::formatter::tests::Buffer;
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            ClerkLayerBuilder::new()
+                .with_span_breadcrumb(true)
+                .with_inherit_span_fields(true)
+                .writer(move || writer.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("job", id = 7).in_scope(|| info!("started"));
+        });
+        assert!(buffer.contents().ends_with(" job{id=7}: started id=7\n"));
+    }
 }
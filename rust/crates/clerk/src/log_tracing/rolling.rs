@@ -0,0 +1,494 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError, mpsc};
+use std::thread::JoinHandle;
+
+use jiff::Zoned;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::log_tracing::error::ClerkError;
+
+/// Suffix format of files rotated by size.
+const ROTATED_FORMAT: &str = "%Y-%m-%dT%H-%M-%S-%6fZ";
+
+/// When a [`RollingFileWriter`] starts a new file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rotation {
+    /// Never rotate, the log file grows forever.
+    #[default]
+    Never,
+
+    /// Rotate at the start of every hour, the rotated file is suffixed with
+    /// its hour, e.g. `app.log.2024-01-15-14`.
+    Hourly,
+
+    /// Rotate at the start of every day, the rotated file is suffixed with
+    /// its day, e.g. `app.log.2024-01-15`.
+    Daily,
+
+    /// Rotate before the file grows over the given number of bytes, the
+    /// rotated file is suffixed with the time of the rotation, e.g.
+    /// `app.log.2024-01-15T14-30-00-123456Z`.
+    Size(u64),
+}
+
+impl Rotation {
+    /// Returns the period `time` falls in, for time-based rotations.
+    fn period(self, time: &Zoned) -> Option<String> {
+        match self {
+            Self::Hourly => Some(time.strftime("%Y-%m-%d-%H").to_string()),
+            Self::Daily => Some(time.strftime("%Y-%m-%d").to_string()),
+            Self::Never | Self::Size(_) => None,
+        }
+    }
+}
+
+/// Rotation policy of a [`RollingFileWriter`].
+///
+/// # Example
+///
+/// ```
+/// use clerk::{Rotation, RotationPolicy};
+///
+/// // one file per day, keeping a compressed week of history
+/// let policy = RotationPolicy {
+///     rotation: Rotation::Daily,
+///     keep: Some(7),
+///     compress: true,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RotationPolicy {
+    /// When to start a new file.
+    pub rotation: Rotation,
+
+    /// Number of rotated files to keep, the oldest ones are removed. All
+    /// are kept if `None`.
+    pub keep: Option<usize>,
+
+    /// Whether to gzip rotated files, appending `.gz` to their name.
+    ///
+    /// Rotated files are compressed and pruned on a background thread, so
+    /// the write of the event rotating the file doesn't wait for them.
+    pub compress: bool,
+}
+
+/// Log file writer rotating the file according to a [`RotationPolicy`].
+///
+/// The active file keeps its path, rotated files are renamed next to it
+/// with a suffix. It can be passed to
+/// `tracing_subscriber::fmt::Layer::with_writer`, see
+/// [`crate::ClerkLayerBuilder::rolling_file`].
+///
+/// Rotated files are compressed and pruned on a background thread, in the
+/// order of their rotation. Dropping the writer waits for the queued ones.
+///
+/// A failed rotation or clean-up doesn't fail the write of an event, its
+/// error is kept until read with [`RollingFileWriter::take_last_error`].
+pub struct RollingFileWriter {
+    /// Path of the active file.
+    path: PathBuf,
+
+    policy: RotationPolicy,
+
+    state: Mutex<RollingState>,
+
+    /// Thread compressing and pruning rotated files, if the policy asks
+    /// for it.
+    cleanup: Option<Cleanup>,
+
+    /// Error of the last failed rotation or clean-up.
+    last_error: Arc<Mutex<Option<io::Error>>>,
+}
+
+/// Background thread of a [`RollingFileWriter`] cleaning up the rotated
+/// files sent to it.
+struct Cleanup {
+    sender: mpsc::Sender<PathBuf>,
+    thread: JoinHandle<()>,
+}
+
+/// Active file of a [`RollingFileWriter`].
+struct RollingState {
+    /// Active file, closed while it is rotated.
+    file: Option<File>,
+
+    /// Size of the active file in bytes.
+    size: u64,
+
+    /// Period of the active file, for time-based rotations.
+    period: Option<String>,
+}
+
+impl RollingFileWriter {
+    /// Opens the log file at `filepath` in append mode, creating its parent
+    /// directory if needed.
+    ///
+    /// An existing file last modified in an earlier period is rotated on
+    /// the first write.
+    pub fn new<F>(filepath: F, policy: RotationPolicy) -> Result<Self, ClerkError>
+    where
+        F: AsRef<Path>,
+    {
+        let path = filepath.as_ref().to_owned();
+        let file = crate::log_tracing::layer::open_log_file(&path, false)?;
+        let metadata = file.metadata()?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| jiff::Timestamp::try_from(time).ok())
+            .map_or_else(Zoned::now, |time| {
+                time.to_zoned(jiff::tz::TimeZone::system())
+            });
+        let last_error = Arc::new(Mutex::new(None));
+        let cleanup = if policy.compress || policy.keep.is_some() {
+            Some(Cleanup::spawn(path.clone(), policy, last_error.clone())?)
+        } else {
+            None
+        };
+        Ok(Self {
+            path,
+            policy,
+            state: Mutex::new(RollingState {
+                file: Some(file),
+                size: metadata.len(),
+                period: policy.rotation.period(&modified),
+            }),
+            cleanup,
+            last_error,
+        })
+    }
+
+    /// Returns the error of the last failed rotation or clean-up of rotated
+    /// files since the previous call, if any.
+    ///
+    /// Events are written even if their rotation fails, so these errors are
+    /// not returned by the writes.
+    pub fn take_last_error(&self) -> Option<io::Error> {
+        self.last_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+
+    /// Writes `buf` into the active file, rotating it first if needed at
+    /// time `now`.
+    ///
+    /// The event is written even if the rotation fails, the error is kept
+    /// for [`RollingFileWriter::take_last_error`].
+    fn write_at(&self, buf: &[u8], now: &Zoned) -> io::Result<usize> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        // an empty file is reused rather than rotated
+        let rotate = state.size > 0
+            && match self.policy.rotation {
+                Rotation::Never => false,
+                Rotation::Size(max) => state.size + buf.len() as u64 > max,
+                Rotation::Hourly | Rotation::Daily => {
+                    state.period != self.policy.rotation.period(now)
+                }
+            };
+        let rotated = if rotate {
+            Some(self.rotate(&mut state, now))
+        } else {
+            if state.size == 0 {
+                state.period = self.policy.rotation.period(now);
+            }
+            None
+        };
+
+        let file = match &mut state.file {
+            Some(file) => file,
+            file @ None => file.insert(self.open()?),
+        };
+        file.write_all(buf)?;
+        state.size += buf.len() as u64;
+        drop(state);
+
+        match rotated {
+            Some(Ok(rotated)) => {
+                if let Some(cleanup) = &self.cleanup {
+                    // the thread only stops once the sender is dropped
+                    let _ = cleanup.sender.send(rotated);
+                }
+            }
+            Some(Err(e)) => set_error(&self.last_error, e),
+            None => {}
+        }
+        Ok(buf.len())
+    }
+
+    /// Renames the active file with its suffix and returns the path of the
+    /// rotated file.
+    ///
+    /// The active file starts a new period even if the rename fails, so
+    /// later events are appended to it rather than retrying the rotation.
+    fn rotate(&self, state: &mut RollingState, now: &Zoned) -> io::Result<PathBuf> {
+        // the file must be closed before being renamed on Windows
+        if let Some(mut file) = state.file.take() {
+            file.flush()?;
+        }
+        let suffix = state
+            .period
+            .take()
+            .unwrap_or_else(|| now.strftime(ROTATED_FORMAT).to_string());
+        state.size = 0;
+        state.period = self.policy.rotation.period(now);
+        let rotated = self.unused_path(&suffix);
+        std::fs::rename(&self.path, &rotated)?;
+        Ok(rotated)
+    }
+
+    /// Opens the active file in append mode.
+    fn open(&self) -> io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+    }
+
+    /// Returns the path of a rotated file with `suffix` which is not taken
+    /// yet, numbering it if needed.
+    fn unused_path(&self, suffix: &str) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(".");
+        name.push(suffix);
+        let path = PathBuf::from(name);
+        let taken = |path: &Path| path.exists() || gz_path(path).exists();
+        if !taken(&path) {
+            return path;
+        }
+        (1..)
+            .map(|index| {
+                let mut name = path.as_os_str().to_owned();
+                name.push(format!(".{index}"));
+                PathBuf::from(name)
+            })
+            .find(|path| !taken(path))
+            .unwrap_or(path)
+    }
+}
+
+impl Drop for RollingFileWriter {
+    fn drop(&mut self) {
+        if let Some(Cleanup { sender, thread }) = self.cleanup.take() {
+            drop(sender);
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Cleanup {
+    /// Spawns the thread compressing each rotated file of the active file
+    /// at `path` and removing the oldest ones, according to `policy`.
+    fn spawn(
+        path: PathBuf,
+        policy: RotationPolicy,
+        last_error: Arc<Mutex<Option<io::Error>>>,
+    ) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel::<PathBuf>();
+        let thread = std::thread::Builder::new()
+            .name("clerk-rolling".to_string())
+            .spawn(move || {
+                for rotated in receiver {
+                    let compressed = if policy.compress {
+                        gzip_file(&rotated)
+                    } else {
+                        Ok(())
+                    };
+                    let pruned = policy.keep.map_or(Ok(()), |keep| prune(&path, keep));
+                    if let Err(e) = compressed.and(pruned) {
+                        set_error(&last_error, e);
+                    }
+                }
+            })?;
+        Ok(Self { sender, thread })
+    }
+}
+
+/// Keeps `error` as the last error of a [`RollingFileWriter`].
+fn set_error(last_error: &Mutex<Option<io::Error>>, error: io::Error) {
+    *last_error.lock().unwrap_or_else(PoisonError::into_inner) = Some(error);
+}
+
+impl io::Write for &RollingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.write_at(buf, &Zoned::now()) }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match &mut state.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for RollingFileWriter {
+    type Writer = &'a Self;
+
+    fn make_writer(&'a self) -> Self::Writer { self }
+}
+
+/// Returns `path` with `.gz` appended.
+fn gz_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".gz");
+    PathBuf::from(name)
+}
+
+/// Replaces a file with its gzip-compressed copy suffixed with `.gz`,
+/// streaming it rather than reading it into memory.
+fn gzip_file(path: &Path) -> io::Result<()> {
+    let mut input = File::open(path)?;
+    let mut encoder = flate2::write::GzEncoder::new(
+        io::BufWriter::new(File::create(gz_path(path))?),
+        flate2::Compression::default(),
+    );
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.flush()?;
+    drop(input);
+    std::fs::remove_file(path)
+}
+
+/// Removes the oldest files rotated from the active file at `path`,
+/// keeping `keep` of them.
+fn prune(path: &Path, keep: usize) -> io::Result<()> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(());
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let mut rotated: Vec<_> = std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+        })
+        .collect();
+    // suffixes are timestamps, so names sort from the oldest file
+    rotated.sort();
+    let excess = rotated.len().saturating_sub(keep);
+    for path in &rotated[..excess] {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> Zoned {
+        let time: jiff::Timestamp = time.parse().unwrap();
+        time.to_zoned(jiff::tz::TimeZone::UTC)
+    }
+
+    fn names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_rotate_by_size() -> mischief::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let writer = RollingFileWriter::new(
+            dir.path().join("app.log"),
+            RotationPolicy {
+                rotation: Rotation::Size(10),
+                keep: Some(2),
+                compress: false,
+            },
+        )?;
+        for (index, time) in [
+            "2024-01-15T14:00:00.000001Z",
+            "2024-01-15T14:00:00.000002Z",
+            "2024-01-15T14:00:00.000003Z",
+            "2024-01-15T14:00:00.000004Z",
+        ]
+        .iter()
+        .enumerate()
+        {
+            writer.write_at(format!("event {index}\n").as_bytes(), &at(time))?;
+        }
+        drop(writer);
+        assert_eq!(
+            names(dir.path()),
+            [
+                "app.log",
+                "app.log.2024-01-15T14-00-00-000003Z",
+                "app.log.2024-01-15T14-00-00-000004Z",
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("app.log"))?,
+            "event 3\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotate_daily_compressed() -> mischief::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let writer = RollingFileWriter::new(
+            dir.path().join("app.log"),
+            RotationPolicy {
+                rotation: Rotation::Daily,
+                keep: None,
+                compress: true,
+            },
+        )?;
+        writer.write_at(b"day 1\n", &at("2024-01-15T23:59:59Z"))?;
+        writer.write_at(b"day 1 again\n", &at("2024-01-15T23:59:59.5Z"))?;
+        writer.write_at(b"day 2\n", &at("2024-01-16T00:00:00Z"))?;
+        drop(writer);
+        assert_eq!(names(dir.path()), ["app.log", "app.log.2024-01-15.gz"]);
+
+        let gz = File::open(dir.path().join("app.log.2024-01-15.gz"))?;
+        let data = io::read_to_string(flate2::read::GzDecoder::new(gz))?;
+        assert_eq!(data, "day 1\nday 1 again\n");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("app.log"))?,
+            "day 2\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotation_error_keeps_event() -> mischief::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("app.log");
+        let writer = RollingFileWriter::new(
+            &path,
+            RotationPolicy {
+                rotation: Rotation::Daily,
+                keep: None,
+                compress: false,
+            },
+        )?;
+        writer.write_at(b"day 1\n", &at("2024-01-15T12:00:00Z"))?;
+        // the rename of the rotation fails once the active file is gone
+        std::fs::remove_file(&path)?;
+        assert_eq!(writer.write_at(b"day 2\n", &at("2024-01-16T12:00:00Z"))?, 6);
+        assert_eq!(
+            writer.take_last_error().map(|e| e.kind()),
+            Some(io::ErrorKind::NotFound)
+        );
+        assert!(writer.take_last_error().is_none());
+        writer.write_at(b"day 2 again\n", &at("2024-01-16T13:00:00Z"))?;
+        drop(writer);
+        assert_eq!(names(dir.path()), ["app.log"]);
+        assert_eq!(std::fs::read_to_string(&path)?, "day 2\nday 2 again\n");
+        Ok(())
+    }
+}
@@ -4,11 +4,15 @@ use core::str::Utf8Error;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+#[cfg_attr(feature = "mischief", derive(mischief::Diagnosis))]
 pub enum EnvoyError {
     #[error("The pointer was null.")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "envoy::null_ptr"))]
     NullPtr,
     #[error(transparent)]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "envoy::invalid_utf8"))]
     InvalidUtf8(#[from] Utf8Error),
     #[error(transparent)]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "envoy::nul_error"))]
     NulError(#[from] NulError),
 }
@@ -1,3 +1,6 @@
+use core::any::Any;
+use core::error::Error;
+use core::fmt::Display;
 use std::collections::VecDeque;
 
 use clerk::tracing::Instrument;
@@ -84,7 +87,7 @@ where
 
                 Some(res) = self.joinset.join_next() => {
                     if let Err(e) = res {
-                        clerk::error!("job panicked: {}", e);
+                        clerk::error!(error = &e as &dyn Error, "job panicked");
                     }
                 }
 
@@ -99,7 +102,7 @@ where
 
         while let Some(res) = self.joinset.join_next().await {
             if let Err(e) = res {
-                clerk::error!("job panicked: {}", e);
+                clerk::error!(error = &e as &dyn Error, "job panicked");
             }
         }
 
@@ -151,7 +154,7 @@ where
         if let ResourceMode::Pooled(ref mut pool) = self.mode
             && let Err(e) = pool.free(job.resources.as_slice())
         {
-            clerk::error!("resource free failed: {}", e);
+            clerk::error!(error = &e as &dyn Error, "resource free failed");
         }
     }
 
@@ -172,7 +175,7 @@ where
                             break;
                         }
                         Err(e) => {
-                            clerk::error!("resource allocation failed: {}", e);
+                            clerk::error!(error = &e as &dyn Error, "resource allocation failed");
                         }
                     }
                 }
@@ -204,7 +207,7 @@ where
                         clerk::debug!("job execute succeeded, post processing");
 
                         if let Err(e) = job.payload.post_process().await {
-                            clerk::error!("payload post process error: {}", e);
+                            clerk::error!(error = error_value(&e), "payload post process error");
                         }
 
                         let _ = tx.send(DispatcherEvent::FreeResource(job)).await;
@@ -213,7 +216,7 @@ where
                     }
 
                     Err(e) => {
-                        clerk::error!(attempt = %job.attempt, "job execute failed: {}", e);
+                        clerk::error!(attempt = %job.attempt, error = error_value(&e), "job execute failed");
 
                         let _ = tx.send(DispatcherEvent::RetryJob(job)).await;
                     }
@@ -238,6 +241,21 @@ where
     start_dispatcher_with_mode(ResourceMode::Unlimited)
 }
 
+/// Returns the `error` field of a payload error.
+///
+/// A [`mischief::Report`] is recorded as an error, so that file layers write
+/// the plain chain of its diagnoses and colored terminal layers render it.
+/// Other errors are recorded with their `Display` output.
+fn error_value<E>(error: &E) -> Box<dyn clerk::tracing::Value + '_>
+where
+    E: Display + 'static,
+{
+    match (error as &dyn Any).downcast_ref::<mischief::Report>() {
+        Some(report) => Box::new(report.as_error()),
+        None => Box::new(clerk::tracing::field::display(error)),
+    }
+}
+
 fn start_dispatcher_with_mode<P>(mode: ResourceMode) -> DispatcherHandle<P>
 where
     P: IPayload + Send + 'static,
@@ -1,11 +1,17 @@
 use clerk::ClerkError;
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, thiserror::Error, mischief::Diagnosis)]
 pub enum KioyuError {
     #[error(transparent)]
+    #[diagnosis(code = "kioyu::log")]
     Clerk(#[from] ClerkError),
     #[error(transparent)]
+    #[diagnosis(code = "kioyu::io")]
     IO(#[from] std::io::Error),
     #[error("Dispatcher closed.")]
+    #[diagnosis(
+        code = "kioyu::dispatcher_closed",
+        help = "The dispatcher was shut down before the job could be sent."
+    )]
     DispatcherClosed,
 }
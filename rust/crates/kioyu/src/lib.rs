@@ -5,7 +5,8 @@ mod log;
 mod resource;
 
 pub use dispatcher::{DispatcherHandle, start_dispatcher, start_dispatcher_unlimited};
+pub use error::KioyuError;
 pub use job::{IPayload, Job, ResourceRequest};
-pub use log::{KIOYU_JOB_SPAN, kioyu_layers};
+pub use log::{KIOYU_JOB_SPAN, KioyuLogOptions, kioyu_layers, kioyu_layers_with_options};
 pub use resource::{ResourceKey, ResourcePool};
 pub use tokio_util::sync::CancellationToken;
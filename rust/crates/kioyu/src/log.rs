@@ -6,7 +6,10 @@ use std::sync::Mutex;
 use clerk::tracing_subscriber::layer::Context;
 use clerk::tracing_subscriber::registry::LookupSpan;
 use clerk::tracing_subscriber::{self, Layer};
-use clerk::{ClerkFormatter, FormatEventToWriter, file_layer, tracing_core};
+use clerk::{
+    ClerkFormat, ClerkFormatter, ClerkJsonFormatter, ClerkLayerBuilder, ClerkLogfmtFormatter,
+    FormatEventToWriter, RotationPolicy, tracing_core,
+};
 use tracing_core::{Event, Subscriber};
 
 use crate::error::KioyuError;
@@ -14,6 +17,37 @@ pub const KIOYU_JOB_SPAN: &str = "kioyu-job";
 pub const TIME_FORMAT: &str = "%Y-%m-%dT%H-%M-%S-%6fZ";
 struct JobId(String, String);
 
+/// Options of [`kioyu_layers_with_options`].
+///
+/// # Example
+///
+/// ```
+/// use clerk::{ClerkFormat, Rotation, RotationPolicy};
+///
+/// // JSON logs, the last 10 runs, `kioyu.log` rotated daily
+/// let options = kioyu::KioyuLogOptions {
+///     format: ClerkFormat::Json,
+///     rotation: RotationPolicy {
+///         rotation: Rotation::Daily,
+///         keep: Some(7),
+///         compress: true,
+///     },
+///     keep_runs: Some(10),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KioyuLogOptions {
+    /// Output format of `kioyu.log` and the job files.
+    pub format: ClerkFormat,
+
+    /// Rotation policy of `kioyu.log`, for long-running dispatchers.
+    pub rotation: RotationPolicy,
+
+    /// Number of run directories to keep, including the new one, the
+    /// oldest ones are removed. All are kept if `None`.
+    pub keep_runs: Option<usize>,
+}
+
 struct JobIdVisitor {
     id: Option<String>,
     name: Option<String>,
@@ -46,28 +80,29 @@ impl tracing_core::field::Visit for JobIdVisitor {
     }
 }
 
-struct JobFileLayer {
+struct JobFileLayer<F> {
     jobs_dir: PathBuf,
     handles: Mutex<HashMap<String, File>>,
-    formatter: ClerkFormatter,
+    formatter: F,
 }
 
-impl JobFileLayer {
-    pub fn new<T>(jobs_dir: T) -> Self
+impl<F> JobFileLayer<F> {
+    pub fn new<T>(jobs_dir: T, formatter: F) -> Self
     where
         T: Into<PathBuf>,
     {
         Self {
             jobs_dir: jobs_dir.into(),
             handles: Mutex::new(HashMap::new()),
-            formatter: ClerkFormatter { color: false },
+            formatter,
         }
     }
 }
 
-impl<S> Layer<S> for JobFileLayer
+impl<S, F> Layer<S> for JobFileLayer<F>
 where
     S: Subscriber + for<'a> LookupSpan<'a>,
+    F: FormatEventToWriter + 'static,
 {
     fn on_new_span(
         &self,
@@ -141,6 +176,12 @@ where
 ///
 /// See: <https://github.com/tokio-rs/tracing/issues/1181>
 ///
+/// # Job fields on the terminal
+///
+/// Terminal lines of job events show the `job.id` and `job.name` fields of
+/// their [`KIOYU_JOB_SPAN`] with a terminal layer writing span breadcrumbs,
+/// see [`clerk::ClerkLayerBuilder::with_span_breadcrumb`].
+///
 /// # Examples
 ///
 /// ```
@@ -169,19 +210,93 @@ where
     S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
     R: AsRef<std::path::Path>,
 {
-    let run_dir = log_root
-        .as_ref()
-        .join("kioyu")
-        .join(jiff::Zoned::now().strftime(TIME_FORMAT).to_string());
+    kioyu_layers_with_options(log_root, KioyuLogOptions::default())
+}
+
+/// Sets up kioyu's logging layers under `log_root`, like [`kioyu_layers`],
+/// with the format and retention of `options`.
+///
+/// Job files are written without span context, see
+/// [`clerk::FormatEventToWriter`].
+///
+/// # Examples
+///
+/// ```
+/// use clerk::tracing_subscriber::layer::SubscriberExt;
+/// use clerk::tracing_subscriber::util::SubscriberInitExt;
+/// use clerk::{ClerkFormat, tracing_subscriber};
+/// use tempfile::tempdir;
+///
+/// let log_root = tempdir().unwrap();
+/// let options = kioyu::KioyuLogOptions {
+///     format: ClerkFormat::Json,
+///     keep_runs: Some(10),
+///     ..Default::default()
+/// };
+/// clerk::tracing_subscriber::registry()
+///     .with(
+///         kioyu::kioyu_layers_with_options::<tracing_subscriber::Registry, _>(
+///             log_root.path(),
+///             options,
+///         )
+///         .unwrap(),
+///     )
+///     .init();
+/// ```
+pub fn kioyu_layers_with_options<S, R>(
+    log_root: R,
+    options: KioyuLogOptions,
+) -> Result<Vec<Box<dyn Layer<S> + Send + Sync>>, KioyuError>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    R: AsRef<std::path::Path>,
+{
+    let runs_dir = log_root.as_ref().join("kioyu");
+    let run_dir = runs_dir.join(jiff::Zoned::now().strftime(TIME_FORMAT).to_string());
 
     let jobs_dir = run_dir.join("jobs");
     std::fs::create_dir_all(&jobs_dir)?;
+    if let Some(keep) = options.keep_runs {
+        prune_runs(&runs_dir, keep)?;
+    }
 
-    let kioyu_log = file_layer(run_dir.join("kioyu.log"), true)?.with_filter(
-        tracing_subscriber::filter::filter_fn(|meta| meta.target().starts_with("kioyu")),
-    );
+    let format = options.format;
+    let kioyu_log = ClerkLayerBuilder::new()
+        .with_format(format)
+        .rolling_file(run_dir.join("kioyu.log"), options.rotation)?
+        .with_filter(tracing_subscriber::filter::filter_fn(|meta| {
+            meta.target().starts_with("kioyu")
+        }));
 
-    let job_log = JobFileLayer::new(jobs_dir);
+    let job_log = match format {
+        ClerkFormat::Text => JobFileLayer::new(jobs_dir, ClerkFormatter::new(false)).boxed(),
+        ClerkFormat::Json => JobFileLayer::new(jobs_dir, ClerkJsonFormatter).boxed(),
+        ClerkFormat::Logfmt => JobFileLayer::new(jobs_dir, ClerkLogfmtFormatter).boxed(),
+    };
 
-    Ok(vec![kioyu_log.boxed(), job_log.boxed()])
+    Ok(vec![kioyu_log.boxed(), job_log])
+}
+
+/// Removes the oldest run directories of `runs_dir`, keeping `keep` of
+/// them and at least the current one.
+///
+/// Only directories named after [`TIME_FORMAT`] are considered.
+fn prune_runs(runs_dir: &std::path::Path, keep: usize) -> std::io::Result<()> {
+    let mut runs: Vec<_> = std::fs::read_dir(runs_dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && path.file_name().is_some_and(|name| {
+                    jiff::fmt::strtime::parse(TIME_FORMAT, name.to_string_lossy().as_ref()).is_ok()
+                })
+        })
+        .collect();
+    // names are timestamps, so they sort from the oldest run
+    runs.sort();
+    let excess = runs.len().saturating_sub(keep.max(1));
+    for run in &runs[..excess] {
+        std::fs::remove_dir_all(run)?;
+    }
+    Ok(())
 }
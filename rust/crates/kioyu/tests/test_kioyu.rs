@@ -11,10 +11,11 @@ use clerk::tracing_subscriber::layer::SubscriberExt;
 use clerk::tracing_subscriber::util::SubscriberInitExt;
 use clerk::{LevelFilter, NotInSpanFilter, tracing_subscriber};
 use kioyu::{
-    CancellationToken, IPayload, Job, KIOYU_JOB_SPAN, ResourceKey, ResourcePool, ResourceRequest,
-    kioyu_layers, start_dispatcher, start_dispatcher_unlimited,
+    CancellationToken, IPayload, Job, KIOYU_JOB_SPAN, KioyuError, KioyuLogOptions, ResourceKey,
+    ResourcePool, ResourceRequest, kioyu_layers, kioyu_layers_with_options, start_dispatcher,
+    start_dispatcher_unlimited,
 };
-use mischief::IntoMischief;
+use mischief::{IDiagnosis, IntoMischief, Report};
 use tempfile::tempdir;
 use tokio::sync::mpsc;
 use tokio::time::{Duration, sleep};
@@ -161,7 +162,7 @@ async fn run_dispatcher_test(mode: DispatcherMode, snapshot_name: &str) -> misch
     clerk::tracing_subscriber::registry()
         .with(
             kioyu_layers::<tracing_subscriber::Registry, _>(log_root.path())
-                .into_mischief()?
+                .map_err(Report::from_diagnosis)?
                 .with_filter(LevelFilter::TRACE),
         )
         .with(
@@ -233,6 +234,38 @@ async fn test_dispatcher_unlimited() -> mischief::Result<()> {
     run_dispatcher_test(DispatcherMode::Unlimited, "kioyu_unlimited_log_dir_tree").await
 }
 
+#[test]
+fn test_prune_runs() -> mischief::Result<()> {
+    let log_root = tempdir().unwrap();
+    let runs_dir = log_root.path().join("kioyu");
+    for run in [
+        "2024-01-15T14-30-00-000001Z",
+        "2024-01-15T14-30-00-000002Z",
+        "2024-01-15T14-30-00-000003Z",
+        "not-a-run",
+    ] {
+        std::fs::create_dir_all(runs_dir.join(run)).into_mischief()?;
+    }
+
+    let options = KioyuLogOptions {
+        keep_runs: Some(2),
+        ..Default::default()
+    };
+    kioyu_layers_with_options::<tracing_subscriber::Registry, _>(log_root.path(), options)
+        .map_err(Report::from_diagnosis)?;
+
+    let mut runs: Vec<_> = std::fs::read_dir(&runs_dir)
+        .into_mischief()?
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    runs.sort();
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs[0], "2024-01-15T14-30-00-000003Z");
+    assert_ne!(runs[1], "not-a-run");
+    assert_eq!(runs[2], "not-a-run");
+    Ok(())
+}
+
 // ── retry tests
 // ───────────────────────────────────────────────────────────────
 
@@ -330,3 +363,51 @@ async fn test_retry_exhaustion_frees_resources() {
     assert_eq!(exhausted_exec.load(Ordering::SeqCst), 2);
     assert_eq!(succeeding_exec.load(Ordering::SeqCst), 1);
 }
+
+#[test]
+fn test_error_diagnosis() {
+    let report = Report::from_diagnosis(KioyuError::DispatcherClosed);
+    assert_eq!(report.error().code(), Some("kioyu::dispatcher_closed"));
+    assert!(report.error().help().is_some());
+    assert!(report.is::<KioyuError>());
+}
+
+/// Job files record a failed job's report as a plain `error` field instead
+/// of its rendered, colored tree.
+#[tokio::test]
+async fn test_job_log_error_is_plain() -> mischief::Result<()> {
+    let log_root = tempdir().unwrap();
+    let subscriber = clerk::tracing_subscriber::registry().with(
+        kioyu_layers::<tracing_subscriber::Registry, _>(log_root.path())
+            .map_err(Report::from_diagnosis)?
+            .with_filter(LevelFilter::TRACE),
+    );
+    // the test runtime is single-threaded, so jobs see the scoped subscriber
+    let _guard = clerk::tracing::subscriber::set_default(subscriber);
+
+    let handle = start_dispatcher_unlimited::<FailingPayload>();
+    let (payload, _, _, mut attempt_rx, _) = FailingPayload::new(1);
+    handle
+        .submit(Job::new("plain-job", payload, ResourceRequest::none(), 0))
+        .await
+        .unwrap();
+    wait_for_n(&mut attempt_rx, 1, "execute attempt").await;
+    handle.shutdown().await;
+
+    let run_dir = std::fs::read_dir(log_root.path().join("kioyu"))
+        .into_mischief()?
+        .next()
+        .unwrap()
+        .into_mischief()?
+        .path();
+    let job_log = std::fs::read_dir(run_dir.join("jobs"))
+        .into_mischief()?
+        .next()
+        .unwrap()
+        .into_mischief()?
+        .path();
+    let log = std::fs::read_to_string(job_log).into_mischief()?;
+    assert!(log.contains(" job execute failed attempt=0 error=intentional failure on attempt 1"));
+    assert!(!log.contains('\x1b'));
+    Ok(())
+}
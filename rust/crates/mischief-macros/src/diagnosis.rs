@@ -0,0 +1,268 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Fields, Ident, LitStr, Member, Result};
+
+/// Metadata parsed from `#[diagnosis(...)]` attributes.
+///
+/// Variant attributes are merged over the attribute of the enum, so the
+/// enum can hold defaults such as a common `url`.
+#[derive(Default, Clone)]
+struct DiagnosisAttr {
+    code: Option<LitStr>,
+    severity: Option<Ident>,
+    help: Option<LitStr>,
+    url: Option<LitStr>,
+}
+
+impl DiagnosisAttr {
+    /// Parses the `#[diagnosis(...)]` attributes of an item over `self`.
+    fn parse(mut self, attrs: &[Attribute]) -> Result<Self> {
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("diagnosis"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let key = meta
+                    .path
+                    .get_ident()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                match key.as_str() {
+                    "code" => self.code = Some(meta.value()?.parse()?),
+                    "help" => self.help = Some(meta.value()?.parse()?),
+                    "url" => self.url = Some(meta.value()?.parse()?),
+                    "severity" => {
+                        let severity: Ident = meta.value()?.parse()?;
+                        if !matches!(
+                            severity.to_string().as_str(),
+                            "Advice" | "Warning" | "Error"
+                        ) {
+                            return Err(syn::Error::new(
+                                severity.span(),
+                                "expected one of: `Advice`, `Warning`, `Error`",
+                            ));
+                        }
+                        self.severity = Some(severity);
+                    }
+                    _ => {
+                        return Err(meta.error(
+                            "unexpected field, expected one of: `code`, `severity`, `help`, `url`",
+                        ));
+                    }
+                }
+                Ok(())
+            })?;
+        }
+        Ok(self)
+    }
+}
+
+/// A struct or enum variant: its pattern, metadata and source field.
+struct Case {
+    pattern: TokenStream,
+    attr: DiagnosisAttr,
+    source: Option<Member>,
+    /// Field of an `#[error(transparent)]` item, whose diagnosis is
+    /// forwarded.
+    transparent: Option<Member>,
+}
+
+impl Case {
+    fn new(
+        pattern: TokenStream,
+        attr: DiagnosisAttr,
+        attrs: &[Attribute],
+        fields: &Fields,
+    ) -> Self {
+        Self {
+            pattern,
+            attr,
+            source: source_field(fields),
+            transparent: transparent_field(attrs, fields),
+        }
+    }
+}
+
+/// Returns the source field of a struct or variant, following the rules of
+/// `thiserror`: a field marked `#[source]` or `#[from]`, or else a field
+/// named `source`.
+fn source_field(fields: &Fields) -> Option<Member> {
+    let members = || {
+        fields.iter().enumerate().map(|(index, field)| {
+            let member = field
+                .ident
+                .clone()
+                .map_or_else(|| Member::from(index), Member::Named);
+            (field, member)
+        })
+    };
+    members()
+        .find(|(field, _)| {
+            field
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("source") || attr.path().is_ident("from"))
+        })
+        .or_else(|| {
+            members().find(|(field, _)| field.ident.as_ref().is_some_and(|i| i == "source"))
+        })
+        .map(|(_, member)| member)
+}
+
+/// Returns the field of an item marked `#[error(transparent)]`, whose
+/// `Display` and `source` forward to that field with `thiserror`.
+fn transparent_field(attrs: &[Attribute], fields: &Fields) -> Option<Member> {
+    let transparent = attrs.iter().any(|attr| {
+        attr.path().is_ident("error")
+            && attr
+                .parse_args::<Ident>()
+                .is_ok_and(|ident| ident == "transparent")
+    });
+    let field = fields.iter().next().filter(|_| transparent)?;
+    Some(
+        field
+            .ident
+            .clone()
+            .map_or_else(|| Member::from(0), Member::Named),
+    )
+}
+
+/// Expands `#[derive(Diagnosis)]`.
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let attr = DiagnosisAttr::default().parse(&input.attrs)?;
+    let cases = match &input.data {
+        Data::Struct(data) => vec![Case::new(quote! { Self }, attr, &input.attrs, &data.fields)],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                Ok(Case::new(
+                    quote! { Self::#ident },
+                    attr.clone().parse(&variant.attrs)?,
+                    &variant.attrs,
+                    &variant.fields,
+                ))
+            })
+            .collect::<Result<_>>()?,
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`Diagnosis` cannot be derived for unions",
+            ));
+        }
+    };
+
+    // Builds a method body matching every case. A transparent case calls
+    // `method` on its field when the field is a diagnosis, so its chain
+    // stays in step with the `Error::source` chain forwarded by `thiserror`;
+    // otherwise and for the other cases the arm returns `fallback`.
+    let delegate = |method: TokenStream, fallback: &dyn Fn(&Case) -> TokenStream| {
+        let arms = cases.iter().map(|case| {
+            let pattern = &case.pattern;
+            let fallback = fallback(case);
+            match &case.transparent {
+                Some(member) => quote! {
+                    #pattern { #member: inner, .. } =>
+                        match (&&::mischief::__private::Source(inner)).as_diagnosis() {
+                            ::core::option::Option::Some(inner) => inner.#method,
+                            ::core::option::Option::None => #fallback,
+                        },
+                },
+                None => quote! { #pattern { .. } => #fallback, },
+            }
+        });
+        quote! {
+            #[allow(unused_imports)]
+            use ::mischief::__private::{IDiagnosisSource as _, IPlainSource as _};
+            #[allow(unreachable_patterns)]
+            match self { #(#arms)* }
+        }
+    };
+    let str_fallback = |get: fn(&DiagnosisAttr) -> &Option<LitStr>| {
+        move |case: &Case| match get(&case.attr) {
+            Some(value) => quote! { ::core::option::Option::Some(#value) },
+            None => quote! { ::core::option::Option::None },
+        }
+    };
+    let none = |_: &Case| quote! { ::core::option::Option::None };
+
+    let description = delegate(quote! { description() }, &|_| {
+        quote! {
+            ::mischief::__private::Cow::Owned(::mischief::__private::ToString::to_string(self))
+        }
+    });
+    let code = delegate(quote! { code() }, &str_fallback(|attr| &attr.code));
+    let help = delegate(quote! { help() }, &str_fallback(|attr| &attr.help));
+    let url = delegate(quote! { url() }, &str_fallback(|attr| &attr.url));
+    let severity = delegate(quote! { severity() }, &|case| match &case.attr.severity {
+        Some(severity) => quote! {
+            ::core::option::Option::Some(::mischief::Severity::#severity)
+        },
+        None => quote! { ::core::option::Option::None },
+    });
+    let source_code = delegate(quote! { source_code() }, &none);
+    let labels = delegate(quote! { labels() }, &|_| quote! { &[] });
+    let attachments = delegate(quote! { attachments() }, &|_| quote! { &[] });
+    let related = delegate(quote! { related() }, &|_| {
+        quote! { ::mischief::__private::Box::new(::core::iter::empty()) }
+    });
+
+    // The source of a transparent case is the source of its field, the
+    // field itself being the level described by this diagnosis.
+    let source_arms = cases.iter().map(|case| {
+        let pattern = &case.pattern;
+        match (&case.transparent, &case.source) {
+            (Some(member), _) => quote! {
+                #pattern { #member: inner, .. } =>
+                    (&&::mischief::__private::Source(inner))
+                        .as_diagnosis()
+                        .and_then(|inner| inner.source()),
+            },
+            (None, Some(member)) => quote! {
+                #pattern { #member: source, .. } =>
+                    (&&::mischief::__private::Source(source)).as_diagnosis(),
+            },
+            (None, None) => quote! { #pattern { .. } => ::core::option::Option::None, },
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::mischief::IDiagnosis for #ident #ty_generics #where_clause {
+            fn description(&self) -> ::mischief::__private::Cow<'_, str> { #description }
+
+            fn source(&self) -> ::core::option::Option<&dyn ::mischief::IDiagnosis> {
+                #[allow(unused_imports)]
+                use ::mischief::__private::{IDiagnosisSource as _, IPlainSource as _};
+                #[allow(unreachable_patterns)]
+                match self { #(#source_arms)* }
+            }
+
+            fn code(&self) -> ::core::option::Option<&str> { #code }
+
+            fn severity(&self) -> ::core::option::Option<::mischief::Severity> { #severity }
+
+            fn help(&self) -> ::core::option::Option<&str> { #help }
+
+            fn url(&self) -> ::core::option::Option<&str> { #url }
+
+            fn source_code(&self) -> ::core::option::Option<&::mischief::SourceCode> {
+                #source_code
+            }
+
+            fn labels(&self) -> &[::mischief::Label] { #labels }
+
+            fn attachments(&self) -> &[::mischief::Attachment] { #attachments }
+
+            fn related(
+                &self,
+            ) -> ::mischief::__private::Box<
+                dyn ::core::iter::Iterator<Item = &dyn ::mischief::IDiagnosis> + '_,
+            > {
+                #related
+            }
+        }
+    })
+}
@@ -1,143 +1,179 @@
-use proc_macro::TokenStream;
-use quote::quote;
-use syn::parse::{Parse, ParseStream, Result};
-use syn::punctuated::Punctuated;
-use syn::{Expr, Ident, LitStr, Token};
-
-/// Internal structure used to parse the arguments of `mischief!`.
-///
-/// Supports:
-/// - Positional format arguments for the error description.
-/// - Optional named fields: `code`, `severity`, `help`, `url`.
-struct MischiefErrorInput {
-    description_lit: LitStr,
-    description_args: Punctuated<Expr, Token![,]>,
-    code: Option<Expr>,
-    severity: Option<Expr>,
-    help: Option<Expr>,
-    url: Option<Expr>,
-}
-
-impl Parse for MischiefErrorInput {
-    fn parse(input: ParseStream<'_>) -> Result<Self> {
-        // The first argument must always be a string literal for the description.
-        let description_lit = input.parse()?;
-        let mut description_args = Punctuated::new();
-
-        let mut code = None;
-        let mut severity = None;
-        let mut help = None;
-        let mut url = None;
-        let mut seen_named_arg = false;
-
-        while !input.is_empty() {
-            input.parse::<Token![,]>()?; // consume comma
-            if input.is_empty() {
-                break; // allow trailing comma
-            }
-
-            // Named fields: key = value
-            if input.peek(Ident) && input.peek2(Token![=]) {
-                seen_named_arg = true;
-                let key: Ident = input.parse()?;
-                input.parse::<Token![=]>()?;
-                let value: Expr = input.parse()?;
-
-                match key.to_string().as_str() {
-                    "code" if code.is_none() => code = Some(value),
-                    "severity" if severity.is_none() => severity = Some(value),
-                    "help" if help.is_none() => help = Some(value),
-                    "url" if url.is_none() => url = Some(value),
-                    "source" | "code" | "severity" | "help" | "url" => {
-                        return Err(syn::Error::new(
-                            key.span(),
-                            format!("duplicate field `{key}`"),
-                        ));
-                    }
-                    _ => {
-                        return Err(syn::Error::new(
-                            key.span(),
-                            "unexpected field, expected one of: `source`, `code`, `severity`, `help`, `url`",
-                        ));
-                    }
-                }
-            } else {
-                // Positional format arguments
-                if seen_named_arg {
-                    return Err(syn::Error::new(
-                        input.span(),
-                        "positional arguments cannot follow named arguments",
-                    ));
-                }
-                description_args.push(input.parse()?);
-            }
-        }
-
-        Ok(Self {
-            description_lit,
-            description_args,
-            code,
-            severity,
-            help,
-            url,
-        })
-    }
-}
-
-/// Procedural macro to create a `MischiefError` wrapped in a `Report`.
-///
-/// # Syntax
-///
-/// ```text
-/// mischief!("format string", positional_args..., code = ..., severity = ..., help = ..., url = ...)
-/// ```
-#[proc_macro]
-pub fn mischief(input: TokenStream) -> TokenStream {
-    let parsed_input = match syn::parse::<MischiefErrorInput>(input) {
-        Ok(input) => input,
-        Err(e) => return e.to_compile_error().into(),
-    };
-
-    let description_lit = parsed_input.description_lit;
-    let description_args = parsed_input.description_args;
-
-    let code = parsed_input
-        .code
-        .map_or_else(|| quote! { None }, |e| quote! { Some(#e.into()) });
-    let severity = parsed_input
-        .severity
-        .map_or_else(|| quote! { None }, |e| quote! { Some(#e.into()) });
-    let help = parsed_input
-        .help
-        .map_or_else(|| quote! { None }, |e| quote! { Some(#e.into()) });
-    let url = parsed_input
-        .url
-        .map_or_else(|| quote! { None }, |e| quote! { Some(#e.into()) });
-
-    let expanded = quote! {
-        {
-            extern crate mischief;
-            use mischief::{MischiefError, Report};
-
-            extern crate alloc;
-            use alloc::string::String;
-            use core::fmt::Write;
-
-            let mut description = String::new();
-            write!(description, #description_lit, #description_args).unwrap();
-
-            Report::new(
-                MischiefError::new(
-                    description,
-                    None,
-                    #code,
-                    #severity,
-                    #help,
-                    #url,
-                )
-            )
-        }
-    };
-
-    expanded.into()
-}
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::{DeriveInput, Expr, Ident, LitStr, Token, parse_macro_input};
+
+mod diagnosis;
+
+/// Internal structure used to parse the arguments of `mischief!`.
+///
+/// Supports:
+/// - Positional format arguments for the error description.
+/// - Optional named fields: `code`, `severity`, `help`, `url`.
+struct MischiefErrorInput {
+    description_lit: LitStr,
+    description_args: Punctuated<Expr, Token![,]>,
+    code: Option<Expr>,
+    severity: Option<Expr>,
+    help: Option<Expr>,
+    url: Option<Expr>,
+}
+
+impl Parse for MischiefErrorInput {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        // The first argument must always be a string literal for the description.
+        let description_lit = input.parse()?;
+        let mut description_args = Punctuated::new();
+
+        let mut code = None;
+        let mut severity = None;
+        let mut help = None;
+        let mut url = None;
+        let mut seen_named_arg = false;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?; // consume comma
+            if input.is_empty() {
+                break; // allow trailing comma
+            }
+
+            // Named fields: key = value
+            if input.peek(Ident) && input.peek2(Token![=]) {
+                seen_named_arg = true;
+                let key: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                let value: Expr = input.parse()?;
+
+                match key.to_string().as_str() {
+                    "code" if code.is_none() => code = Some(value),
+                    "severity" if severity.is_none() => severity = Some(value),
+                    "help" if help.is_none() => help = Some(value),
+                    "url" if url.is_none() => url = Some(value),
+                    "source" | "code" | "severity" | "help" | "url" => {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            format!("duplicate field `{key}`"),
+                        ));
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            "unexpected field, expected one of: `source`, `code`, `severity`, `help`, `url`",
+                        ));
+                    }
+                }
+            } else {
+                // Positional format arguments
+                if seen_named_arg {
+                    return Err(syn::Error::new(
+                        input.span(),
+                        "positional arguments cannot follow named arguments",
+                    ));
+                }
+                description_args.push(input.parse()?);
+            }
+        }
+
+        Ok(Self {
+            description_lit,
+            description_args,
+            code,
+            severity,
+            help,
+            url,
+        })
+    }
+}
+
+/// Procedural macro to create a `MischiefError` wrapped in a `Report`.
+///
+/// # Syntax
+///
+/// ```text
+/// mischief!("format string", positional_args..., code = ..., severity = ..., help = ..., url = ...)
+/// ```
+#[proc_macro]
+pub fn mischief(input: TokenStream) -> TokenStream {
+    let parsed_input = match syn::parse::<MischiefErrorInput>(input) {
+        Ok(input) => input,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let description_lit = parsed_input.description_lit;
+    let description_args = parsed_input.description_args;
+
+    let code = parsed_input
+        .code
+        .map_or_else(|| quote! { None }, |e| quote! { Some(#e.into()) });
+    let severity = parsed_input
+        .severity
+        .map_or_else(|| quote! { None }, |e| quote! { Some(#e.into()) });
+    let help = parsed_input
+        .help
+        .map_or_else(|| quote! { None }, |e| quote! { Some(#e.into()) });
+    let url = parsed_input
+        .url
+        .map_or_else(|| quote! { None }, |e| quote! { Some(#e.into()) });
+
+    let expanded = quote! {
+        {
+            extern crate mischief;
+            use mischief::{MischiefError, Report};
+
+            extern crate alloc;
+            use alloc::string::String;
+            use core::fmt::Write;
+
+            let mut description = String::new();
+            write!(description, #description_lit, #description_args).unwrap();
+
+            Report::new(
+                MischiefError::new(
+                    description,
+                    None,
+                    #code,
+                    #severity,
+                    #help,
+                    #url,
+                )
+            )
+        }
+    };
+
+    expanded.into()
+}
+
+/// Derive macro implementing `IDiagnosis` for error structs and enums.
+///
+/// The description is the `Display` output of the error, formatted on
+/// demand by `IDiagnosis::description`. Metadata comes from
+/// `#[diagnosis(...)]` attributes on the type or on each variant, variant
+/// attributes taking precedence:
+///
+/// ```text
+/// #[diagnosis(code = "rax::checksum", severity = Warning, help = "...", url = "...")]
+/// ```
+///
+/// The source is the field marked `#[source]` or `#[from]`, or else the
+/// field named `source`, like `thiserror`. It is only a diagnosis source
+/// when its type implements `IDiagnosis` too.
+///
+/// An `#[error(transparent)]` struct or variant forwards every method to its
+/// field when the field implements `IDiagnosis`, the way `thiserror`
+/// forwards `Display` and `source`. Otherwise its `#[diagnosis(...)]`
+/// attributes apply, with the field's sources converted as a plain error
+/// chain.
+///
+/// Whether the source implements `IDiagnosis` is decided where the derive
+/// expands, without specialization. A source whose type is a generic
+/// parameter of the item, or a trait object such as `Box<dyn Error>`, is
+/// therefore never a diagnosis source, even if the concrete type
+/// implements `IDiagnosis`: it is converted as a plain error chain.
+#[proc_macro_derive(Diagnosis, attributes(diagnosis))]
+pub fn derive_diagnosis(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    diagnosis::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
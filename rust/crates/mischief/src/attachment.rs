@@ -0,0 +1,32 @@
+extern crate alloc;
+use alloc::string::String;
+
+/// Context attached to a diagnosis without adding a causal level.
+///
+/// Attachments hold facts about the failed operation, such as the job id
+/// or the path of the file being read. Renderers show them as a block
+/// under the diagnosis they are attached to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Attachment {
+    /// A key-value fact, shown as `key: value`.
+    Field {
+        /// Name of the fact, e.g. `job`.
+        key: String,
+
+        /// Value of the fact.
+        value: String,
+    },
+
+    /// A free-form remark, shown as `note: text`.
+    Note(String),
+
+    /// A titled block of text, such as the output of a command, shown
+    /// under its title.
+    Section {
+        /// Title of the section, e.g. `stdout`.
+        title: String,
+
+        /// Text of the section, possibly spanning several lines.
+        body: String,
+    },
+}
@@ -1,9 +1,14 @@
 extern crate alloc;
+use alloc::borrow::Cow;
 use alloc::boxed::Box;
 use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::error::Error;
 use core::fmt::Display;
 
-use crate::IDiagnosis;
+use crate::{Attachment, IDiagnosis, Label, SourceCode};
 
 /// Concrete diagnosis error type used by the crate.
 ///
@@ -20,6 +25,10 @@ use crate::IDiagnosis;
 /// Unlike plain error types that only implement `Display`, this
 /// structure exposes diagnosis metadata that can be consumed by
 /// renderers, logging systems, or reporting frameworks.
+///
+/// A diagnosis converted from a standard error keeps the original value,
+/// which [`crate::Report::downcast_ref`] and related methods give back.
+/// Clones share the original value.
 #[derive(Clone)]
 pub struct MischiefError {
     /// Human-readable description of the diagnosis.
@@ -47,6 +56,41 @@ pub struct MischiefError {
 
     /// Optional URL pointing to related documentation.
     url: Option<String>,
+
+    /// Optional source text the labels point into.
+    source_code: Option<SourceCode>,
+
+    /// Labeled spans of the source text.
+    labels: Vec<Label>,
+
+    /// Context attached without adding a causal level.
+    attachments: Vec<Attachment>,
+
+    /// Independent errors reported together with this one.
+    pub(crate) related: Vec<Self>,
+
+    /// Original error this diagnosis was converted from.
+    pub(crate) typed: Option<Arc<dyn ITypedError>>,
+
+    /// Whether this diagnosis was converted from a source of the typed
+    /// error of an outer diagnosis.
+    pub(crate) derived: bool,
+}
+
+/// Original error kept by a diagnosis.
+pub(crate) trait ITypedError: Error + Send + Sync {
+    /// Takes the error out so it can be downcast by value, or gives it back
+    /// if it is still shared with a clone.
+    fn into_any(self: Arc<Self>) -> Result<Box<dyn Any>, Arc<dyn ITypedError>>;
+}
+
+impl<E: Error + Send + Sync + 'static> ITypedError for E {
+    fn into_any(self: Arc<Self>) -> Result<Box<dyn Any>, Arc<dyn ITypedError>> {
+        match Arc::try_unwrap(self) {
+            Ok(error) => Ok(Box::new(error)),
+            Err(shared) => Err(shared),
+        }
+    }
 }
 
 impl MischiefError {
@@ -79,13 +123,167 @@ impl MischiefError {
             severity,
             help: help.map(|s| s.to_string()),
             url: url.map(|s| s.to_string()),
+            source_code: None,
+            labels: Vec::new(),
+            attachments: Vec::new(),
+            related: Vec::new(),
+            typed: None,
+            derived: false,
+        }
+    }
+
+    /// Converts a standard error and its source chain, without keeping the
+    /// original value.
+    pub(crate) fn from_plain_error(error: &dyn Error) -> Self {
+        let mut converted = Self::convert_error(error);
+        converted.derived = false;
+        converted
+    }
+
+    /// Converts a standard error and its source chain, keeping the
+    /// original value.
+    pub(crate) fn from_error<E>(error: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        let mut converted = Self::convert_error(&error);
+        converted.derived = false;
+        converted.typed = Some(Arc::new(error));
+        converted
+    }
+
+    /// Converts an error implementing [`IDiagnosis`], keeping its metadata
+    /// and the original value.
+    pub(crate) fn from_diagnosis<E>(error: E) -> Self
+    where
+        E: IDiagnosis + Error + Send + Sync + 'static,
+    {
+        let mut converted = Self::convert_diagnosis(&error, Some(&error));
+        converted.typed = Some(Arc::new(error));
+        converted
+    }
+
+    /// Converts the chain of a standard error, marking every level as
+    /// derived from the typed error above it.
+    fn convert_error(err: &dyn Error) -> Self {
+        let mut error = Self::new(
+            &err.to_string(),
+            err.source().map(|src| Box::new(Self::convert_error(src))),
+            None,
+            None,
+            None,
+            None,
+        );
+        error.derived = true;
+        error
+    }
+
+    /// Converts a diagnosis and its sources. Once the diagnosis chain
+    /// ends, the remaining sources of `err` are converted as plain errors.
+    fn convert_diagnosis(diagnosis: &dyn IDiagnosis, err: Option<&dyn Error>) -> Self {
+        let err_source = err.and_then(Error::source);
+        let source = match diagnosis.source() {
+            Some(src) => {
+                let mut source = Self::convert_diagnosis(src, err_source);
+                source.derived = true;
+                Some(source)
+            }
+            None => err_source.map(Self::convert_error),
+        };
+        Self {
+            description: diagnosis.description().into_owned(),
+            source: source.map(Box::new),
+            code: diagnosis.code().map(String::from),
+            severity: diagnosis.severity(),
+            help: diagnosis.help().map(String::from),
+            url: diagnosis.url().map(String::from),
+            source_code: diagnosis.source_code().cloned(),
+            labels: diagnosis.labels().to_vec(),
+            attachments: diagnosis.attachments().to_vec(),
+            related: diagnosis
+                .related()
+                .map(|related| Self::convert_diagnosis(related, None))
+                .collect(),
+            typed: None,
+            derived: false,
         }
     }
+
+    /// Attaches the source text the labels of this diagnosis point into.
+    pub fn with_source_code(mut self, source_code: SourceCode) -> Self {
+        self.source_code = Some(source_code);
+        self
+    }
+
+    /// Adds a labeled span of the source text.
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    /// Attaches a key-value fact, such as a job id.
+    pub fn attach<K: Display, V: Display>(mut self, key: K, value: V) -> Self {
+        self.attachments.push(Attachment::Field {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
+    /// Attaches a free-form note.
+    pub fn note<N: Display>(mut self, note: N) -> Self {
+        self.attachments.push(Attachment::Note(note.to_string()));
+        self
+    }
+
+    /// Attaches a titled block of text, such as the output of a command.
+    pub fn with_section<T: Display, B: Display>(mut self, title: T, body: B) -> Self {
+        self.attachments.push(Attachment::Section {
+            title: title.to_string(),
+            body: body.to_string(),
+        });
+        self
+    }
+
+    /// Adds independent errors reported together with this one.
+    pub fn with_related<I>(mut self, related: I) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        self.related.extend(related);
+        self
+    }
+}
+
+/// Formats the description of the diagnosis.
+impl Display for MischiefError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+impl core::fmt::Debug for MischiefError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MischiefError")
+            .field("description", &self.description)
+            .field("code", &self.code)
+            .field("severity", &self.severity)
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Exposes the chain of descriptions to code expecting standard errors,
+/// such as `tracing` subscribers.
+impl Error for MischiefError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
+    }
 }
 
 impl IDiagnosis for MischiefError {
     /// Returns the primary description of the diagnosis.
-    fn description(&self) -> &str { &self.description }
+    fn description(&self) -> Cow<'_, str> { Cow::Borrowed(&self.description) }
 
     /// Returns the underlying diagnosis source, if present.
     ///
@@ -105,4 +303,18 @@ impl IDiagnosis for MischiefError {
 
     /// Returns the optional documentation URL for the diagnosis.
     fn url(&self) -> Option<&str> { self.url.as_deref() }
+
+    /// Returns the source text the labels point into, if present.
+    fn source_code(&self) -> Option<&SourceCode> { self.source_code.as_ref() }
+
+    /// Returns the labeled spans of the source text.
+    fn labels(&self) -> &[Label] { &self.labels }
+
+    /// Returns the context attached to the diagnosis.
+    fn attachments(&self) -> &[Attachment] { &self.attachments }
+
+    /// Returns the errors reported together with this one.
+    fn related(&self) -> Box<dyn Iterator<Item = &dyn IDiagnosis> + '_> {
+        Box::new(self.related.iter().map(|e| e as &dyn IDiagnosis))
+    }
 }
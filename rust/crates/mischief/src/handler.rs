@@ -0,0 +1,81 @@
+extern crate alloc;
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+use core::fmt;
+
+use crate::render::MischiefHandler;
+use crate::{IDiagnosis, Report};
+
+/// Trait for types rendering the diagnoses of a [`crate::Report`].
+///
+/// A handler decides the whole presentation of a report: the theme, the
+/// indentation, the wrapping width, or even a non-textual format. The
+/// handler used by every report can be replaced with [`set_hook`], and a
+/// single report can be rendered with another one through
+/// [`crate::Report::display_with`].
+pub trait IReportHandler: Send + Sync {
+    /// Renders `diagnosis` and its chain into `f`.
+    fn render(&self, diagnosis: &dyn IDiagnosis, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+
+    /// Renders a whole report into `f`.
+    ///
+    /// The default implementation renders the diagnoses of the report with
+    /// [`IReportHandler::render`], followed by its span trace and backtrace
+    /// if they were captured.
+    fn render_report(&self, report: &Report, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(report.error(), f)?;
+        #[cfg(feature = "tracing")]
+        if let Some(span_trace) = report.span_trace() {
+            crate::render_span_trace(span_trace, f)?;
+        }
+        #[cfg(feature = "backtrace")]
+        if let Some(backtrace) = report.backtrace() {
+            crate::render_backtrace(&backtrace, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Handler installed by [`set_hook`].
+#[cfg(feature = "std")]
+static HOOK: std::sync::RwLock<Option<Box<dyn IReportHandler>>> = std::sync::RwLock::new(None);
+
+/// Installs `handler` as the handler of every [`crate::Report`], replacing
+/// the previous one.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "fancy")]
+/// mischief::set_hook(mischief::render::MischiefHandler::no_color());
+/// ```
+#[cfg(feature = "std")]
+pub fn set_hook<H: IReportHandler + 'static>(handler: H) {
+    *HOOK
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(Box::new(handler));
+}
+
+/// Removes the handler installed by [`set_hook`], restoring the default
+/// [`MischiefHandler`].
+#[cfg(feature = "std")]
+pub fn take_hook() -> Option<Box<dyn IReportHandler>> {
+    HOOK.write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .take()
+}
+
+/// Renders a report with the installed handler, or the default one.
+pub(crate) fn render_hooked(report: &Report, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    #[cfg(feature = "std")]
+    if let Some(handler) = HOOK
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .as_deref()
+    {
+        return handler.render_report(report, f);
+    }
+    // The plain handler is a unit struct, the fancy one is not.
+    #[allow(clippy::default_constructed_unit_structs)]
+    MischiefHandler::default().render_report(report, f)
+}
@@ -0,0 +1,106 @@
+extern crate alloc;
+use alloc::string::{String, ToString};
+use core::fmt::Display;
+use core::ops::Range;
+
+/// Named source text a diagnosis points into.
+///
+/// The name is usually a file path such as `shook.toml`, and is shown
+/// in front of the line and column of the primary label.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceCode {
+    /// Name of the source, e.g. its file path.
+    name: String,
+
+    /// Full text of the source.
+    text: String,
+}
+
+impl SourceCode {
+    /// Creates a new `SourceCode` from a name and its text.
+    pub fn new<N, T>(name: N, text: T) -> Self
+    where
+        N: Display,
+        T: Into<String>,
+    {
+        Self {
+            name: name.to_string(),
+            text: text.into(),
+        }
+    }
+
+    /// Returns the name of the source.
+    pub fn name(&self) -> &str { &self.name }
+
+    /// Returns the full text of the source.
+    pub fn text(&self) -> &str { &self.text }
+
+    /// Returns the 1-based line and column of the byte `offset`.
+    ///
+    /// Columns count characters, not bytes. Offsets past the end of the
+    /// text are clamped to it.
+    pub fn location(&self, offset: usize) -> (usize, usize) {
+        let offset = floor_char_boundary(&self.text, offset);
+        let before = &self.text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+}
+
+/// A labeled byte span of a [`SourceCode`].
+///
+/// Primary labels mark the location of the problem itself and are
+/// underlined with `^`. Secondary labels add context, such as where a
+/// conflicting value was first defined, and are underlined with `-`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Label {
+    /// Byte range of the labeled text.
+    span: Range<usize>,
+
+    /// Optional message shown next to the underline.
+    message: Option<String>,
+
+    /// Whether the label marks the problem itself.
+    primary: bool,
+}
+
+impl Label {
+    /// Creates a primary label over `span`.
+    pub fn primary<M: Display>(span: Range<usize>, message: Option<M>) -> Self {
+        Self {
+            span,
+            message: message.map(|m| m.to_string()),
+            primary: true,
+        }
+    }
+
+    /// Creates a secondary label over `span`.
+    pub fn secondary<M: Display>(span: Range<usize>, message: Option<M>) -> Self {
+        Self {
+            span,
+            message: message.map(|m| m.to_string()),
+            primary: false,
+        }
+    }
+
+    /// Returns the byte range of the labeled text.
+    pub fn span(&self) -> Range<usize> { self.span.clone() }
+
+    /// Returns the message of the label, if any.
+    pub fn message(&self) -> Option<&str> { self.message.as_deref() }
+
+    /// Returns `true` for a primary label.
+    pub fn is_primary(&self) -> bool { self.primary }
+}
+
+/// Returns the largest char boundary of `text` not after `offset`.
+pub(crate) fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
@@ -14,10 +14,13 @@
 //!
 //! - A human-readable description
 //! - A causal source diagnostic
+//! - Related diagnostics reported alongside it
 //! - An optional error code
 //! - A severity classification
 //! - Optional help text
 //! - An optional documentation URL
+//! - An optional named source text with labeled spans
+//! - Attached key-value facts, notes and sections
 //!
 //! This abstraction allows errors to carry structured metadata that can be
 //! consumed by renderers, logging systems, or external tooling.
@@ -41,15 +44,24 @@
 //! A `Report` can be created directly from a `MischiefError`, or constructed
 //! automatically from any type implementing [`core::error::Error`]. During
 //! conversion, the entire source chain of the original error is recursively
-//! transformed into structured diagnostics.
+//! transformed into structured diagnostics. Errors converted with
+//! [`Report::from_error`] are kept as well, so callers can still branch on
+//! them with [`Report::downcast_ref`], [`Report::is`] or [`Report::chain`].
 //!
 //! The crate also provides utilities for working with `Report` values,
 //! including:
 //!
 //! - [`IntoMischief`] for converting results into diagnostic-aware results
 //! - [`WrapErr`] for attaching contextual diagnostics to existing errors
+//! - [`Report::from_diagnosis`] and [`IntoDiagnosis`] for errors deriving
+//!   `Diagnosis`, keeping their code, severity, help and URL
 //! - A convenient [`Result`] alias using `Report` as the default error type
 //!
+//! The `?` operator converts through the blanket [`From`] implementation,
+//! which cannot tell on stable Rust whether an error also implements
+//! [`IDiagnosis`]. An error deriving `Diagnosis` keeps its metadata when
+//! converted with `.into_diagnosis()?`, or with `.wrap_err(...)?`.
+//!
 //! # Rendering
 //!
 //! Diagnostic output can be rendered in multiple formats depending on the
@@ -60,13 +72,56 @@
 //! supports:
 //!
 //! - hierarchical error chains
+//! - related errors as sibling branches
 //! - customizable indentation styles
 //! - colored output
 //! - terminal hyperlinks
+//! - source snippets with labeled spans
 //!
 //! When the `fancy` feature is disabled, a minimal text renderer is used
 //! that prints the diagnostic chain in a simple, dependency-free format.
 //!
+//! Both renderers are exposed as a `MischiefHandler` implementing
+//! [`IReportHandler`]. Applications can install another handler for every
+//! report with [`set_hook`], e.g. a theme preset without colors for CI
+//! logs, or render a single report with [`Report::display_with`]. The
+//! default fancy theme honors the `NO_COLOR` and `CLICOLOR_FORCE`
+//! environment variables.
+//!
+//! With the `json` feature, reports can also be serialized for tooling:
+//! `to_json` and [`Report::to_json`] describe a diagnosis chain as JSON,
+//! while `to_sarif` and [`Report::to_sarif`] build a SARIF 2.1.0 log from
+//! the diagnoses carrying source locations. `JsonHandler` and
+//! `SarifHandler` install these formats for every report, e.g. behind an
+//! `--error-format json` flag.
+//!
+//! # Backtraces
+//!
+//! With the `backtrace` feature, reports capture a backtrace when they are
+//! created. Capture is governed at runtime by the `RUST_LIB_BACKTRACE` and
+//! `RUST_BACKTRACE` environment variables, in release builds as well, and
+//! can be overridden with [`set_backtrace_style`]. Symbols are only
+//! resolved once a report is rendered. Frames of the standard library,
+//! `tokio`, `backtrace` and `mischief` itself are hidden unless the style
+//! is [`BacktraceStyle::Full`].
+//!
+//! # Threads and Tracing
+//!
+//! `Report` is `Send + Sync + 'static`, so it can be returned from threads
+//! and async tasks. With the `tracing` feature, a report also captures a
+//! [`SpanTrace`] of the spans entered when it was created, rendered after
+//! its diagnoses. [`Report::as_error`] records a report as a structured
+//! `tracing` field, e.g. `error!(error = report.as_error(), "job failed")`,
+//! which subscribers write as a plain chain, and colored `clerk` layers
+//! with its `mischief` feature as the rendered report.
+//!
+//! # Panics
+//!
+//! [`install_panic_hook`] renders panics as reports too, with the panic
+//! message, its location and the backtrace laid out like any other error.
+//! [`PanicHook`] adds a footer asking users to report the bug, and can
+//! write a crash report file for each panic.
+//!
 //! # Severity
 //!
 //! Diagnostics may optionally include a [`Severity`] classification. This
@@ -132,15 +187,66 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 // #![feature(specialization)]
 // #![allow(incomplete_features)]
-pub use crate::report::{IntoMischief, Report, Result, WrapErr};
+pub use crate::report::{IntoDiagnosis, IntoMischief, Report, Result, WrapErr};
 mod error;
 pub(crate) mod report;
 pub use error::MischiefError;
+mod attachment;
+pub use attachment::Attachment;
+mod handler;
+pub use handler::IReportHandler;
+#[cfg(feature = "std")]
+pub use handler::{set_hook, take_hook};
+mod label;
+pub use label::{Label, SourceCode};
 mod protocol;
 pub use protocol::{IDiagnosis, Severity};
+#[cfg(feature = "tracing")]
+mod span_trace;
+#[cfg(feature = "tracing")]
+pub use span_trace::SpanTrace;
+#[cfg(feature = "backtrace")]
+mod trace;
+#[cfg(feature = "backtrace")]
+pub use trace::{BacktraceStyle, backtrace_style, set_backtrace_style};
+#[cfg(feature = "std")]
+mod panic;
+#[cfg(feature = "std")]
+pub use panic::{PanicHook, install_panic_hook};
 #[cfg(feature = "macros")]
 mod macros;
 #[cfg(feature = "macros")]
-pub use macros::mischief;
+pub use macros::{Diagnosis, mischief};
 pub mod render;
 pub use render::*;
+
+/// Items used by the code generated by `#[derive(Diagnosis)]`.
+#[doc(hidden)]
+pub mod __private {
+    extern crate alloc;
+    pub use alloc::borrow::Cow;
+    pub use alloc::boxed::Box;
+    pub use alloc::string::ToString;
+
+    use crate::IDiagnosis;
+
+    /// Wraps a source field to tell, by autoref specialization, whether its
+    /// type implements [`IDiagnosis`].
+    pub struct Source<'a, T>(pub &'a T);
+
+    pub trait IDiagnosisSource<'a> {
+        fn as_diagnosis(&self) -> Option<&'a dyn IDiagnosis>;
+    }
+
+    impl<'a, T: IDiagnosis> IDiagnosisSource<'a> for &Source<'a, T> {
+        fn as_diagnosis(&self) -> Option<&'a dyn IDiagnosis> { Some(self.0) }
+    }
+
+    pub trait IPlainSource<'a> {
+        fn as_diagnosis(&self) -> Option<&'a dyn IDiagnosis>;
+    }
+
+    impl<'a, T> IPlainSource<'a> for Source<'a, T> {
+        fn as_diagnosis(&self) -> Option<&'a dyn IDiagnosis> { None }
+    }
+}
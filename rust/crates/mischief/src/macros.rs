@@ -1,5 +1,5 @@
-/// Re-exports the `mischief!` procedural macro.
-pub use mischief_macros::mischief;
+/// Re-exports the `mischief!` procedural macro and the `Diagnosis` derive.
+pub use mischief_macros::{Diagnosis, mischief};
 
 /// Early-return macro for error handling.
 ///
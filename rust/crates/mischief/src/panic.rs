@@ -0,0 +1,168 @@
+use std::boxed::Box;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{IReportHandler, MischiefError, Report};
+
+/// Panic hook rendering panics as mischief reports.
+///
+/// A panic is shown as a report whose cause is the panic message, with the
+/// thread and location attached, followed by the backtrace. The report is
+/// rendered by the handler installed with [`crate::set_hook`], so panics
+/// share the theme and tree layout of errors.
+///
+/// # Example
+///
+/// ```no_run
+/// mischief::PanicHook::new()
+///     .with_issue_url("https://github.com/Glatzel/toolbox/issues")
+///     .with_crash_report(std::env::temp_dir())
+///     .install();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PanicHook {
+    /// URL where users should report the bug.
+    issue_url: Option<String>,
+
+    /// Directory crash reports are written into.
+    crash_report_dir: Option<PathBuf>,
+}
+
+impl PanicHook {
+    /// Creates a panic hook without bug report footer nor crash report.
+    pub fn new() -> Self { Self::default() }
+
+    /// Ends the output with a footer asking users to report the bug at
+    /// `url`.
+    pub fn with_issue_url<U: fmt::Display>(mut self, url: U) -> Self {
+        self.issue_url = Some(url.to_string());
+        self
+    }
+
+    /// Writes each panic, with its full backtrace, into a new file of
+    /// `dir`, and tells users where to find it.
+    ///
+    /// The backtrace is written even if [`crate::BacktraceStyle::Off`]
+    /// hides it from the terminal, and requires the `backtrace` feature.
+    pub fn with_crash_report<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.crash_report_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Replaces the current panic hook with this one.
+    pub fn install(self) { std::panic::set_hook(Box::new(move |info| self.report_panic(info))); }
+
+    /// Renders a panic to stderr and writes its crash report.
+    fn report_panic(&self, info: &PanicHookInfo<'_>) {
+        let report = Self::panic_report(info);
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(stderr, "{report}");
+        if let Some(dir) = &self.crash_report_dir {
+            match Self::write_crash_report(dir, &report) {
+                Ok(path) => {
+                    let _ = writeln!(stderr, "A crash report was written to {}", path.display());
+                }
+                Err(err) => {
+                    let _ = writeln!(stderr, "Failed to write a crash report: {err}");
+                }
+            }
+        }
+        if let Some(url) = &self.issue_url {
+            let _ = writeln!(stderr, "This is a bug. Please report it at {url}");
+        }
+    }
+
+    /// Builds the report of a panic.
+    fn panic_report(info: &PanicHookInfo<'_>) -> Report {
+        let message = info.payload_as_str().unwrap_or("Box<dyn Any>");
+        let thread = std::thread::current();
+        let mut error = MischiefError::new(
+            "The application panicked",
+            Some(Box::new(MischiefError::new(
+                message, None, None, None, None, None,
+            ))),
+            None,
+            None,
+            None,
+            None,
+        )
+        .attach("thread", thread.name().unwrap_or("<unnamed>"));
+        if let Some(location) = info.location() {
+            error = error.attach("location", location);
+        }
+        Report::new(error)
+    }
+
+    /// Writes the crash report of a panic into a new file of `dir`,
+    /// without colors, and returns its path.
+    fn write_crash_report(dir: &Path, report: &Report) -> io::Result<PathBuf> {
+        let elapsed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let stem = format!(
+            "crash-{}-{:09}-{}",
+            elapsed.as_secs(),
+            elapsed.subsec_nanos(),
+            std::process::id()
+        );
+        std::fs::create_dir_all(dir)?;
+        let (path, mut file) = create_new_file(dir, &stem)?;
+
+        let handler = cfg_select! {
+            any(feature = "color", feature = "pretty", feature = "hyperlink") => {
+                crate::render::MischiefHandler::no_color()
+            }
+            _ => crate::render::MischiefHandler,
+        };
+        write!(file, "{}", Diagnosis(report, &handler))?;
+        // The backtrace of the report is only captured if the terminal shows
+        // it, while the crash report always needs one.
+        #[cfg(feature = "backtrace")]
+        match report.backtrace() {
+            Some(backtrace) => writeln!(file, "\nBacktrace:\n{:?}", &*backtrace)?,
+            None => writeln!(file, "\nBacktrace:\n{:?}", backtrace::Backtrace::new())?,
+        }
+        Ok(path)
+    }
+}
+
+/// Creates a new file named after `stem` in `dir`, adding a counter to the
+/// name while it is taken, e.g. by the crash report of another thread.
+fn create_new_file(dir: &Path, stem: &str) -> io::Result<(PathBuf, File)> {
+    let mut attempt = 0_u32;
+    loop {
+        let path = match attempt {
+            0 => dir.join(format!("{stem}.txt")),
+            n => dir.join(format!("{stem}-{n}.txt")),
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Displays the diagnosis of a report with a handler, leaving out the
+/// backtrace and span trace its [`IReportHandler::render_report`] would
+/// render with the colors of the terminal.
+struct Diagnosis<'a>(&'a Report, &'a dyn IReportHandler);
+
+impl fmt::Display for Diagnosis<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.1.render(self.0.error(), f) }
+}
+
+/// Installs a [`PanicHook`] without bug report footer nor crash report.
+///
+/// # Example
+///
+/// ```no_run
+/// mischief::install_panic_hook();
+/// panic!("index out of bounds");
+/// ```
+pub fn install_panic_hook() { PanicHook::new().install(); }
@@ -1,5 +1,10 @@
+extern crate alloc;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
 use core::fmt::{Debug, Display};
 
+use crate::{Attachment, Label, SourceCode};
+
 /// Trait representing structured diagnosis information.
 ///
 /// Types implementing this trait describe errors or diagnosis conditions
@@ -21,7 +26,11 @@ pub trait IDiagnosis {
     /// This should provide a concise explanation of the problem.
     /// It is typically used as the primary message when displaying
     /// the diagnosis.
-    fn description(&self) -> &str;
+    ///
+    /// A description stored by the diagnosis is borrowed, while types
+    /// formatting it on demand, such as the ones deriving `Diagnosis`,
+    /// return it owned.
+    fn description(&self) -> Cow<'_, str>;
 
     /// Returns the underlying cause of this diagnosis, if any.
     ///
@@ -67,9 +76,45 @@ pub trait IDiagnosis {
     /// to the diagnosis.
     fn url(&self) -> Option<&str>;
 
-    // Optional future extensions for source code snippets or labels:
-    // fn source_code<'a>(&'a self) -> Option<&'a str>;
-    // fn labels<'a>(&'a self) -> Option<&'a str>;
+    /// Returns the source text the labels of this diagnosis point into.
+    ///
+    /// Renderers show the labeled lines of the source under the
+    /// description. Returning `None` indicates that the diagnosis is
+    /// not tied to a source.
+    fn source_code(&self) -> Option<&SourceCode> { None }
+
+    /// Returns the labeled spans of [`IDiagnosis::source_code`].
+    ///
+    /// Labels are ignored when the diagnosis has no source code.
+    fn labels(&self) -> &[Label] { &[] }
+
+    /// Returns the context attached to this diagnosis.
+    ///
+    /// Renderers show attachments as a block under the diagnosis, after
+    /// its source snippet.
+    fn attachments(&self) -> &[Attachment] { &[] }
+
+    /// Returns independent diagnoses reported together with this one.
+    ///
+    /// Unlike [`IDiagnosis::source`], related diagnoses are not causes
+    /// but siblings, such as every failed field of a validated
+    /// configuration. Renderers show each of them as a separate branch.
+    fn related(&self) -> Box<dyn Iterator<Item = &dyn IDiagnosis> + '_> {
+        Box::new(core::iter::empty())
+    }
+}
+
+impl<T: IDiagnosis + ?Sized> IDiagnosis for &T {
+    fn description(&self) -> Cow<'_, str> { (**self).description() }
+    fn source(&self) -> Option<&dyn IDiagnosis> { (**self).source() }
+    fn code(&self) -> Option<&str> { (**self).code() }
+    fn severity(&self) -> Option<Severity> { (**self).severity() }
+    fn help(&self) -> Option<&str> { (**self).help() }
+    fn url(&self) -> Option<&str> { (**self).url() }
+    fn source_code(&self) -> Option<&SourceCode> { (**self).source_code() }
+    fn labels(&self) -> &[Label] { (**self).labels() }
+    fn attachments(&self) -> &[Attachment] { (**self).attachments() }
+    fn related(&self) -> Box<dyn Iterator<Item = &dyn IDiagnosis> + '_> { (**self).related() }
 }
 
 /// Represents the severity level associated with a diagnosis.
@@ -1,8 +1,21 @@
 #[cfg(any(feature = "color", feature = "pretty", feature = "hyperlink"))]
 mod fancy_render;
+#[cfg(feature = "json")]
+mod json;
 #[cfg(not(any(feature = "color", feature = "pretty", feature = "hyperlink")))]
 mod no_fancy_render;
+#[cfg(feature = "json")]
+mod sarif;
+mod snippet;
 #[cfg(any(feature = "color", feature = "pretty", feature = "hyperlink"))]
 pub use fancy_render::*;
+#[cfg(all(feature = "json", feature = "backtrace"))]
+pub(crate) use json::backtrace_to_json;
+#[cfg(all(feature = "json", feature = "tracing"))]
+pub(crate) use json::span_trace_to_json;
+#[cfg(feature = "json")]
+pub use json::{JsonHandler, to_json};
 #[cfg(not(any(feature = "color", feature = "pretty", feature = "hyperlink")))]
 pub use no_fancy_render::*;
+#[cfg(feature = "json")]
+pub use sarif::{SarifHandler, to_sarif};
@@ -2,13 +2,15 @@ use core::fmt::{self, Write};
 extern crate alloc;
 use alloc::format;
 use alloc::string::{String, ToString};
+use std::ffi::OsStr;
 
 use arbor::protocol::{IIndent, Layer, Line};
 use arbor::renders::OwnedRender;
 use arbor::trees::OwnedTree;
-use owo_colors::{OwoColorize, Style};
+use owo_colors::{AnsiColors, OwoColorize, Style};
 
-use crate::{IDiagnosis, Severity};
+use super::snippet::{Paint, render_snippet};
+use crate::{Attachment, IDiagnosis, IReportHandler, Severity};
 
 /// Indentation configuration used when rendering diagnostic trees.
 ///
@@ -38,6 +40,13 @@ pub struct MischiefIndent {
 
     /// Prefix used for continuation lines of intermediate diagnostics.
     pub other: String,
+
+    /// Glyph pointing from a backtrace frame to its source location.
+    pub frame: &'static str,
+
+    /// Glyph repeated to draw the rules around backtrace and span trace
+    /// titles.
+    pub rule: &'static str,
 }
 
 impl IIndent for MischiefIndent {
@@ -53,50 +62,100 @@ impl IIndent for MischiefIndent {
     }
 }
 
+impl MischiefIndent {
+    /// Creates an indentation drawn with Unicode box characters, painted
+    /// with `color`.
+    pub fn unicode(color: Option<AnsiColors>) -> Self {
+        let paint = |text: &str| paint(text, color);
+        Self {
+            root_first: paint("x "),
+            root_other: paint("│ "),
+            top_middle_first: paint("├─▶ "),
+            bottom_first: paint("╰─▶ "),
+            bottom_other: "    ",
+            other: paint("│   "),
+            frame: "╰─",
+            rule: "═",
+        }
+    }
+
+    /// Creates an indentation drawn with ASCII characters only, painted
+    /// with `color`.
+    pub fn ascii(color: Option<AnsiColors>) -> Self {
+        let paint = |text: &str| paint(text, color);
+        Self {
+            root_first: paint("x "),
+            root_other: paint("| "),
+            top_middle_first: paint("|-- "),
+            bottom_first: paint("`-- "),
+            bottom_other: "    ",
+            other: paint("|   "),
+            frame: "`-",
+            rule: "=",
+        }
+    }
+}
+
 impl Default for MischiefIndent {
     /// Creates a default indentation configuration.
     ///
-    /// The implementation automatically chooses Unicode tree glyphs
-    /// when the terminal supports Unicode output. Otherwise, an ASCII
-    /// fallback representation is used.
+    /// Unicode tree glyphs are used when the `pretty` feature is enabled,
+    /// ASCII ones otherwise. The glyphs are red when colors are enabled,
+    /// see [`color_enabled`].
     fn default() -> Self {
-        cfg_select! {
-            all(feature = "color", feature = "pretty") => Self {
-                root_first: "x ".red().to_string(),
-                root_other: "│ ".red().to_string(),
-                top_middle_first: "├─▶ ".red().to_string(),
-                bottom_first: "╰─▶ ".red().to_string(),
-                bottom_other: "    ",
-                other: "│   ".red().to_string(),
-            },
-            all(feature = "color", not(feature = "pretty")) => Self {
-                root_first: "x".red().to_string(),
-                root_other: "| ".red().to_string(),
-                top_middle_first: "|-- ".red().to_string(),
-                bottom_first: "`-- ".red().to_string(),
-                bottom_other: "    ",
-                other: "|   ".red().to_string(),
-            },
-            all(not(feature = "color"), feature = "pretty") => Self {
-                root_first: "x ".to_string(),
-                root_other: "│ ".to_string(),
-                top_middle_first: "├─▶ ".to_string(),
-                bottom_first: "╰─▶ ".to_string(),
-                bottom_other: "    ",
-                other: "│   ".to_string(),
-            },
-            all(not(feature = "color"), not(feature = "pretty")) => Self {
-                root_first: "x".to_string(),
-                root_other: "| ".to_string(),
-                top_middle_first: "|-- ".to_string(),
-                bottom_first: "`-- ".to_string(),
-                bottom_other: "    ",
-                other: "|   ".to_string(),
-            },
+        let color = color_enabled().then_some(AnsiColors::Red);
+        if cfg!(feature = "pretty") {
+            Self::unicode(color)
+        } else {
+            Self::ascii(color)
         }
     }
 }
 
+/// Formats `text` with `style`, if any.
+#[cfg(any(feature = "backtrace", feature = "tracing"))]
+fn styled<T: fmt::Display>(text: T, style: Option<&Style>) -> String {
+    match style {
+        Some(style) => text.style(*style).to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Paints `text` with `color`, if any.
+fn paint(text: &str, color: Option<AnsiColors>) -> String {
+    match color {
+        Some(color) => text.color(color).to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Returns `true` if diagnoses should be rendered with colors.
+///
+/// Reads the `NO_COLOR` and `CLICOLOR_FORCE` environment variables, see
+/// [`color_enabled_by`].
+pub fn color_enabled() -> bool {
+    color_enabled_by(
+        std::env::var_os("NO_COLOR").as_deref(),
+        std::env::var_os("CLICOLOR_FORCE").as_deref(),
+    )
+}
+
+/// Returns `true` if diagnoses should be rendered with colors, given the
+/// values of the `NO_COLOR` and `CLICOLOR_FORCE` environment variables.
+///
+/// Colors are forced by a `CLICOLOR_FORCE` other than `0`, or else disabled
+/// by `NO_COLOR`. Empty values count as unset. Otherwise colors follow the
+/// `color` feature.
+pub fn color_enabled_by(no_color: Option<&OsStr>, clicolor_force: Option<&OsStr>) -> bool {
+    if clicolor_force.is_some_and(|value| !value.is_empty() && value != "0") {
+        return true;
+    }
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    cfg!(feature = "color")
+}
+
 /// Describes how hyperlinks should be rendered in diagnostic output.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum HyperlinkFormat {
@@ -133,6 +192,18 @@ pub trait ITheme {
 
     /// Returns the style and rendering format for hyperlinks.
     fn hyperlink_style(&self) -> &(Option<Style>, HyperlinkFormat);
+
+    /// Returns the style of the line numbers and separators of source
+    /// snippets.
+    fn gutter_style(&self) -> Option<&Style> { None }
+
+    /// Returns the style of the underline and message of a primary or
+    /// secondary label.
+    fn label_style(&self, _primary: bool) -> Option<&Style> { None }
+
+    /// Returns the style of the keys, note prefixes and section titles of
+    /// attachments. Defaults to the style of the `help:` prefix.
+    fn attachment_style(&self) -> Option<&Style> { self.help_style().0.as_ref() }
 }
 
 /// Default styling implementation for diagnosis output.
@@ -141,11 +212,11 @@ pub trait ITheme {
 /// including colors for severity labels, help messages, and hyperlinks.
 /// Styling is implemented using the `owo_colors` crate.
 ///
-/// The default configuration automatically adapts to terminal capabilities,
-/// enabling color and hyperlink support only when supported by the output
-/// stream.
+/// The default theme is [`MischiefTheme::colored`] when colors are
+/// enabled, see [`color_enabled`], and [`MischiefTheme::no_color`]
+/// otherwise.
 #[derive(Debug, Clone)]
-struct MischiefTheme {
+pub struct MischiefTheme {
     /// Style applied to general text.
     pub default_style: Option<Style>,
 
@@ -166,58 +237,123 @@ struct MischiefTheme {
 
     /// Style and format used when rendering hyperlinks.
     pub hyperlink_style: (Option<Style>, HyperlinkFormat),
+
+    /// Style applied to the gutter of source snippets.
+    pub gutter_style: Option<Style>,
+
+    /// Style applied to primary labels.
+    pub label_primary_style: Option<Style>,
+
+    /// Style applied to secondary labels.
+    pub label_secondary_style: Option<Style>,
+
+    /// Style applied to the frames of backtraces.
+    pub trace_style: Option<Style>,
+
+    /// Style applied to the titles of backtraces and span traces.
+    pub title_style: Option<Style>,
+
+    /// Style applied to the rules around titles and to the hidden frames
+    /// note of backtraces.
+    pub rule_style: Option<Style>,
+}
+
+impl MischiefTheme {
+    /// Creates the colored theme.
+    pub fn colored() -> Self {
+        Self {
+            default_style: None,
+            description_style: Some(Style::default()),
+            severity_advice_style: Some(Style::new().green()),
+            severity_warning_style: Some(Style::new().yellow()),
+            severity_error_style: Some(Style::new().red()),
+            help_style: Default::default(),
+            hyperlink_style: (Some(Style::new().blue()), hyperlink_format()),
+            gutter_style: Some(Style::new().blue().bold()),
+            label_primary_style: Some(Style::new().red().bold()),
+            label_secondary_style: Some(Style::new().blue().bold()),
+            trace_style: Some(Style::new().fg::<owo_colors::colors::css::CadetBlue>()),
+            title_style: Some(Style::new().bold()),
+            rule_style: Some(Style::new().bright_black()),
+        }
+    }
+
+    /// Creates a theme without any style or terminal hyperlink, e.g. for CI
+    /// logs.
+    pub fn no_color() -> Self {
+        Self {
+            default_style: None,
+            description_style: None,
+            severity_advice_style: None,
+            severity_warning_style: None,
+            severity_error_style: None,
+            help_style: (None, None),
+            hyperlink_style: (None, HyperlinkFormat::Plain),
+            gutter_style: None,
+            label_primary_style: None,
+            label_secondary_style: None,
+            trace_style: None,
+            title_style: None,
+            rule_style: None,
+        }
+    }
+
+    /// Creates a theme of bold, bright colors for low contrast terminals.
+    pub fn high_contrast() -> Self {
+        Self {
+            default_style: None,
+            description_style: Some(Style::new().bold()),
+            severity_advice_style: Some(Style::new().bright_green().bold()),
+            severity_warning_style: Some(Style::new().bright_yellow().bold()),
+            severity_error_style: Some(Style::new().bright_red().bold()),
+            help_style: (Some(Style::new().bright_cyan().bold()), None),
+            hyperlink_style: (
+                Some(Style::new().bright_blue().underline()),
+                hyperlink_format(),
+            ),
+            gutter_style: Some(Style::new().bright_white().bold()),
+            label_primary_style: Some(Style::new().bright_red().bold()),
+            label_secondary_style: Some(Style::new().bright_cyan().bold()),
+            trace_style: Some(Style::new().bright_cyan()),
+            title_style: Some(Style::new().bright_white().bold()),
+            rule_style: Some(Style::new().bright_white()),
+        }
+    }
 }
 
 impl Default for MischiefTheme {
-    /// Creates a theme configured according to terminal capabilities.
-    ///
-    /// Color and hyperlink support are enabled only when the terminal
-    /// reports compatibility.
+    /// Creates a theme configured according to [`color_enabled`].
     fn default() -> Self {
-        cfg_select! {
-            feature = "color" => {
-                let default_style = None;
-                let description_style = Some(Style::default());
-                let severity_advice_style = Some(Style::new().green());
-                let severity_warning_style = Some(Style::new().yellow());
-                let severity_error_style = Some(Style::new().red());
-                let help_style = Default::default();
-            }
-            _ => {
-                let default_style = None;
-                let description_style = None;
-                let severity_advice_style = None;
-                let severity_warning_style = None;
-                let severity_error_style = None;
-                let help_style = (None, None);
-            }
-        };
-        cfg_select! {
-            all(feature = "hyperlink", feature = "color") => {
-                let hyperlink_style = (Some(Style::new().blue()), HyperlinkFormat::Link);
-            }
-            all(feature = "hyperlink", not(feature = "color")) => {
-                let hyperlink_style = (None, HyperlinkFormat::Link);
-            }
-            all(not(feature = "hyperlink"), feature = "color") => {
-                let hyperlink_style = (Some(Style::new().blue()), HyperlinkFormat::Plain);
-            }
-            _ => {
-                let hyperlink_style = (None, HyperlinkFormat::Plain);
-            }
-        };
-        Self {
-            default_style,
-            description_style,
-            severity_advice_style,
-            severity_warning_style,
-            severity_error_style,
-            help_style,
-            hyperlink_style,
+        if color_enabled() {
+            Self::colored()
+        } else {
+            Self::no_color()
         }
     }
 }
 
+/// Returns the hyperlink format enabled by the `hyperlink` feature.
+const fn hyperlink_format() -> HyperlinkFormat {
+    if cfg!(feature = "hyperlink") {
+        HyperlinkFormat::Link
+    } else {
+        HyperlinkFormat::Plain
+    }
+}
+
+impl<T: ITheme + ?Sized> ITheme for &T {
+    fn default_style(&self) -> Option<&Style> { (**self).default_style() }
+    fn description_style(&self) -> Option<&Style> { (**self).description_style() }
+    fn severity_style(&self, severity: Option<Severity>) -> Option<&Style> {
+        (**self).severity_style(severity)
+    }
+    fn help_style(&self) -> &(Option<Style>, Option<Style>) { (**self).help_style() }
+    fn hyperlink_style(&self) -> &(Option<Style>, HyperlinkFormat) { (**self).hyperlink_style() }
+    fn gutter_style(&self) -> Option<&Style> { (**self).gutter_style() }
+    fn label_style(&self, primary: bool) -> Option<&Style> { (**self).label_style(primary) }
+    fn attachment_style(&self) -> Option<&Style> { (**self).attachment_style() }
+}
+
 impl ITheme for MischiefTheme {
     fn default_style(&self) -> Option<&Style> { self.default_style.as_ref() }
     fn description_style(&self) -> Option<&Style> { self.description_style.as_ref() }
@@ -234,6 +370,16 @@ impl ITheme for MischiefTheme {
     fn help_style(&self) -> &(Option<Style>, Option<Style>) { &self.help_style }
 
     fn hyperlink_style(&self) -> &(Option<Style>, HyperlinkFormat) { &self.hyperlink_style }
+
+    fn gutter_style(&self) -> Option<&Style> { self.gutter_style.as_ref() }
+
+    fn label_style(&self, primary: bool) -> Option<&Style> {
+        if primary {
+            self.label_primary_style.as_ref()
+        } else {
+            self.label_secondary_style.as_ref()
+        }
+    }
 }
 
 /// Rendering context used to format a [`crate::IDiagnosis`] as a diagnosis
@@ -264,8 +410,8 @@ impl<D: IDiagnosis, I: IIndent, T: ITheme> RenderBundle<'_, D, I, T> {
     /// Formats a single diagnosis entry as a styled string.
     ///
     /// The output may include severity labels, error codes, hyperlinks,
-    /// descriptions, and optional help messages depending on the
-    /// metadata provided by the diagnosis.
+    /// descriptions, source snippets, attachments, and optional help
+    /// messages depending on the metadata provided by the diagnosis.
     pub fn render(&self, diagnosis: &dyn IDiagnosis, theme: &T) -> String {
         use core::fmt::Write;
 
@@ -291,11 +437,47 @@ impl<D: IDiagnosis, I: IIndent, T: ITheme> RenderBundle<'_, D, I, T> {
 
         Self::apply_style(
             &mut buffer,
-            diagnosis.description(),
+            &diagnosis.description(),
             theme.description_style(),
         )
         .unwrap();
 
+        if let Some(source_code) = diagnosis.source_code() {
+            writeln!(buffer).unwrap();
+            let snippet = render_snippet(source_code, diagnosis.labels(), |paint, text| {
+                let style = match paint {
+                    Paint::Gutter => theme.gutter_style(),
+                    Paint::Primary => theme.label_style(true),
+                    Paint::Secondary => theme.label_style(false),
+                };
+                let mut painted = String::new();
+                Self::apply_style(&mut painted, text, style).unwrap();
+                painted
+            });
+            buffer.push_str(&snippet);
+        }
+
+        for attachment in diagnosis.attachments() {
+            writeln!(buffer).unwrap();
+            let style = theme.attachment_style();
+            match attachment {
+                Attachment::Field { key, value } => {
+                    Self::apply_style(&mut buffer, &format!("{key}: "), style).unwrap();
+                    buffer.push_str(value);
+                }
+                Attachment::Note(note) => {
+                    Self::apply_style(&mut buffer, "note: ", style).unwrap();
+                    buffer.push_str(note);
+                }
+                Attachment::Section { title, body } => {
+                    Self::apply_style(&mut buffer, &format!("{title}:"), style).unwrap();
+                    for line in body.lines() {
+                        write!(buffer, "\n  {line}").unwrap();
+                    }
+                }
+            }
+        }
+
         if let Some(s) = diagnosis.help() {
             writeln!(buffer).unwrap();
             Self::apply_style(&mut buffer, "help: ", theme.help_style().0.as_ref()).unwrap();
@@ -305,6 +487,25 @@ impl<D: IDiagnosis, I: IIndent, T: ITheme> RenderBundle<'_, D, I, T> {
         buffer
     }
 
+    /// Builds the tree of a diagnosis: its causal chain as leaves of the
+    /// root, followed by the trees of its related diagnoses.
+    fn tree(&self, diagnosis: &dyn IDiagnosis) -> OwnedTree<String> {
+        let mut tree = OwnedTree::new(self.render(diagnosis, &self.theme));
+
+        let mut source = diagnosis.source();
+        while let Some(e) = source {
+            tree.push(
+                OwnedTree::new(self.render(e, &self.theme))
+                    .with_leaves(e.related().map(|related| self.tree(related))),
+            );
+            source = e.source();
+        }
+        for related in diagnosis.related() {
+            tree.push(self.tree(related));
+        }
+        tree
+    }
+
     /// Applies a text style to a string and writes it into the buffer.
     fn apply_style(
         buffer: &mut String,
@@ -353,14 +554,9 @@ impl<D: IDiagnosis, I: IIndent + Clone, T: ITheme> fmt::Display for RenderBundle
     ///
     /// Each diagnosis in the causal chain is converted into a tree
     /// node and rendered using the configured indentation and theme.
+    /// Related diagnoses follow the chain as sibling branches.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut tree = OwnedTree::new(self.render(self.diagnosis as &dyn IDiagnosis, &self.theme));
-
-        let mut source = self.diagnosis.source();
-        while let Some(e) = source {
-            tree.push(self.render(e, &self.theme));
-            source = e.source();
-        }
+        let tree = self.tree(self.diagnosis as &dyn IDiagnosis);
 
         let render = OwnedRender {
             tree: &tree,
@@ -372,97 +568,226 @@ impl<D: IDiagnosis, I: IIndent + Clone, T: ITheme> fmt::Display for RenderBundle
         write!(f, "{render}")
     }
 }
-pub fn render_diagnosis<D>(diagnosis: &D, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
-where
-    D: IDiagnosis,
-{
-    let bundle = RenderBundle {
-        diagnosis,
-        theme: MischiefTheme::default(),
-        indent: MischiefIndent::default(),
-        #[cfg(feature = "textwrap")]
-        width: match terminal_size::terminal_size() {
-            Some((w, _)) => w.0 as usize,
-            None => 0,
-        },
-    };
-    writeln!(f, "{bundle}")
+/// Default report handler, rendering diagnoses as a themed tree.
+///
+/// The presets cover common needs; the fields can also be set directly to
+/// combine a theme with another indentation.
+///
+/// # Example
+///
+/// ```
+/// use mischief::render::{MischiefHandler, MischiefIndent};
+/// let handler = MischiefHandler {
+///     indent: MischiefIndent::ascii(None),
+///     ..MischiefHandler::high_contrast()
+/// };
+/// mischief::set_hook(handler);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MischiefHandler {
+    /// Theme used for styling output.
+    pub theme: MischiefTheme,
+
+    /// Indentation of the diagnosis tree.
+    pub indent: MischiefIndent,
+
+    /// Wrapping width, or `None` for the width of the terminal.
+    #[cfg(feature = "textwrap")]
+    pub width: Option<usize>,
 }
 
-#[cfg(all(feature = "backtrace", debug_assertions))]
-pub fn render_backtrace(
-    backtrace: &backtrace::Backtrace,
-    f: &mut fmt::Formatter<'_>,
-) -> fmt::Result {
-    let title = " Backtrace ";
+impl MischiefHandler {
+    /// Creates a handler without any color, e.g. for CI logs.
+    #[cfg_attr(not(feature = "textwrap"), allow(clippy::needless_update))]
+    pub fn no_color() -> Self {
+        Self {
+            theme: MischiefTheme::no_color(),
+            indent: if cfg!(feature = "pretty") {
+                MischiefIndent::unicode(None)
+            } else {
+                MischiefIndent::ascii(None)
+            },
+            ..Self::default()
+        }
+    }
 
-    let width =
-        terminal_size::terminal_size().map_or(80, |(terminal_size::Width(w), _)| w as usize);
-    let left = (width - title.len()) / 2;
-    let right = width - title.len() - left;
+    /// Creates a handler with the [high contrast](MischiefTheme::high_contrast)
+    /// theme.
+    #[cfg_attr(not(feature = "textwrap"), allow(clippy::needless_update))]
+    pub fn high_contrast() -> Self {
+        let color = Some(AnsiColors::BrightRed);
+        Self {
+            theme: MischiefTheme::high_contrast(),
+            indent: if cfg!(feature = "pretty") {
+                MischiefIndent::unicode(color)
+            } else {
+                MischiefIndent::ascii(color)
+            },
+            ..Self::default()
+        }
+    }
 
-    let indent = cfg_select! {
-        feature = "pretty" => "╰─",
-        _ => "`-".to_string(),
-    };
+    /// Creates a handler with the default theme, drawing the tree with
+    /// ASCII characters only.
+    pub fn ascii() -> Self {
+        Self {
+            indent: MischiefIndent::ascii(color_enabled().then_some(AnsiColors::Red)),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the wrapping width, or else the width of the terminal.
+    #[cfg(any(feature = "textwrap", feature = "backtrace", feature = "tracing"))]
+    fn width(&self) -> Option<usize> {
+        #[cfg(feature = "textwrap")]
+        if let Some(width) = self.width {
+            return Some(width);
+        }
+        cfg_select! {
+            feature = "terminal_size" => {
+                terminal_size::terminal_size().map(|(terminal_size::Width(w), _)| w as usize)
+            }
+            _ => None,
+        }
+    }
 
-    cfg_select! {
-        feature = "color" => {
-            use owo_colors::CssColors::CadetBlue;
+    /// Renders the frames of a backtrace shown with the current
+    /// [`crate::BacktraceStyle`] under a `Backtrace` title.
+    #[cfg(feature = "backtrace")]
+    pub fn render_backtrace(
+        &self,
+        backtrace: &backtrace::Backtrace,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let style = crate::backtrace_style();
+        if style == crate::BacktraceStyle::Off {
+            return Ok(());
+        }
+        let (frames, hidden) = crate::trace::frames(backtrace, style);
+        self.write_title(f, " Backtrace ")?;
 
+        let trace_style = self.theme.trace_style.as_ref();
+        for frame in &frames {
             writeln!(
                 f,
-                "{}{}{}",
-                "═".repeat(left).fg::<owo_colors::colors::BrightBlack>(),
-                title.bold(),
-                "═".repeat(right).fg::<owo_colors::colors::BrightBlack>(),
+                "{}: {}",
+                styled(format_args!("{:>4}", frame.index), trace_style),
+                styled(&frame.name, trace_style)
             )?;
 
-            // write frames
-            for (idx, frame) in backtrace.frames().iter().enumerate() {
-                for symbol in frame.symbols() {
-                    let name = symbol
-                        .name()
-                        .map_or_else(|| "<unknown>".into(), |n| n.to_string());
-
-                    writeln!(f, "{:>4}: {}", idx.color(CadetBlue), name.color(CadetBlue))?;
-
-                    if let Some(file) = symbol.filename() {
-                        write!(f, "   {} {}", indent.color(CadetBlue), file.display())?;
+            if let Some(file) = frame.file {
+                write!(
+                    f,
+                    "   {} {}",
+                    styled(self.indent.frame, trace_style),
+                    file.display()
+                )?;
 
-                        if let Some(line) = symbol.lineno() {
-                            write!(f, ":{line}")?;
-                        }
-
-                        writeln!(f)?;
-                    }
+                if let Some(line) = frame.line {
+                    write!(f, ":{line}")?;
                 }
+
+                writeln!(f)?;
             }
         }
-        _ => {
-            writeln!(f, "{}{}{}", "═".repeat(left), title, "═".repeat(right),)?;
+        if hidden > 0 {
+            let note = format!("{hidden} frames hidden, set RUST_BACKTRACE=full to show them");
+            writeln!(f, "{}", styled(note, self.theme.rule_style.as_ref()))?;
+        }
+        Ok(())
+    }
 
-            // write frames
-            for (idx, frame) in backtrace.frames().iter().enumerate() {
-                for symbol in frame.symbols() {
-                    let name = symbol
-                        .name()
-                        .map_or_else(|| "<unknown>".into(), |n| n.to_string());
+    /// Renders the spans of a span trace under a `Span trace` title.
+    #[cfg(feature = "tracing")]
+    pub fn render_span_trace(
+        &self,
+        span_trace: &crate::SpanTrace,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        self.write_title(f, " Span trace ")?;
+        write!(f, "{span_trace}")
+    }
 
-                    writeln!(f, "{:>4}: {}", idx, name)?;
+    /// Writes a title centered in a rule as wide as the wrapping width, or
+    /// 80 columns if unknown.
+    #[cfg(any(feature = "backtrace", feature = "tracing"))]
+    fn write_title(&self, f: &mut fmt::Formatter<'_>, title: &str) -> fmt::Result {
+        let width = self.width().unwrap_or(80);
+        let left = width.saturating_sub(title.len()) / 2;
+        let right = width.saturating_sub(title.len() + left);
+        let rule_style = self.theme.rule_style.as_ref();
+        writeln!(
+            f,
+            "{}{}{}",
+            styled(self.indent.rule.repeat(left), rule_style),
+            styled(title, self.theme.title_style.as_ref()),
+            styled(self.indent.rule.repeat(right), rule_style),
+        )
+    }
+}
 
-                    if let Some(file) = symbol.filename() {
-                        write!(f, "   {} {}", indent, file.display())?;
+impl Default for MischiefHandler {
+    /// Creates a handler with the default theme and indentation, wrapping
+    /// lines at the width of the terminal.
+    fn default() -> Self {
+        Self {
+            theme: MischiefTheme::default(),
+            indent: MischiefIndent::default(),
+            #[cfg(feature = "textwrap")]
+            width: None,
+        }
+    }
+}
 
-                        if let Some(line) = symbol.lineno() {
-                            write!(f, ":{line}")?;
-                        }
+impl IReportHandler for MischiefHandler {
+    fn render(&self, diagnosis: &dyn IDiagnosis, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bundle = RenderBundle {
+            diagnosis: &diagnosis,
+            theme: &self.theme,
+            indent: self.indent.clone(),
+            #[cfg(feature = "textwrap")]
+            width: self.width().unwrap_or(0),
+        };
+        writeln!(f, "{bundle}")
+    }
 
-                        writeln!(f)?;
-                    }
-                }
-            }
+    /// Renders the diagnoses of the report, followed by its span trace and
+    /// backtrace drawn with the theme, indentation and width of the handler.
+    fn render_report(&self, report: &crate::Report, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(report.error(), f)?;
+        #[cfg(feature = "tracing")]
+        if let Some(span_trace) = report.span_trace() {
+            self.render_span_trace(span_trace, f)?;
         }
-    };
-    Ok(())
+        #[cfg(feature = "backtrace")]
+        if let Some(backtrace) = report.backtrace() {
+            self.render_backtrace(&backtrace, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders a diagnosis with the default [`MischiefHandler`].
+pub fn render_diagnosis<D>(diagnosis: &D, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+where
+    D: IDiagnosis,
+{
+    MischiefHandler::default().render(diagnosis, f)
+}
+
+/// Renders a backtrace with the default [`MischiefHandler`], see
+/// [`MischiefHandler::render_backtrace`].
+#[cfg(feature = "backtrace")]
+pub fn render_backtrace(
+    backtrace: &backtrace::Backtrace,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    MischiefHandler::default().render_backtrace(backtrace, f)
+}
+
+/// Renders a span trace with the default [`MischiefHandler`], see
+/// [`MischiefHandler::render_span_trace`].
+#[cfg(feature = "tracing")]
+pub fn render_span_trace(span_trace: &crate::SpanTrace, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    MischiefHandler::default().render_span_trace(span_trace, f)
 }
@@ -0,0 +1,178 @@
+extern crate alloc;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde_json::{Map, Value, json};
+
+use crate::{Attachment, IDiagnosis, IReportHandler, Report, Severity};
+
+/// Report handler writing diagnoses as JSON, see [`to_json`].
+///
+/// # Example
+///
+/// ```
+/// use mischief::render::JsonHandler;
+/// mischief::set_hook(JsonHandler { pretty: true });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct JsonHandler {
+    /// Whether to indent the JSON over several lines.
+    pub pretty: bool,
+}
+
+impl JsonHandler {
+    /// Writes `value` into `f`, followed by a line break.
+    fn write(&self, value: &Value, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pretty {
+            writeln!(f, "{value:#}")
+        } else {
+            writeln!(f, "{value}")
+        }
+    }
+}
+
+impl IReportHandler for JsonHandler {
+    fn render(&self, diagnosis: &dyn IDiagnosis, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(&to_json(diagnosis), f)
+    }
+
+    fn render_report(&self, report: &Report, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(&report.to_json(), f)
+    }
+}
+
+/// Serializes a diagnosis and its chain to JSON.
+///
+/// The object holds the `description`, `code`, `severity`, `help` and
+/// `url` of the diagnosis, its `file` and `labels`, its `attachments`
+/// and its `related` diagnoses. The rest of the chain is listed under `causes`,
+/// from the outermost one, with the same fields. Missing values are `null`.
+///
+/// Each label holds its byte `start` and `end`, its `message`, whether it
+/// is `primary`, and its 1-based `line` and `column` when the diagnosis
+/// has source code. Each attachment holds its `kind`, one of `field`,
+/// `note` or `section`, with its `key` and `value`, its `text`, or its
+/// `title` and `body`.
+///
+/// # Example
+///
+/// ```
+/// use mischief::mischief;
+/// use mischief::render::to_json;
+/// let report = mischief!("port is out of range", code = "shook::port");
+/// let json = to_json(report.error());
+/// assert_eq!(json["code"], "shook::port");
+/// assert_eq!(json["causes"].as_array().unwrap().len(), 0);
+/// ```
+pub fn to_json(diagnosis: &dyn IDiagnosis) -> Value {
+    let mut object = entry(diagnosis);
+    let causes: Vec<Value> = core::iter::successors(diagnosis.source(), |d| (*d).source())
+        .map(|cause| Value::Object(entry(cause)))
+        .collect();
+    object.insert("causes".to_string(), Value::Array(causes));
+    Value::Object(object)
+}
+
+/// Serializes one diagnosis of a chain, without its causes.
+fn entry(diagnosis: &dyn IDiagnosis) -> Map<String, Value> {
+    let source_code = diagnosis.source_code();
+    let labels: Vec<Value> = diagnosis
+        .labels()
+        .iter()
+        .map(|label| {
+            let mut object = json!({
+                "start": label.span().start,
+                "end": label.span().end,
+                "message": label.message(),
+                "primary": label.is_primary(),
+            });
+            if let Some(source_code) = source_code {
+                let (line, column) = source_code.location(label.span().start);
+                object["line"] = json!(line);
+                object["column"] = json!(column);
+            }
+            object
+        })
+        .collect();
+    let attachments: Vec<Value> = diagnosis
+        .attachments()
+        .iter()
+        .map(|attachment| match attachment {
+            Attachment::Field { key, value } => json!({
+                "kind": "field",
+                "key": key,
+                "value": value,
+            }),
+            Attachment::Note(text) => json!({ "kind": "note", "text": text }),
+            Attachment::Section { title, body } => json!({
+                "kind": "section",
+                "title": title,
+                "body": body,
+            }),
+        })
+        .collect();
+    let related: Vec<Value> = diagnosis.related().map(to_json).collect();
+    let mut object = Map::new();
+    object.insert("description".to_string(), json!(diagnosis.description()));
+    object.insert("code".to_string(), json!(diagnosis.code()));
+    object.insert(
+        "severity".to_string(),
+        json!(diagnosis.severity().map(severity_name)),
+    );
+    object.insert("help".to_string(), json!(diagnosis.help()));
+    object.insert("url".to_string(), json!(diagnosis.url()));
+    object.insert(
+        "file".to_string(),
+        json!(source_code.map(|source_code| source_code.name())),
+    );
+    object.insert("labels".to_string(), Value::Array(labels));
+    object.insert("attachments".to_string(), Value::Array(attachments));
+    object.insert("related".to_string(), Value::Array(related));
+    object
+}
+
+/// Returns the lowercase name of a severity.
+pub(crate) fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Advice => "advice",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+/// Serializes the spans of a span trace, from the innermost one.
+#[cfg(feature = "tracing")]
+pub(crate) fn span_trace_to_json(span_trace: &crate::SpanTrace) -> Value {
+    let mut spans = Vec::new();
+    span_trace.with_spans(|metadata, fields| {
+        spans.push(json!({
+            "target": metadata.target(),
+            "name": metadata.name(),
+            "fields": fields,
+            "file": metadata.file(),
+            "line": metadata.line(),
+        }));
+        true
+    });
+    Value::Array(spans)
+}
+
+/// Serializes the frames of a backtrace shown with the current
+/// [`crate::BacktraceStyle`], one object per symbol.
+#[cfg(feature = "backtrace")]
+pub(crate) fn backtrace_to_json(backtrace: &backtrace::Backtrace) -> Value {
+    let (frames, _) = crate::trace::frames(backtrace, crate::backtrace_style());
+    let frames: Vec<Value> = frames
+        .into_iter()
+        .map(|frame| {
+            json!({
+                "index": frame.index,
+                "name": frame.name,
+                "file": frame.file.map(|file| file.display().to_string()),
+                "line": frame.line,
+            })
+        })
+        .collect();
+    Value::Array(frames)
+}
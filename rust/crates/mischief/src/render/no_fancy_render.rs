@@ -1,5 +1,23 @@
 extern crate alloc;
-use crate::IDiagnosis;
+use alloc::string::String;
+
+use super::snippet::render_snippet;
+use crate::{Attachment, IDiagnosis, IReportHandler};
+
+/// Default report handler, rendering diagnoses as plain text with
+/// [`render_diagnosis`].
+#[derive(Debug, Clone, Default)]
+pub struct MischiefHandler;
+
+impl IReportHandler for MischiefHandler {
+    fn render(
+        &self,
+        diagnosis: &dyn IDiagnosis,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
+        render_dyn(diagnosis, f)
+    }
+}
 
 /// Produces an iterator over the diagnosis chain.
 ///
@@ -11,8 +29,8 @@ use crate::IDiagnosis;
 /// The resulting iterator represents the full causal chain of the
 /// diagnosis, ordered from the outermost diagnosisto the deepest
 /// underlying source.
-fn chain(diagnosis: &impl crate::IDiagnosis) -> impl Iterator<Item = &dyn crate::IDiagnosis> {
-    core::iter::successors(Some(diagnosis as &dyn crate::IDiagnosis), |r| r.source())
+fn chain(diagnosis: &dyn crate::IDiagnosis) -> impl Iterator<Item = &dyn crate::IDiagnosis> {
+    core::iter::successors(Some(diagnosis), |r| (*r).source())
 }
 
 /// Renders a diagnosis chain in a simple textual format.
@@ -22,7 +40,12 @@ fn chain(diagnosis: &impl crate::IDiagnosis) -> impl Iterator<Item = &dyn crate:
 /// are treated as causes and displayed under a `"Caused by:"` section.
 ///
 /// Each diagnosis in the causal chain is displayed on its own line
-/// using the description returned by [`IDiagnosis::description`].
+/// using the description returned by [`IDiagnosis::description`],
+/// followed by its source snippet and attachments if it has any.
+///
+/// Related diagnoses are listed under a `"Related:"` section below the
+/// diagnosis they belong to, root or cause, each one rendered the same way
+/// and indented.
 ///
 /// This renderer provides a minimal, dependency-free textual
 /// representation of diagnosis chains and can serve as a fallback
@@ -31,11 +54,17 @@ pub fn render_diagnosis<D: IDiagnosis>(
     diagnosis: &D,
     f: &mut core::fmt::Formatter<'_>,
 ) -> core::fmt::Result {
+    render_dyn(diagnosis, f)
+}
+
+fn render_dyn(diagnosis: &dyn IDiagnosis, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
     let mut chain = chain(diagnosis);
 
     if let Some(first) = chain.next() {
         f.write_str(&alloc::format!("Error: {}", first.description()))?;
         writeln!(f)?;
+        write_snippet(first, "", f)?;
+        write_attachments(first, "", f)?;
     }
 
     let mut first = true;
@@ -49,14 +78,120 @@ pub fn render_diagnosis<D: IDiagnosis>(
 
         f.write_str(&alloc::format!("    {}", diagnosis.description()))?;
         writeln!(f)?;
+        write_snippet(diagnosis, "    ", f)?;
+        write_attachments(diagnosis, "    ", f)?;
+        write_related(diagnosis, "    ", f)?;
+    }
+
+    write_related(diagnosis, "", f)
+}
+
+/// Writes the related diagnoses of a diagnosis under a `"Related:"` section,
+/// each line prefixed by `indent` and indented one more level.
+fn write_related(
+    diagnosis: &dyn IDiagnosis,
+    indent: &str,
+    f: &mut core::fmt::Formatter<'_>,
+) -> core::fmt::Result {
+    let mut related = diagnosis.related().peekable();
+    if related.peek().is_some() {
+        writeln!(f, "\n{indent}Related:")?;
+    }
+    for diagnosis in related {
+        let rendered = alloc::format!("{}", Related(diagnosis));
+        for line in rendered.lines() {
+            if line.is_empty() {
+                writeln!(f)?;
+            } else {
+                writeln!(f, "{indent}    {line}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renders a related diagnosis with the same layout as the root one.
+struct Related<'a>(&'a dyn IDiagnosis);
+
+impl core::fmt::Display for Related<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { render_dyn(self.0, f) }
+}
+
+/// Writes the attachments of a diagnosis, each line prefixed by `indent`.
+fn write_attachments(
+    diagnosis: &dyn IDiagnosis,
+    indent: &str,
+    f: &mut core::fmt::Formatter<'_>,
+) -> core::fmt::Result {
+    for attachment in diagnosis.attachments() {
+        match attachment {
+            Attachment::Field { key, value } => writeln!(f, "{indent}{key}: {value}")?,
+            Attachment::Note(note) => writeln!(f, "{indent}note: {note}")?,
+            Attachment::Section { title, body } => {
+                writeln!(f, "{indent}{title}:")?;
+                for line in body.lines() {
+                    writeln!(f, "{indent}  {line}")?;
+                }
+            }
+        }
     }
+    Ok(())
+}
 
+/// Writes the unstyled source snippet of a diagnosis, each line prefixed
+/// by `indent`.
+fn write_snippet(
+    diagnosis: &dyn IDiagnosis,
+    indent: &str,
+    f: &mut core::fmt::Formatter<'_>,
+) -> core::fmt::Result {
+    let Some(source_code) = diagnosis.source_code() else {
+        return Ok(());
+    };
+    let snippet = render_snippet(source_code, diagnosis.labels(), |_, text| {
+        String::from(text)
+    });
+    for line in snippet.lines() {
+        writeln!(f, "{indent}{line}")?;
+    }
     Ok(())
 }
-#[cfg(all(feature = "backtrace", debug_assertions))]
+/// Renders the spans of a span trace.
+#[cfg(feature = "tracing")]
+pub fn render_span_trace(
+    span_trace: &crate::SpanTrace,
+    f: &mut core::fmt::Formatter<'_>,
+) -> core::fmt::Result {
+    write!(f, "Span trace:\n{span_trace}")
+}
+
+/// Renders the frames of a backtrace shown with the current
+/// [`crate::BacktraceStyle`].
+#[cfg(feature = "backtrace")]
 pub fn render_backtrace(
     backtrace: &backtrace::Backtrace,
     f: &mut core::fmt::Formatter<'_>,
 ) -> core::fmt::Result {
-    writeln!(f, "Backtrace:\n{:?}", backtrace)
+    let style = crate::backtrace_style();
+    if style == crate::BacktraceStyle::Off {
+        return Ok(());
+    }
+    let (frames, hidden) = crate::trace::frames(backtrace, style);
+    writeln!(f, "Backtrace:")?;
+    for frame in frames {
+        writeln!(f, "{:>4}: {}", frame.index, frame.name)?;
+        if let Some(file) = frame.file {
+            match frame.line {
+                Some(line) => writeln!(f, "             at {}:{line}", file.display())?,
+                None => writeln!(f, "             at {}", file.display())?,
+            }
+        }
+    }
+    if hidden > 0 {
+        writeln!(
+            f,
+            "{hidden} frames hidden, set RUST_BACKTRACE=full to show them"
+        )?;
+    }
+    Ok(())
 }
@@ -0,0 +1,155 @@
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde_json::{Value, json};
+
+use super::json::severity_name;
+use crate::{IDiagnosis, IReportHandler, Label, Severity, SourceCode};
+
+/// Report handler writing diagnoses as a SARIF 2.1.0 log, see
+/// [`to_sarif`].
+#[derive(Debug, Clone)]
+pub struct SarifHandler {
+    /// Name of the tool reported in the log, e.g. `shook`.
+    pub tool: String,
+}
+
+impl IReportHandler for SarifHandler {
+    fn render(&self, diagnosis: &dyn IDiagnosis, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:#}", to_sarif([diagnosis], &self.tool))
+    }
+}
+
+/// Serializes diagnoses to a SARIF 2.1.0 log with a single run of `tool`.
+///
+/// Every diagnosis of a chain, or among the related diagnoses, that has
+/// source code and labels becomes a result: its primary labels are the
+/// locations of the result and its secondary labels the related
+/// locations. A diagnosis without any such diagnosis in its tree, such as
+/// one of several aggregated errors, becomes a single result without
+/// location, so no error is lost.
+///
+/// Diagnosis codes are the rule ids of the results. Codes with a URL are
+/// listed as rules of the tool, with the URL as their help URI.
+///
+/// # Example
+///
+/// ```
+/// use mischief::render::to_sarif;
+/// use mischief::{IDiagnosis, Label, SourceCode, mischief};
+/// let report = mischief!("invalid timeout", code = "shook::timeout")
+///     .with_source_code(SourceCode::new("shook.toml", "timeout = \"ten\""))
+///     .with_label(Label::primary(10..15, Some("expected an integer")));
+/// let sarif = to_sarif([report.error() as &dyn IDiagnosis], "shook");
+/// let result = &sarif["runs"][0]["results"][0];
+/// assert_eq!(result["ruleId"], "shook::timeout");
+/// assert_eq!(
+///     result["locations"][0]["physicalLocation"]["region"]["startColumn"],
+///     11
+/// );
+/// ```
+pub fn to_sarif<'a, I>(diagnoses: I, tool: &str) -> Value
+where
+    I: IntoIterator<Item = &'a dyn IDiagnosis>,
+{
+    let mut results = Vec::new();
+    let mut rules = Vec::new();
+    for diagnosis in diagnoses {
+        collect(diagnosis, &mut results, &mut rules);
+    }
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": { "name": tool, "rules": rules } },
+            "results": results,
+        }],
+    })
+}
+
+/// Collects the results and rules of the located diagnoses in the tree
+/// of `diagnosis`, or of `diagnosis` itself if there is none.
+fn collect(diagnosis: &dyn IDiagnosis, results: &mut Vec<Value>, rules: &mut Vec<Value>) {
+    let count = results.len();
+    for level in core::iter::successors(Some(diagnosis), |d| (*d).source()) {
+        if level.source_code().is_some() && !level.labels().is_empty() {
+            results.push(result(level));
+            push_rule(level, rules);
+        }
+        for related in level.related() {
+            collect(related, results, rules);
+        }
+    }
+    if results.len() == count {
+        results.push(result(diagnosis));
+        push_rule(diagnosis, rules);
+    }
+}
+
+/// Adds the rule of a diagnosis with a code and a URL, once per code.
+fn push_rule(diagnosis: &dyn IDiagnosis, rules: &mut Vec<Value>) {
+    let (Some(code), Some(url)) = (diagnosis.code(), diagnosis.url()) else {
+        return;
+    };
+    if rules.iter().all(|rule| rule["id"] != code) {
+        rules.push(json!({ "id": code, "helpUri": url }));
+    }
+}
+
+/// Builds the SARIF result of a diagnosis.
+fn result(diagnosis: &dyn IDiagnosis) -> Value {
+    let mut text = diagnosis.description().into_owned();
+    if let Some(help) = diagnosis.help() {
+        text.push_str("\nhelp: ");
+        text.push_str(help);
+    }
+    let level = match diagnosis.severity().unwrap_or_default() {
+        Severity::Advice => "note",
+        severity => severity_name(severity),
+    };
+    let mut result = json!({
+        "level": level,
+        "message": { "text": text },
+    });
+    if let Some(code) = diagnosis.code() {
+        result["ruleId"] = json!(code);
+    }
+    if let Some(source_code) = diagnosis.source_code() {
+        let (primary, secondary): (Vec<&Label>, Vec<&Label>) = diagnosis
+            .labels()
+            .iter()
+            .partition(|label| label.is_primary());
+        result["locations"] = primary
+            .into_iter()
+            .map(|label| location(source_code, label))
+            .collect();
+        result["relatedLocations"] = secondary
+            .into_iter()
+            .map(|label| location(source_code, label))
+            .collect();
+    }
+    result
+}
+
+/// Builds the SARIF location of a label.
+fn location(source_code: &SourceCode, label: &Label) -> Value {
+    let (start_line, start_column) = source_code.location(label.span().start);
+    let (end_line, end_column) = source_code.location(label.span().end);
+    let mut location = json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": source_code.name() },
+            "region": {
+                "startLine": start_line,
+                "startColumn": start_column,
+                "endLine": end_line,
+                "endColumn": end_column,
+            },
+        },
+    });
+    if let Some(message) = label.message() {
+        location["message"] = json!({ "text": message });
+    }
+    location
+}
@@ -0,0 +1,144 @@
+extern crate alloc;
+use alloc::collections::btree_map::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::label::floor_char_boundary;
+use crate::{Label, SourceCode};
+
+/// Part of a source snippet a renderer may style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Paint {
+    /// The location arrow, the separators and the line numbers.
+    Gutter,
+
+    /// Underline and message of a primary label.
+    Primary,
+
+    /// Underline and message of a secondary label.
+    Secondary,
+}
+
+/// Underline of a label on one source line.
+struct Underline<'a> {
+    column: usize,
+    len: usize,
+    primary: bool,
+    message: Option<&'a str>,
+}
+
+/// Renders the lines of `source` covered by `labels`, rustc style.
+///
+/// The snippet starts with the location of the first primary label,
+/// followed by each labeled line and one underline row per label on it.
+/// A label spanning several lines is underlined on each of them and its
+/// message is shown on the last one. Skipped lines are shown as `...`.
+///
+/// `paint` styles each part of the snippet.
+pub(crate) fn render_snippet(
+    source: &SourceCode,
+    labels: &[Label],
+    paint: impl Fn(Paint, &str) -> String,
+) -> String {
+    let text = source.text();
+    let lines: Vec<(usize, &str)> = text
+        .split('\n')
+        .scan(0, |start, line| {
+            let current = *start;
+            *start += line.len() + 1;
+            Some((current, line.strip_suffix('\r').unwrap_or(line)))
+        })
+        .collect();
+    let line_of = |offset: usize| {
+        lines
+            .partition_point(|(start, _)| *start <= offset)
+            .saturating_sub(1)
+    };
+
+    let mut underlines: BTreeMap<usize, Vec<Underline<'_>>> = BTreeMap::new();
+    for label in labels {
+        let span = label.span();
+        let start = floor_char_boundary(text, span.start);
+        let end = floor_char_boundary(text, span.end.max(start));
+        let (first, last) = (line_of(start), line_of(end.saturating_sub(1).max(start)));
+        for (index, &(line_start, line)) in lines.iter().enumerate().take(last + 1).skip(first) {
+            let from = start.saturating_sub(line_start).min(line.len());
+            let to = if index == last {
+                end.saturating_sub(line_start).min(line.len())
+            } else {
+                line.len()
+            };
+            underlines.entry(index).or_default().push(Underline {
+                column: line[..from].chars().count(),
+                len: line[from..to.max(from)].chars().count().max(1),
+                primary: label.is_primary(),
+                message: if index == last { label.message() } else { None },
+            });
+        }
+    }
+
+    let gutter_width = underlines
+        .keys()
+        .next_back()
+        .map_or(1, |index| format!("{}", index + 1).len());
+    let pad = " ".repeat(gutter_width);
+    let mut buffer = String::new();
+
+    let anchor = labels
+        .iter()
+        .find(|label| label.is_primary())
+        .or(labels.first());
+    let location = match anchor {
+        Some(label) => {
+            let (line, column) = source.location(label.span().start);
+            format!("{}:{line}:{column}", source.name())
+        }
+        None => String::from(source.name()),
+    };
+    write!(buffer, "{pad}{} {location}", paint(Paint::Gutter, "-->")).unwrap();
+    if underlines.is_empty() {
+        return buffer;
+    }
+    write!(buffer, "\n{pad} {}", paint(Paint::Gutter, "|")).unwrap();
+
+    let mut previous = None;
+    for (index, mut rows) in underlines {
+        if previous.is_some_and(|previous| index > previous + 1) {
+            write!(buffer, "\n{}", paint(Paint::Gutter, "...")).unwrap();
+        }
+        previous = Some(index);
+        let number = format!("{:>gutter_width$} |", index + 1);
+        write!(
+            buffer,
+            "\n{} {}",
+            paint(Paint::Gutter, &number),
+            lines[index].1
+        )
+        .unwrap();
+
+        rows.sort_by_key(|row| row.column);
+        for row in rows {
+            let (kind, marker) = if row.primary {
+                (Paint::Primary, "^")
+            } else {
+                (Paint::Secondary, "-")
+            };
+            let mut underline = marker.repeat(row.len);
+            if let Some(message) = row.message {
+                underline.push(' ');
+                underline.push_str(message);
+            }
+            write!(
+                buffer,
+                "\n{pad} {} {}{}",
+                paint(Paint::Gutter, "|"),
+                " ".repeat(row.column),
+                paint(kind, &underline)
+            )
+            .unwrap();
+        }
+    }
+    buffer
+}
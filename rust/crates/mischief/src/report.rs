@@ -2,16 +2,22 @@ use core::error::Error;
 use core::fmt::{Debug, Display};
 extern crate alloc;
 use alloc::boxed::Box;
+use alloc::format;
 use alloc::string::ToString;
+use alloc::vec::Vec;
 
 use crate::error::MischiefError;
-use crate::render::render_diagnosis;
+use crate::handler::{IReportHandler, render_hooked};
+use crate::{Attachment, IDiagnosis, Label, SourceCode};
 
 pub struct ReportInner {
     error: MischiefError,
 
-    #[cfg(all(feature = "backtrace", debug_assertions))]
-    backtrace: backtrace::Backtrace,
+    #[cfg(feature = "backtrace")]
+    backtrace: Option<crate::trace::LazyBacktrace>,
+
+    #[cfg(feature = "tracing")]
+    span_trace: Option<crate::SpanTrace>,
 }
 /// High-level wrapper around [`MischiefError`] used for ergonomic error
 /// handling.
@@ -27,41 +33,389 @@ pub struct ReportInner {
 /// Formatting a `Report` will render the full diagnosis chain. If the
 /// `fancy` feature is enabled, a structured tree-based renderer is used.
 /// Otherwise a minimal textual fallback renderer is used.
+///
+/// A `Report` is `Send + Sync + 'static`, so it can cross threads and be
+/// held across `.await` points.
 pub struct Report(pub Box<ReportInner>);
 
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync + 'static>() {}
+    assert_send_sync::<Report>();
+};
+
 impl Report {
     /// Creates a new `Report` from a [`MischiefError`].
     ///
     /// This function wraps the provided diagnosis as the root error
     /// contained by the report.
     pub fn new(error: MischiefError) -> Self {
-        #[cfg(all(feature = "backtrace", debug_assertions))]
-        let backtrace = backtrace::Backtrace::new();
+        #[cfg(feature = "backtrace")]
+        let backtrace = crate::trace::LazyBacktrace::capture();
+
+        #[cfg(feature = "tracing")]
+        let span_trace = Some(crate::SpanTrace::capture()).filter(|trace| !trace.is_empty());
 
         Self(Box::new(ReportInner {
             error,
 
-            #[cfg(all(feature = "backtrace", debug_assertions))]
+            #[cfg(feature = "backtrace")]
             backtrace,
+
+            #[cfg(feature = "tracing")]
+            span_trace,
         }))
     }
 
+    /// Creates a `Report` from an error, keeping the original value.
+    ///
+    /// The source chain is converted like the blanket [`From`] conversion
+    /// used by `?`, and the error can in addition be retrieved with
+    /// [`Report::downcast_ref`] and [`Report::downcast`]. A
+    /// [`MischiefError`] becomes the root diagnosis as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mischief::Report;
+    /// let report = std::fs::File::open("missing")
+    ///     .map_err(Report::from_error)
+    ///     .unwrap_err();
+    /// assert!(report.is::<std::io::Error>());
+    /// ```
+    pub fn from_error<E>(error: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        let error: Box<dyn core::any::Any> = Box::new(error);
+        match error.downcast::<MischiefError>() {
+            Ok(error) => Self::new(*error),
+            Err(error) => Self::new(MischiefError::from_error(*error.downcast::<E>().unwrap())),
+        }
+    }
+
+    /// Creates a `Report` from an error implementing [`IDiagnosis`], such
+    /// as one deriving `Diagnosis`.
+    ///
+    /// Unlike the blanket [`From`] conversion used by `?`, which only sees
+    /// the [`Error`] implementation, the code, severity, help, URL and
+    /// labels of every diagnosis in the chain are kept. The original error
+    /// is kept as well, as by [`Report::from_error`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mischief::{Diagnosis, IDiagnosis, Report};
+    ///
+    /// #[derive(Debug, Diagnosis)]
+    /// #[diagnosis(code = "rax::checksum", help = "Check the serial settings.")]
+    /// struct ChecksumError;
+    /// impl std::fmt::Display for ChecksumError {
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///         write!(f, "checksum mismatch")
+    ///     }
+    /// }
+    /// impl std::error::Error for ChecksumError {}
+    ///
+    /// let report = Report::from_diagnosis(ChecksumError);
+    /// assert_eq!(report.error().code(), Some("rax::checksum"));
+    /// assert!(report.is::<ChecksumError>());
+    /// ```
+    pub fn from_diagnosis<E>(error: E) -> Self
+    where
+        E: IDiagnosis + Error + Send + Sync + 'static,
+    {
+        Self::new(MischiefError::from_diagnosis(error))
+    }
+
+    /// Creates a `Report` collecting several independent errors.
+    ///
+    /// The root diagnosis counts the errors, which are kept as its
+    /// [related](crate::IDiagnosis::related) diagnoses. Backtraces of the
+    /// collected reports are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mischief::{IDiagnosis, Report, mischief};
+    /// let report = Report::aggregate([
+    ///     mischief!("port is out of range"),
+    ///     mischief!("host is empty"),
+    /// ]);
+    /// assert_eq!(report.error().description(), "2 errors occurred");
+    /// assert_eq!(report.error().related().count(), 2);
+    /// ```
+    pub fn aggregate<I, R>(errors: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<Self>,
+    {
+        let related: Vec<MischiefError> = errors
+            .into_iter()
+            .map(|error| error.into().0.error)
+            .collect();
+        let description = match related.len() {
+            1 => "1 error occurred".to_string(),
+            n => format!("{n} errors occurred"),
+        };
+        Self::new(
+            MischiefError::new(description, None, None, None, None, None).with_related(related),
+        )
+    }
+
     /// Returns a reference to the underlying diagnosis.
     ///
     /// This allows callers to inspect structured metadata such as
     /// error codes, severity levels, and help messages.
     pub fn error(&self) -> &MischiefError { &self.0.error }
 
-    /// Renders the report using the configured rendering backend.
+    /// Returns the diagnosis chain as a standard error, whose sources are
+    /// the causes of the report.
     ///
-    /// If the `fancy` feature is enabled, a themed tree renderer based
-    /// on `arbor` is used. Otherwise a minimal textual renderer is used.
-    fn render_report(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        render_diagnosis(&self.0.error, f)?;
+    /// Recording it as a `tracing` field writes the chain as plain text,
+    /// e.g. `error=Job failed error.sources=[port closed]` with
+    /// `tracing_subscriber::fmt`, instead of the rendered report.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mischief::{IntoMischief, WrapErr};
+    /// let report = Err::<(), _>(std::io::Error::other("port closed"))
+    ///     .into_mischief()
+    ///     .wrap_err("Job failed")
+    ///     .unwrap_err();
+    /// let error = report.as_error();
+    /// assert_eq!(error.to_string(), "Job failed");
+    /// assert_eq!(error.source().unwrap().to_string(), "port closed");
+    /// ```
+    pub fn as_error(&self) -> &(dyn Error + Send + Sync + 'static) { &self.0.error }
+
+    /// Returns the spans entered when the report was created, if any.
+    #[cfg(feature = "tracing")]
+    pub fn span_trace(&self) -> Option<&crate::SpanTrace> { self.0.span_trace.as_ref() }
+
+    /// Attaches the source text the labels of the root diagnosis point
+    /// into.
+    pub fn with_source_code(mut self, source_code: SourceCode) -> Self {
+        self.0.error = self.0.error.with_source_code(source_code);
+        self
+    }
+
+    /// Adds a labeled span of the source text to the root diagnosis.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mischief::{IDiagnosis, Label, SourceCode, mischief};
+    /// let report = mischief!("invalid timeout")
+    ///     .with_source_code(SourceCode::new("shook.toml", "timeout = \"ten\""))
+    ///     .with_label(Label::primary(10..15, Some("expected an integer")));
+    /// assert_eq!(report.error().labels().len(), 1);
+    /// ```
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.0.error = self.0.error.with_label(label);
+        self
+    }
+
+    /// Attaches a key-value fact to the root diagnosis, without adding a
+    /// causal level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mischief::{IDiagnosis, mischief};
+    /// let report = mischief!("sandbox failed to start")
+    ///     .attach("job", 42)
+    ///     .attach("sandbox", "ci-linux");
+    /// assert_eq!(report.attachment("job"), Some("42"));
+    /// assert_eq!(report.error().attachments().len(), 2);
+    /// ```
+    pub fn attach<K: Display, V: Display>(mut self, key: K, value: V) -> Self {
+        self.0.error = self.0.error.attach(key, value);
+        self
+    }
+
+    /// Attaches a free-form note to the root diagnosis.
+    pub fn note<N: Display>(mut self, note: N) -> Self {
+        self.0.error = self.0.error.note(note);
+        self
+    }
+
+    /// Attaches a titled block of text to the root diagnosis, such as the
+    /// output of a failed command.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mischief::mischief;
+    /// let report = mischief!("hook `fmt` failed")
+    ///     .with_section("stdout", "checking 3 files\n1 file would be reformatted");
+    /// println!("{report}");
+    /// ```
+    pub fn with_section<T: Display, B: Display>(mut self, title: T, body: B) -> Self {
+        self.0.error = self.0.error.with_section(title, body);
+        self
+    }
+
+    /// Returns the value of the first field attached under `key`, from the
+    /// outermost diagnosis of the chain.
+    pub fn attachment(&self, key: &str) -> Option<&str> {
+        self.levels()
+            .flat_map(|level| level.attachments())
+            .find_map(|attachment| match attachment {
+                Attachment::Field { key: k, value } if k == key => Some(value.as_str()),
+                _ => None,
+            })
+    }
+
+    /// Returns the diagnoses of the chain, from the outermost one.
+    fn levels(&self) -> impl Iterator<Item = &MischiefError> {
+        core::iter::successors(Some(&self.0.error), |e| e.source.as_deref())
+    }
+
+    /// Iterates over the original errors kept in the chain, from the
+    /// outermost one.
+    ///
+    /// Errors converted into the report are yielded with their sources.
+    /// Context added with [`WrapErr`] or the `mischief!` macro has no
+    /// original error and is skipped.
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn Error + 'static)> {
+        self.levels()
+            .scan(
+                None,
+                |cursor: &mut Option<&(dyn Error + 'static)>, level| {
+                    *cursor = match &level.typed {
+                        Some(typed) => Some(typed.as_ref() as &(dyn Error + 'static)),
+                        None if level.derived => cursor.and_then(|e| e.source()),
+                        None => None,
+                    };
+                    Some(*cursor)
+                },
+            )
+            .flatten()
+    }
+
+    /// Returns `true` if an error of type `T` is in the chain.
+    pub fn is<T: Error + 'static>(&self) -> bool { self.downcast_ref::<T>().is_some() }
+
+    /// Returns the first error of type `T` in the chain, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mischief::{Report, WrapErr};
+    /// let report = std::fs::File::open("missing")
+    ///     .map_err(Report::from_error)
+    ///     .wrap_err("Failed to read the configuration")
+    ///     .unwrap_err();
+    /// let error = report.downcast_ref::<std::io::Error>().unwrap();
+    /// assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    /// ```
+    pub fn downcast_ref<T: Error + 'static>(&self) -> Option<&T> {
+        self.chain().find_map(|e| e.downcast_ref::<T>())
+    }
+
+    /// Takes the first error of type `T` kept in the chain out of the
+    /// report, or gives the report back if there is none.
+    ///
+    /// Only errors converted into the report are owned by it; their
+    /// sources can be borrowed with [`Report::downcast_ref`] only. An error
+    /// still shared with a clone of the diagnosis is not taken either.
+    pub fn downcast<T: Error + 'static>(mut self) -> core::result::Result<T, Self> {
+        let Some(depth) = self.levels().position(|level| {
+            level
+                .typed
+                .as_deref()
+                .is_some_and(|e| (e as &(dyn Error + 'static)).is::<T>())
+        }) else {
+            return Err(self);
+        };
+        let mut level = &mut self.0.error;
+        for _ in 0..depth {
+            level = level.source.as_deref_mut().unwrap();
+        }
+        match level.typed.take().unwrap().into_any() {
+            Ok(typed) => Ok(*typed.downcast::<T>().unwrap()),
+            Err(shared) => {
+                level.typed = Some(shared);
+                Err(self)
+            }
+        }
+    }
+
+    /// Returns a value formatting the report with `handler`, instead of the
+    /// handler installed by [`crate::set_hook`].
+    ///
+    /// The report is rendered by [`IReportHandler::render_report`], so the
+    /// span trace and backtrace are included like when the report is
+    /// displayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mischief::mischief;
+    /// use mischief::render::MischiefHandler;
+    /// let report = mischief!("port is out of range");
+    /// println!("{}", report.display_with(&MischiefHandler::default()));
+    /// ```
+    pub fn display_with<'a>(&'a self, handler: &'a dyn IReportHandler) -> impl Display + 'a {
+        struct DisplayWith<'a>(&'a Report, &'a dyn IReportHandler);
+
+        impl Display for DisplayWith<'_> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                self.1.render_report(self.0, f)
+            }
+        }
 
-        #[cfg(all(feature = "backtrace", debug_assertions))]
-        crate::render_backtrace(&self.0.backtrace, f)?;
-        Ok(())
+        DisplayWith(self, handler)
+    }
+
+    /// Serializes the report to JSON.
+    ///
+    /// The object is the one built by [`crate::render::to_json`], with the
+    /// frames of the backtrace under `backtrace` and the spans of the span
+    /// trace under `span_trace` when they were captured.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Value {
+        #[allow(unused_mut)]
+        let mut json = crate::render::to_json(&self.0.error);
+        #[cfg(feature = "backtrace")]
+        if let Some(backtrace) = self.backtrace() {
+            json["backtrace"] = crate::render::backtrace_to_json(&backtrace);
+        }
+        #[cfg(feature = "tracing")]
+        if let Some(span_trace) = self.span_trace() {
+            json["span_trace"] = crate::render::span_trace_to_json(span_trace);
+        }
+        json
+    }
+
+    /// Serializes the report to a SARIF 2.1.0 log of `tool`, see
+    /// [`crate::render::to_sarif`].
+    #[cfg(feature = "json")]
+    pub fn to_sarif(&self, tool: &str) -> serde_json::Value {
+        crate::render::to_sarif([&self.0.error as &dyn IDiagnosis], tool)
+    }
+
+    /// Returns the backtrace captured when the report was created, if
+    /// backtraces were enabled, see [`crate::BacktraceStyle`].
+    ///
+    /// Symbols are resolved on the first call, so reports that are handled
+    /// without being rendered stay cheap.
+    #[cfg(feature = "backtrace")]
+    pub fn backtrace(&self) -> Option<impl core::ops::Deref<Target = backtrace::Backtrace> + '_> {
+        self.0
+            .backtrace
+            .as_ref()
+            .map(crate::trace::LazyBacktrace::resolve)
+    }
+
+    /// Renders the report using the installed report handler.
+    ///
+    /// Without a handler installed by [`crate::set_hook`], the default
+    /// `MischiefHandler` is used: a themed tree renderer based on `arbor`
+    /// if the `fancy` feature is enabled, or a minimal textual renderer.
+    fn render_report(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        render_hooked(self, f)
     }
 }
 
@@ -83,29 +437,18 @@ impl Display for Report {
 /// During conversion the full error source chain is recursively
 /// transformed into a hierarchy of [`MischiefError`] values,
 /// preserving the causal structure of the original error.
+///
+/// Only the messages of the chain are kept: the original error is not
+/// available to [`Report::downcast_ref`] and related methods, and the
+/// metadata of a [`MischiefError`] or of an error implementing
+/// [`IDiagnosis`] is not seen. Use [`Report::from_error`] to keep the
+/// original error, and [`IntoDiagnosis`] or [`WrapErr`] to keep the metadata
+/// of a diagnosis, for example with `.into_diagnosis()?`.
 impl<E> From<E> for Report
 where
     E: Error,
 {
-    fn from(value: E) -> Self {
-        fn convert(err: &dyn Error) -> MischiefError {
-            MischiefError::new(
-                &err.to_string(),
-                err.source().map(|src| Box::new(convert(src))),
-                None,
-                None,
-                None,
-                None,
-            )
-        }
-
-        Self(Box::new(ReportInner {
-            error: convert(&value),
-
-            #[cfg(all(feature = "backtrace", debug_assertions))]
-            backtrace: backtrace::Backtrace::new(),
-        }))
-    }
+    fn from(value: E) -> Self { Self::new(MischiefError::from_plain_error(&value)) }
 }
 
 /// Convenient `Result` alias using [`Report`] as the default error type.
@@ -132,10 +475,53 @@ impl<T, E: Error> IntoMischief<T> for Result<T, E> {
     }
 }
 
+/// Trait providing conversion of diagnoses into [`Report`].
+///
+/// The `?` operator converts through the blanket [`From`] implementation,
+/// which only sees the [`Error`] implementation. Calling
+/// [`IntoDiagnosis::into_diagnosis`] before `?` converts the error with
+/// [`Report::from_diagnosis`] instead, keeping the code, severity, help, URL
+/// and labels of every diagnosis in the chain.
+///
+/// # Example
+///
+/// ```
+/// use mischief::{Diagnosis, IDiagnosis, IntoDiagnosis};
+///
+/// #[derive(Debug, Diagnosis)]
+/// #[diagnosis(code = "rax::checksum")]
+/// struct ChecksumError;
+/// impl std::fmt::Display for ChecksumError {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "checksum mismatch")
+///     }
+/// }
+/// impl std::error::Error for ChecksumError {}
+///
+/// fn check() -> mischief::Result<()> {
+///     Err(ChecksumError).into_diagnosis()?;
+///     Ok(())
+/// }
+/// assert_eq!(check().unwrap_err().error().code(), Some("rax::checksum"));
+/// ```
+pub trait IntoDiagnosis<T> {
+    /// Converts the diagnosis into a [`Report`], keeping its metadata.
+    fn into_diagnosis(self) -> Result<T, Report>;
+}
+
+impl<T, E> IntoDiagnosis<T> for Result<T, E>
+where
+    E: IDiagnosis + Error + Send + Sync + 'static,
+{
+    fn into_diagnosis(self) -> Result<T, Report> { self.map_err(Report::from_diagnosis) }
+}
+
 /// Trait for attaching additional diagnosis context to existing errors.
 ///
 /// These methods allow callers to extend an error chain with
-/// higher-level context while preserving the original cause.
+/// higher-level context while preserving the original cause. An error
+/// implementing [`IDiagnosis`] is converted with [`Report::from_diagnosis`]
+/// first.
 pub trait WrapErr<D, T> {
     /// Attaches a context message to the error if the result is `Err`.
     ///
@@ -155,12 +541,17 @@ fn wrap_inner<D>(e: Report, msg: D) -> Report
 where
     D: Display + 'static,
 {
-    let new_inner = if let Some(r) = (&msg as &dyn core::any::Any).downcast_ref::<Report>() {
-        let mut inner = r.0.error.clone();
-        inner.source = Some(Box::new(e.0.error));
-        inner
-    } else {
-        MischiefError::new(&msg, Some(Box::new(e.0.error)), None, None, None, None)
+    let msg: Box<dyn core::any::Any> = Box::new(msg);
+    let new_inner = match msg.downcast::<Report>() {
+        Ok(r) => {
+            let mut inner = r.0.error;
+            inner.source = Some(Box::new(e.0.error));
+            inner
+        }
+        Err(msg) => {
+            let msg = msg.downcast::<D>().unwrap();
+            MischiefError::new(&*msg, Some(Box::new(e.0.error)), None, None, None, None)
+        }
     };
 
     Report::new(new_inner)
@@ -182,3 +573,18 @@ where
         }
     }
 }
+
+impl<D, T, E> WrapErr<D, T> for Result<T, E>
+where
+    D: Display + 'static,
+    E: IDiagnosis + Error + Send + Sync + 'static,
+{
+    fn wrap_err(self, msg: D) -> Result<T, Report> { self.into_diagnosis().wrap_err(msg) }
+
+    fn wrap_err_with<F>(self, msg: F) -> Result<T, Report>
+    where
+        F: FnOnce() -> D,
+    {
+        self.into_diagnosis().wrap_err_with(msg)
+    }
+}
@@ -0,0 +1,144 @@
+use core::any::TypeId;
+use core::fmt;
+use std::sync::{PoisonError, RwLock};
+
+use tracing::{Metadata, Span};
+use tracing_subscriber::Registry;
+use tracing_subscriber::fmt::FormattedFields;
+use tracing_subscriber::fmt::format::DefaultFields;
+use tracing_subscriber::registry::{Extensions, LookupSpan};
+
+/// Reads the fields of a span formatted by a field formatter.
+type FieldsReader = for<'a> fn(&'a Extensions<'_>) -> Option<&'a str>;
+
+/// Field formatters added with [`SpanTrace::add_field_format`].
+static FIELD_FORMATS: RwLock<Vec<(TypeId, FieldsReader)>> = RwLock::new(Vec::new());
+
+/// The `tracing` spans entered when a report was created.
+///
+/// A span trace tells which job, request or file was being processed, as
+/// a backtrace tells which functions were running. It is read from the
+/// [`Registry`] of the current subscriber; span fields are available when
+/// the subscriber has a `tracing_subscriber::fmt` layer with the default
+/// field formatter, such as the text layers of `clerk`, or with a field
+/// formatter added with [`SpanTrace::add_field_format`].
+#[derive(Debug, Clone)]
+pub struct SpanTrace {
+    /// Innermost span entered at capture.
+    span: Span,
+}
+
+impl SpanTrace {
+    /// Captures the spans entered on the current thread.
+    pub fn capture() -> Self {
+        Self {
+            span: Span::current(),
+        }
+    }
+
+    /// Also reads the span fields formatted by the field formatter `N`, for
+    /// subscribers whose layers do not use the default one.
+    ///
+    /// The `clerk` layers writing JSON add their field formatter when its
+    /// `mischief` feature is enabled.
+    ///
+    /// ```
+    /// use tracing_subscriber::fmt::format::Pretty;
+    ///
+    /// // the field formatter of `tracing_subscriber::fmt::layer().pretty()`
+    /// mischief::SpanTrace::add_field_format::<Pretty>();
+    /// ```
+    pub fn add_field_format<N: 'static>() {
+        let mut formats = FIELD_FORMATS
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if !formats.iter().any(|(id, _)| *id == TypeId::of::<N>()) {
+            formats.push((TypeId::of::<N>(), read_fields::<N>));
+        }
+    }
+
+    /// Calls `f` with the metadata and formatted fields of each span, from
+    /// the innermost one, until it returns `false`.
+    pub fn with_spans<F>(&self, mut f: F)
+    where
+        F: FnMut(&'static Metadata<'static>, &str) -> bool,
+    {
+        self.span.with_subscriber(|(id, dispatch)| {
+            let Some(registry) = dispatch.downcast_ref::<Registry>() else {
+                return;
+            };
+            let Some(span) = registry.span(id) else {
+                return;
+            };
+            for span in span.scope() {
+                let extensions = span.extensions();
+                let fields = read_fields::<DefaultFields>(&extensions)
+                    .or_else(|| {
+                        FIELD_FORMATS
+                            .read()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .iter()
+                            .find_map(|(_, read)| read(&extensions))
+                    })
+                    .unwrap_or("");
+                if !f(span.metadata(), fields) {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Returns `true` if no span was entered at capture, or if the spans
+    /// cannot be read from the subscriber.
+    pub fn is_empty(&self) -> bool {
+        let mut empty = true;
+        self.with_spans(|_, _| {
+            empty = false;
+            false
+        });
+        empty
+    }
+}
+
+/// Returns the fields of a span formatted by the field formatter `N`.
+fn read_fields<'a, N: 'static>(extensions: &'a Extensions<'_>) -> Option<&'a str> {
+    extensions
+        .get::<FormattedFields<N>>()
+        .map(|fields| fields.fields.as_str())
+}
+
+/// Formats the spans from the innermost one, with their fields and
+/// location.
+impl fmt::Display for SpanTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut result = Ok(());
+        let mut index = 0;
+        self.with_spans(|metadata, fields| {
+            result = write_span(f, index, metadata, fields);
+            index += 1;
+            result.is_ok()
+        });
+        result
+    }
+}
+
+/// Writes one span of a span trace.
+fn write_span(
+    f: &mut fmt::Formatter<'_>,
+    index: usize,
+    metadata: &Metadata<'_>,
+    fields: &str,
+) -> fmt::Result {
+    write!(f, "{index:>4}: {}::{}", metadata.target(), metadata.name())?;
+    if !fields.is_empty() {
+        write!(f, " with {fields}")?;
+    }
+    writeln!(f)?;
+    if let Some(file) = metadata.file() {
+        match metadata.line() {
+            Some(line) => writeln!(f, "             at {file}:{line}")?,
+            None => writeln!(f, "             at {file}")?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use std::format;
+use std::path::Path;
+use std::string::{String, ToString};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::vec::Vec;
+
+use backtrace::Backtrace;
+
+/// How backtraces of reports are captured and rendered.
+///
+/// The style is read once from the `RUST_LIB_BACKTRACE` environment
+/// variable, or `RUST_BACKTRACE` if it is unset, following the standard
+/// library: `0` disables backtraces, `full` shows every frame and any
+/// other value shows the frames of the application only. Without either
+/// variable, backtraces are captured in debug builds only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BacktraceStyle {
+    /// No backtrace is captured.
+    Off,
+
+    /// Frames of the standard library, `tokio`, `backtrace` and
+    /// `mischief` are hidden.
+    Short,
+
+    /// Every frame is shown.
+    Full,
+}
+
+/// Style set by [`set_backtrace_style`] or read from the environment,
+/// encoded as `1 + style`. Zero means not read yet.
+static STYLE: AtomicU8 = AtomicU8::new(0);
+
+/// Returns the current backtrace style.
+pub fn backtrace_style() -> BacktraceStyle {
+    match STYLE.load(Ordering::Relaxed) {
+        1 => BacktraceStyle::Off,
+        2 => BacktraceStyle::Short,
+        3 => BacktraceStyle::Full,
+        _ => {
+            let style = style_from_env();
+            set_backtrace_style(style);
+            style
+        }
+    }
+}
+
+/// Overrides the backtrace style read from the environment, e.g. from a
+/// `--backtrace` flag.
+///
+/// # Example
+///
+/// ```
+/// use mischief::{BacktraceStyle, backtrace_style, set_backtrace_style};
+/// set_backtrace_style(BacktraceStyle::Full);
+/// assert_eq!(backtrace_style(), BacktraceStyle::Full);
+/// ```
+pub fn set_backtrace_style(style: BacktraceStyle) {
+    let value = match style {
+        BacktraceStyle::Off => 1,
+        BacktraceStyle::Short => 2,
+        BacktraceStyle::Full => 3,
+    };
+    STYLE.store(value, Ordering::Relaxed);
+}
+
+/// Reads the backtrace style from the environment.
+fn style_from_env() -> BacktraceStyle {
+    let var = std::env::var("RUST_LIB_BACKTRACE").or_else(|_| std::env::var("RUST_BACKTRACE"));
+    match var.as_deref() {
+        Ok("0") => BacktraceStyle::Off,
+        Ok("full") => BacktraceStyle::Full,
+        Ok(_) => BacktraceStyle::Short,
+        Err(_) if cfg!(debug_assertions) => BacktraceStyle::Short,
+        Err(_) => BacktraceStyle::Off,
+    }
+}
+
+/// Backtrace captured without symbols, which are resolved on first use.
+pub(crate) struct LazyBacktrace(Mutex<Backtrace>);
+
+impl LazyBacktrace {
+    /// Captures a backtrace, unless backtraces are turned off.
+    pub(crate) fn capture() -> Option<Self> {
+        (backtrace_style() != BacktraceStyle::Off)
+            .then(|| Self(Mutex::new(Backtrace::new_unresolved())))
+    }
+
+    /// Returns the backtrace, resolving its symbols if needed.
+    pub(crate) fn resolve(&self) -> MutexGuard<'_, Backtrace> {
+        let mut backtrace = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        backtrace.resolve();
+        backtrace
+    }
+}
+
+/// Path prefixes of the symbols hidden by [`BacktraceStyle::Short`].
+const HIDDEN_CRATES: [&str; 6] = [
+    "std::",
+    "core::",
+    "alloc::",
+    "tokio::",
+    "backtrace::",
+    "mischief::",
+];
+
+/// Symbols of the runtime around `main` hidden by
+/// [`BacktraceStyle::Short`], besides those starting with `__`.
+const HIDDEN_SYMBOLS: [&str; 4] = [
+    "main",
+    "_start",
+    "BaseThreadInitThunk",
+    "RtlUserThreadStart",
+];
+
+/// A symbol of a backtrace frame.
+pub(crate) struct Frame<'a> {
+    /// Index of the frame in the backtrace.
+    pub(crate) index: usize,
+
+    /// Demangled name of the symbol, without hashes.
+    pub(crate) name: String,
+
+    /// Source file of the symbol.
+    pub(crate) file: Option<&'a Path>,
+
+    /// Line of the symbol in its source file.
+    pub(crate) line: Option<u32>,
+}
+
+/// Returns the frames of a backtrace shown with `style`, and the number of
+/// hidden frames.
+pub(crate) fn frames(backtrace: &Backtrace, style: BacktraceStyle) -> (Vec<Frame<'_>>, usize) {
+    let mut hidden = 0;
+    let mut frames = Vec::new();
+    for (index, frame) in backtrace.frames().iter().enumerate() {
+        for symbol in frame.symbols() {
+            let name = symbol
+                .name()
+                .map_or_else(|| "<unknown>".to_string(), |name| format!("{name:#}"));
+            if style != BacktraceStyle::Full && is_hidden(&name) {
+                hidden += 1;
+                continue;
+            }
+            frames.push(Frame {
+                index,
+                name,
+                file: symbol.filename(),
+                line: symbol.lineno(),
+            });
+        }
+    }
+    (frames, hidden)
+}
+
+/// Returns `true` if a symbol belongs to the runtime or to a crate hidden
+/// by [`BacktraceStyle::Short`].
+fn is_hidden(name: &str) -> bool {
+    let path = name.trim_start_matches('<');
+    name == "<unknown>"
+        || name.starts_with("__")
+        || HIDDEN_SYMBOLS.contains(&name)
+        || HIDDEN_CRATES.iter().any(|prefix| path.starts_with(prefix))
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "backtrace")]
+use mischief::{BacktraceStyle, mischief, set_backtrace_style};
+
+#[test]
+fn backtrace_style() {
+    set_backtrace_style(BacktraceStyle::Off);
+    assert!(mischief!("port is out of range").backtrace().is_none());
+
+    set_backtrace_style(BacktraceStyle::Short);
+    let report = mischief!("port is out of range");
+    assert!(report.backtrace().is_some());
+    let short = report.to_string();
+    assert!(short.contains("test_backtrace::backtrace_style"));
+    assert!(!short.contains("mischief::report::"));
+    assert!(short.contains("frames hidden, set RUST_BACKTRACE=full to show them"));
+
+    set_backtrace_style(BacktraceStyle::Full);
+    let full = report.to_string();
+    assert!(full.contains("mischief::report::"));
+    assert!(!full.contains("frames hidden"));
+
+    set_backtrace_style(BacktraceStyle::Off);
+    assert!(
+        !report
+            .to_string()
+            .contains("test_backtrace::backtrace_style")
+    );
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "macros")]
+use std::io;
+
+#[cfg(feature = "fancy")]
+use mischief::render::*;
+use mischief::{Diagnosis, IDiagnosis, IntoDiagnosis, Report, Severity, WrapErr};
+
+#[cfg(feature = "fancy")]
+struct NoTheme;
+
+#[cfg(feature = "fancy")]
+impl ITheme for NoTheme {
+    fn default_style(&self) -> Option<&owo_colors::Style> { None }
+    fn description_style(&self) -> Option<&owo_colors::Style> { None }
+    fn severity_style(&self, _severity: Option<mischief::Severity>) -> Option<&owo_colors::Style> {
+        None
+    }
+    fn help_style(&self) -> &(Option<owo_colors::Style>, Option<owo_colors::Style>) {
+        &(None, None)
+    }
+    fn hyperlink_style(&self) -> &(Option<owo_colors::Style>, HyperlinkFormat) {
+        &(None, HyperlinkFormat::Plain)
+    }
+}
+
+#[derive(Debug, thiserror::Error, Diagnosis)]
+#[error("invalid sentence `{sentence}`")]
+#[diagnosis(code = "rax::sentence", help = "Check the talker and sentence ids.")]
+struct SentenceError {
+    sentence: String,
+    #[source]
+    checksum: ChecksumError,
+}
+
+#[derive(Debug, thiserror::Error, Diagnosis)]
+#[diagnosis(url = "https://github.com/Glatzel/toolbox")]
+enum ChecksumError {
+    #[error("checksum mismatch: expected {expected:02X}, found {found:02X}")]
+    #[diagnosis(code = "rax::checksum", severity = Warning)]
+    Mismatch { expected: u8, found: u8 },
+
+    #[error("failed to read the sentence")]
+    #[diagnosis(help = "Check the serial port settings.")]
+    Io(#[from] io::Error),
+}
+
+fn sentence_error() -> SentenceError {
+    SentenceError {
+        sentence: "$GPGGA,,,,*7A".to_string(),
+        checksum: ChecksumError::Mismatch {
+            expected: 0x7A,
+            found: 0x7B,
+        },
+    }
+}
+
+#[test]
+fn diagnosis_derive() {
+    let error = sentence_error();
+    assert_eq!(error.description(), "invalid sentence `$GPGGA,,,,*7A`");
+    assert_eq!(error.code(), Some("rax::sentence"));
+    assert_eq!(error.severity(), None);
+    assert_eq!(error.url(), None);
+
+    let source = error.source().unwrap();
+    assert_eq!(source.code(), Some("rax::checksum"));
+    assert_eq!(source.severity(), Some(Severity::Warning));
+    assert_eq!(source.url(), Some("https://github.com/Glatzel/toolbox"));
+
+    let io = ChecksumError::from(io::Error::other("port closed"));
+    assert_eq!(io.code(), None);
+    assert_eq!(io.help(), Some("Check the serial port settings."));
+    assert!(io.source().is_none());
+}
+
+#[test]
+fn diagnosis_from_diagnosis() {
+    let report = Report::from_diagnosis(sentence_error());
+    assert!(report.is::<SentenceError>());
+    assert!(report.is::<ChecksumError>());
+    #[cfg(feature = "fancy")]
+    {
+        let bundle = RenderBundle {
+            diagnosis: report.error(),
+            theme: NoTheme,
+            indent: MischiefIndent::default(),
+            width: 80,
+        };
+        insta::assert_snapshot!(format!("{}", bundle))
+    }
+
+    let report = Report::from_diagnosis(ChecksumError::from(io::Error::new(
+        io::ErrorKind::TimedOut,
+        "port closed",
+    )));
+    assert_eq!(
+        report.downcast_ref::<io::Error>().unwrap().kind(),
+        io::ErrorKind::TimedOut
+    );
+    assert_eq!(
+        report.error().source().unwrap().description(),
+        "port closed"
+    );
+}
+
+#[derive(Debug, thiserror::Error, Diagnosis)]
+enum ServerError {
+    #[error(transparent)]
+    Sentence(#[from] SentenceError),
+
+    #[error(transparent)]
+    #[diagnosis(code = "shook::io")]
+    Io(#[from] io::Error),
+}
+
+#[test]
+fn diagnosis_transparent() {
+    let error = ServerError::from(sentence_error());
+    assert_eq!(error.description(), "invalid sentence `$GPGGA,,,,*7A`");
+    assert_eq!(error.code(), Some("rax::sentence"));
+    assert_eq!(error.help(), Some("Check the talker and sentence ids."));
+    assert_eq!(error.source().unwrap().code(), Some("rax::checksum"));
+
+    let report = Report::from_diagnosis(error);
+    let source = report.error().source().unwrap();
+    assert_eq!(
+        source.description(),
+        "checksum mismatch: expected 7A, found 7B"
+    );
+    assert!(source.source().is_none());
+    assert!(report.is::<ChecksumError>());
+
+    let error = ServerError::from(io::Error::other("port closed"));
+    assert_eq!(error.description(), "port closed");
+    assert_eq!(error.code(), Some("shook::io"));
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn diagnosis_question_mark() {
+    fn parse() -> mischief::Result<()> {
+        Err(sentence_error()).into_diagnosis()?;
+        Ok(())
+    }
+    fn start() -> mischief::Result<()> {
+        Err(sentence_error()).wrap_err("Failed to start")?;
+        Ok(())
+    }
+
+    let report = parse().unwrap_err();
+    assert_eq!(report.error().code(), Some("rax::sentence"));
+    assert!(report.is::<SentenceError>());
+
+    let report = start().unwrap_err();
+    let source = report.error().source().unwrap();
+    assert_eq!(source.code(), Some("rax::sentence"));
+    assert_eq!(source.source().unwrap().code(), Some("rax::checksum"));
+}
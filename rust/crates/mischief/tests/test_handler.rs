@@ -0,0 +1,117 @@
+use core::fmt;
+
+use mischief::render::*;
+use mischief::{IDiagnosis, IReportHandler, WrapErr, mischief};
+
+/// Handler printing the descriptions of the chain on one line.
+struct OneLine;
+
+impl IReportHandler for OneLine {
+    fn render(&self, diagnosis: &dyn IDiagnosis, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", diagnosis.description())?;
+        let mut source = diagnosis.source();
+        while let Some(diagnosis) = source {
+            write!(f, ": {}", diagnosis.description())?;
+            source = diagnosis.source();
+        }
+        Ok(())
+    }
+}
+
+fn report() -> mischief::Report {
+    #[cfg(feature = "backtrace")]
+    mischief::set_backtrace_style(mischief::BacktraceStyle::Off);
+    let result: mischief::Result<()> = Err(mischief!(
+        "port is out of range",
+        help = "Use a port below 65536."
+    ))
+    .wrap_err("failed to load the configuration");
+    result.unwrap_err()
+}
+
+#[test]
+fn handler_display_with() {
+    let report = report();
+    assert_eq!(
+        report.display_with(&OneLine).to_string(),
+        "failed to load the configuration: port is out of range"
+    );
+    #[cfg(feature = "fancy")]
+    {
+        let handler = MischiefHandler {
+            width: Some(80),
+            ..MischiefHandler::no_color()
+        };
+        insta::assert_snapshot!(
+            ("handler_display_with_fancy"),
+            report.display_with(&handler).to_string()
+        );
+        let handler = MischiefHandler {
+            indent: MischiefIndent::ascii(None),
+            ..handler
+        };
+        insta::assert_snapshot!(
+            ("handler_display_with_ascii"),
+            report.display_with(&handler).to_string()
+        );
+    }
+    #[cfg(not(feature = "fancy"))]
+    insta::assert_snapshot!(
+        ("handler_display_with_no_fancy"),
+        report.display_with(&MischiefHandler).to_string()
+    );
+}
+
+/// Handler summarizing a whole report instead of rendering each diagnosis.
+struct Summary;
+
+impl IReportHandler for Summary {
+    fn render(&self, diagnosis: &dyn IDiagnosis, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", diagnosis.description())
+    }
+
+    fn render_report(&self, report: &mischief::Report, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error[{}]: ", report.error().help().unwrap_or("-"))?;
+        self.render(report.error(), f)
+    }
+}
+
+#[test]
+fn handler_display_with_render_report() {
+    let report = mischief!("port is out of range", help = "Use a port below 65536.");
+    assert_eq!(
+        report.display_with(&Summary).to_string(),
+        "error[Use a port below 65536.]: port is out of range"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn handler_set_hook() {
+    let report = report();
+    let default = report.to_string();
+    mischief::set_hook(OneLine);
+    assert!(
+        report
+            .to_string()
+            .starts_with("failed to load the configuration: port is out of range")
+    );
+    assert!(mischief::take_hook().is_some());
+    assert!(mischief::take_hook().is_none());
+    assert_eq!(report.to_string(), default);
+}
+
+#[cfg(feature = "fancy")]
+#[test]
+fn color_enabled_by_environment() {
+    use std::ffi::OsStr;
+    let value = |value: &'static str| Some(OsStr::new(value));
+    let default = cfg!(feature = "color");
+    assert_eq!(color_enabled_by(None, None), default);
+    assert!(!color_enabled_by(value("1"), None));
+    assert!(color_enabled_by(None, value("1")));
+    assert!(color_enabled_by(value("1"), value("1")));
+    // `CLICOLOR_FORCE=0` and empty values count as unset.
+    assert!(!color_enabled_by(value("1"), value("0")));
+    assert_eq!(color_enabled_by(value(""), value("")), default);
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "json")]
+use mischief::render::{to_json, to_sarif};
+use mischief::{IDiagnosis, Label, Report, SourceCode, WrapErr, mischief};
+
+fn report() -> Report {
+    let result: mischief::Result<()> = Err(mischief!(
+        "invalid timeout",
+        severity = mischief::Severity::Warning,
+        code = "shook::timeout",
+        url = "https://github.com/Glatzel/toolbox",
+        help = "Use a number of seconds."
+    )
+    .with_source_code(SourceCode::new(
+        "shook.toml",
+        "[hook]\ntimeout = \"ten\"\nretries = 3\n",
+    ))
+    .with_label(Label::primary(17..22, Some("expected an integer")))
+    .with_label(Label::secondary(0..6, Some("in this table"))))
+    .wrap_err("failed to load the configuration");
+    result.unwrap_err().attach("job", 42)
+}
+
+#[test]
+fn json_report() {
+    let report = report();
+    insta::assert_snapshot!(format!("{:#}", to_json(report.error())));
+
+    let json = report.to_json();
+    assert_eq!(json["description"], "failed to load the configuration");
+    #[cfg(feature = "backtrace")]
+    assert_eq!(
+        json.get("backtrace").is_some(),
+        report.backtrace().is_some()
+    );
+    #[cfg(not(feature = "backtrace"))]
+    assert!(json.get("backtrace").is_none());
+}
+
+#[test]
+fn json_display_with() {
+    let report = report();
+    let rendered = report
+        .display_with(&mischief::render::JsonHandler { pretty: false })
+        .to_string();
+    let json: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+    assert_eq!(json, report.to_json());
+}
+
+#[test]
+fn json_aggregate() {
+    let report = Report::aggregate([mischief!("port is out of range"), report()]);
+    let json = to_json(report.error());
+    assert_eq!(json["description"], "2 errors occurred");
+    assert_eq!(json["related"][1]["causes"][0]["code"], "shook::timeout");
+}
+
+#[test]
+fn json_sarif() {
+    let report = Report::aggregate([mischief!("port is out of range"), report()]);
+    insta::assert_snapshot!(format!("{:#}", report.to_sarif("shook")));
+
+    let plain = mischief!("port is out of range");
+    let sarif = to_sarif([plain.error() as &dyn IDiagnosis], "shook");
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["level"], "error");
+    assert!(results[0].get("locations").is_none());
+}
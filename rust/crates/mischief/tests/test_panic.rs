@@ -0,0 +1,53 @@
+#![cfg(feature = "std")]
+use mischief::PanicHook;
+
+#[test]
+fn panic_crash_report() {
+    let dir = std::env::temp_dir().join(format!("mischief-panic-{}", std::process::id()));
+    // Crash reports carry a backtrace even when the terminal shows none.
+    #[cfg(feature = "backtrace")]
+    mischief::set_backtrace_style(mischief::BacktraceStyle::Off);
+    PanicHook::new()
+        .with_issue_url("https://github.com/Glatzel/toolbox/issues")
+        .with_crash_report(&dir)
+        .install();
+    let result = std::panic::catch_unwind(|| panic!("sentence buffer overflow"));
+    assert!(result.is_err());
+    // Panics of other threads get their own crash report.
+    let threads: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| panic!("worker overflow")))
+        .collect();
+    for thread in threads {
+        assert!(thread.join().is_err());
+    }
+    let _ = std::panic::take_hook();
+
+    let reports: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(reports.len(), 5);
+    assert_eq!(
+        reports
+            .iter()
+            .filter(|report| report.contains("worker overflow"))
+            .count(),
+        4
+    );
+    let report = reports
+        .iter()
+        .find(|report| report.contains("sentence buffer overflow"))
+        .unwrap();
+
+    assert!(report.contains("The application panicked"));
+    assert!(report.contains("sentence buffer overflow"));
+    assert!(report.contains("thread: panic_crash_report"));
+    assert!(report.contains("location: crates/mischief/tests/test_panic.rs:14:"));
+    assert!(!report.contains('\x1b'));
+    #[cfg(feature = "backtrace")]
+    {
+        assert!(report.contains("\nBacktrace:\n"));
+        assert!(report.contains("test_panic::panic_crash_report"));
+    }
+}
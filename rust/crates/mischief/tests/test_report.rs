@@ -1,5 +1,5 @@
 use mischief::render::*;
-use mischief::{IntoMischief, WrapErr, mischief};
+use mischief::{IntoMischief, Report, WrapErr, mischief};
 
 #[cfg(feature = "fancy")]
 struct NoTheme;
@@ -141,6 +141,201 @@ fn report_from_error() -> mischief::Result<()> {
     Ok(())
 }
 
+#[test]
+fn report_labels() {
+    let source = "[server]\nport = 8080\nhost = \"localhost\"\n\n[hooks]\ntimeout = \"ten\"\n";
+    let result: mischief::Result<()> = Err(mischief!(
+        "invalid type: string \"ten\", expected an integer",
+        code = "E001",
+        help = "Use a number of seconds."
+    )
+    .with_source_code(mischief::SourceCode::new("shook.toml", source))
+    .with_label(mischief::Label::primary(
+        59..64,
+        Some("expected an integer"),
+    ))
+    .with_label(mischief::Label::secondary(
+        9..39,
+        Some("server configured here"),
+    ))
+    .with_label(mischief::Label::secondary(41..48, None::<&str>)))
+    .wrap_err("Failed to load the configuration");
+    match result {
+        Ok(_) => unreachable!(),
+        Err(report) => {
+            #[cfg(feature = "fancy")]
+            {
+                let bundle = RenderBundle {
+                    diagnosis: report.error(),
+                    theme: NoTheme,
+                    indent: MischiefIndent::default(),
+                    width: 80,
+                };
+                insta::assert_snapshot!(("report_labels_fancy"), format!("{}", bundle))
+            }
+            #[cfg(not(feature = "fancy"))]
+            {
+                println!("{}", report);
+                insta::assert_snapshot!(("report_labels_no_fancy"), format!("{}", report))
+            }
+        }
+    }
+}
+
+#[test]
+fn report_attachments() {
+    let result: mischief::Result<()> =
+        Err(mischief!("exit status 1")
+            .with_section("stderr", "error: unused import\nerror: aborting"))
+        .wrap_err("Hook `clippy` failed")
+        .map_err(|e| {
+            e.attach("job", 42)
+                .attach("sandbox", "ci-linux")
+                .note("Hooks run in a clean checkout.")
+        });
+    let report = result.unwrap_err();
+    assert_eq!(report.attachment("sandbox"), Some("ci-linux"));
+    assert_eq!(report.attachment("stderr"), None);
+    #[cfg(feature = "fancy")]
+    {
+        let bundle = RenderBundle {
+            diagnosis: report.error(),
+            theme: NoTheme,
+            indent: MischiefIndent::default(),
+            width: 80,
+        };
+        insta::assert_snapshot!(("report_attachments_fancy"), format!("{}", bundle))
+    }
+    #[cfg(not(feature = "fancy"))]
+    {
+        println!("{}", report);
+        insta::assert_snapshot!(("report_attachments_no_fancy"), format!("{}", report))
+    }
+}
+
+#[test]
+fn report_aggregate() {
+    let errors = [
+        Err::<(), _>("expected an integer")
+            .map_err(|e| mischief!("{}", e, code = "E001"))
+            .wrap_err("Invalid `timeout`"),
+        Err(mischief!("must not be empty", help = "Set a host name.")),
+        Err(mischief::Report::aggregate([
+            mischief!("unknown hook `pre-merge`"),
+            mischief!("unknown hook `post-land`"),
+        ])),
+    ];
+    let report = mischief::Report::aggregate(errors.into_iter().filter_map(Result::err));
+    #[cfg(feature = "fancy")]
+    {
+        let bundle = RenderBundle {
+            diagnosis: report.error(),
+            theme: NoTheme,
+            indent: MischiefIndent::default(),
+            width: 80,
+        };
+        insta::assert_snapshot!(("report_aggregate_fancy"), format!("{}", bundle))
+    }
+    #[cfg(not(feature = "fancy"))]
+    {
+        println!("{}", report);
+        insta::assert_snapshot!(("report_aggregate_no_fancy"), format!("{}", report))
+    }
+}
+
+#[test]
+fn report_aggregate_cause() {
+    let report = Err::<(), _>(mischief::Report::aggregate([
+        mischief!("unknown hook `pre-merge`"),
+        mischief!("unknown hook `post-land`"),
+    ]))
+    .wrap_err("Failed to load the hooks")
+    .unwrap_err();
+    #[cfg(feature = "fancy")]
+    {
+        let bundle = RenderBundle {
+            diagnosis: report.error(),
+            theme: NoTheme,
+            indent: MischiefIndent::default(),
+            width: 80,
+        };
+        insta::assert_snapshot!(("report_aggregate_cause_fancy"), format!("{}", bundle))
+    }
+    #[cfg(not(feature = "fancy"))]
+    {
+        insta::assert_snapshot!(("report_aggregate_cause_no_fancy"), format!("{}", report))
+    }
+}
+
+#[test]
+fn report_downcast() {
+    use std::io;
+
+    #[derive(Debug)]
+    struct ConfigError(io::Error);
+    impl std::fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "failed to read the configuration")
+        }
+    }
+    impl std::error::Error for ConfigError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { Some(&self.0) }
+    }
+
+    let report = Err::<(), _>(Report::from_error(ConfigError(io::Error::new(
+        io::ErrorKind::NotFound,
+        "shook.toml",
+    ))))
+    .wrap_err("Failed to start")
+    .unwrap_err();
+
+    assert!(report.is::<ConfigError>());
+    assert!(!report.is::<std::fmt::Error>());
+    assert_eq!(
+        report.downcast_ref::<io::Error>().unwrap().kind(),
+        io::ErrorKind::NotFound
+    );
+    assert_eq!(
+        report.chain().map(|e| e.to_string()).collect::<Vec<_>>(),
+        ["failed to read the configuration", "shook.toml"]
+    );
+
+    let clone = Report::new(report.error().clone());
+    assert!(clone.is::<ConfigError>());
+    assert_eq!(
+        clone.downcast_ref::<io::Error>().unwrap().kind(),
+        io::ErrorKind::NotFound
+    );
+    let report = report.downcast::<ConfigError>().unwrap_err();
+    drop(clone);
+
+    let report = report.downcast::<std::fmt::Error>().unwrap_err();
+    let error = report.downcast::<ConfigError>().unwrap();
+    assert_eq!(error.0.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn report_from_borrowing_error() {
+    use mischief::IDiagnosis;
+
+    #[derive(Debug)]
+    struct ParseError<'a>(&'a str);
+    impl std::fmt::Display for ParseError<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "unexpected token `{}`", self.0)
+        }
+    }
+    impl std::error::Error for ParseError<'_> {}
+
+    let input = String::from("}");
+    let report = Err::<(), _>(ParseError(&input))
+        .into_mischief()
+        .unwrap_err();
+    drop(input);
+    assert_eq!(report.error().description(), "unexpected token `}`");
+    assert_eq!(report.chain().count(), 0);
+}
+
 #[test]
 fn report_ok() -> mischief::Result<()> {
     Ok::<i32, mischief::Result<()>>(2i32)
@@ -149,3 +344,20 @@ fn report_ok() -> mischief::Result<()> {
         .wrap_err_with(|| "Third error")?;
     Ok(())
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn report_across_threads() {
+    let report = std::thread::spawn(|| {
+        Err::<(), _>(mischief!("Worker failed"))
+            .wrap_err("Job failed")
+            .unwrap_err()
+    })
+    .join()
+    .unwrap();
+    assert_eq!(report.as_error().to_string(), "Job failed");
+    assert_eq!(
+        report.as_error().source().unwrap().to_string(),
+        "Worker failed"
+    );
+}
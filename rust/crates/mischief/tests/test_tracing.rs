@@ -0,0 +1,105 @@
+#![cfg(feature = "tracing")]
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use mischief::{IntoMischief, WrapErr};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Writer collecting the output of a fmt layer.
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+}
+
+impl Buffer {
+    fn contents(&self) -> String { String::from_utf8(self.0.lock().unwrap().clone()).unwrap() }
+}
+
+fn failing_job() -> mischief::Result<()> {
+    Err::<(), _>(std::io::Error::other("port closed"))
+        .into_mischief()
+        .wrap_err("Job failed")
+}
+
+#[test]
+fn tracing_span_trace() {
+    let buffer = Buffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(move || writer.clone()),
+    );
+    tracing::subscriber::with_default(subscriber, || {
+        let report = tracing::info_span!("job", id = 42)
+            .in_scope(failing_job)
+            .unwrap_err();
+        let span_trace = report.span_trace().unwrap().to_string();
+        assert!(span_trace.contains("test_tracing::job with id=42"));
+        assert!(span_trace.contains("crates/mischief/tests/test_tracing.rs:"));
+        assert!(report.to_string().contains("Span trace"));
+
+        tracing::error!(error = report.as_error(), "job failed");
+    });
+    let logs = buffer.contents();
+    assert!(logs.contains("error=Job failed error.sources=[port closed]"));
+    assert!(!logs.contains('\x1b'));
+}
+
+#[test]
+fn tracing_no_span() {
+    let subscriber = tracing_subscriber::registry().with(tracing_subscriber::fmt::layer());
+    tracing::subscriber::with_default(subscriber, || {
+        let report = failing_job().unwrap_err();
+        assert!(report.span_trace().is_none());
+    });
+    let report = failing_job().unwrap_err();
+    assert!(report.span_trace().is_none());
+}
+
+#[cfg(all(feature = "backtrace", feature = "fancy"))]
+#[test]
+fn tracing_handler_presets() {
+    use mischief::render::MischiefHandler;
+
+    mischief::set_backtrace_style(mischief::BacktraceStyle::Short);
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(std::io::sink),
+    );
+    let report = tracing::subscriber::with_default(subscriber, || {
+        tracing::info_span!("job", id = 42)
+            .in_scope(failing_job)
+            .unwrap_err()
+    });
+    assert!(report.span_trace().is_some());
+    assert!(report.backtrace().is_some());
+
+    let handler = MischiefHandler {
+        width: Some(40),
+        ..MischiefHandler::no_color()
+    };
+    let no_color = report.display_with(&handler).to_string();
+    assert!(no_color.contains(" Span trace "));
+    assert!(no_color.contains(" Backtrace "));
+    assert!(no_color.contains("test_tracing::tracing_handler_presets"));
+    assert!(!no_color.contains('\x1b'));
+    assert!(
+        no_color
+            .lines()
+            .filter(|line| line.contains(" Backtrace "))
+            .all(|line| line.chars().count() == 40)
+    );
+
+    let ascii = report.display_with(&MischiefHandler::ascii()).to_string();
+    assert!(ascii.contains("=="));
+    assert!(ascii.contains("`-"));
+    assert!(ascii.is_ascii());
+}
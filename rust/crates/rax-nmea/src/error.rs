@@ -1,56 +1,113 @@
 extern crate alloc;
+#[cfg(feature = "tokio")]
+extern crate std;
 use alloc::string::String;
 use core::convert::Infallible;
 use core::num::{ParseFloatError, ParseIntError};
 
 #[derive(Debug, thiserror::Error)]
+#[cfg_attr(feature = "mischief", derive(mischief::Diagnosis))]
 pub enum RaxNmeaError {
     #[error("Invalid sentence: {0:?}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::invalid_sentence"))]
     InvalidSentence(String),
     #[error("Invalid sentence length: {0}")]
+    #[cfg_attr(
+        feature = "mischief",
+        diagnosis(
+            code = "rax_nmea::sentence_length",
+            help = "NMEA 0183 limits a sentence to 82 characters."
+        )
+    )]
     InvalidSentenceLength(usize),
     #[error("Sentence doesn't start with `$`: {0}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::sentence_prefix"))]
     InvalidSentencePrefix(String),
     #[error("require checksum_str length 2, get {0}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::checksum_length"))]
     InvalidChecksumLength(usize),
     #[error("Missing checksum delimiter`*`: {0}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::checksum_delimiter"))]
     MissingChecksumDelimiter(String),
     #[error("Checksum mismatch: calculated {calculated:02X}, expected {expected:02X}")]
+    #[cfg_attr(
+        feature = "mischief",
+        diagnosis(
+            code = "rax_nmea::checksum",
+            help = "Check the serial port settings, such as the baud rate."
+        )
+    )]
     ChecksumMismatch { calculated: u8, expected: u8 },
 
     #[error("Unknown identifier: {0:?}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::unknown_identifier"))]
     UnknownIdentifier(String),
     #[error("Unknown talker: {0:?}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::unknown_talker"))]
     UnknownTalker(String),
     #[error("Unknown Faa mode: {0:?}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::unknown_faa_mode"))]
     UnknownFaaMode(String),
     #[error("Unknown system ID: {0:?}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::unknown_system_id"))]
     UnknownSystemId(String),
     #[error("Unknown status: {0:?}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::unknown_status"))]
     UnknownStatus(String),
     #[error("Unknown DTM datum: {0:?}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::unknown_dtm_datum"))]
     UnknownDtmDatum(String),
     #[error("Unknown navigation status: {0:?}")]
+    #[cfg_attr(
+        feature = "mischief",
+        diagnosis(code = "rax_nmea::unknown_navigation_status")
+    )]
     UnknownNavigationStatus(String),
     #[error("Unknown GGA quality indicator: {0:?}")]
+    #[cfg_attr(
+        feature = "mischief",
+        diagnosis(code = "rax_nmea::unknown_gga_quality_indicator")
+    )]
     UnknownGgaQualityIndicator(String),
     #[error("Unknown GRS residual mode: {0:?}")]
+    #[cfg_attr(
+        feature = "mischief",
+        diagnosis(code = "rax_nmea::unknown_grs_residual_mode")
+    )]
     UnknownGrsResidualMode(String),
     #[error("Unknown TXT type: {0}")]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::unknown_txt_type"))]
     UnknownTxtType(u8),
     #[error("Unknown GSA selection mode: {0:?}")]
+    #[cfg_attr(
+        feature = "mischief",
+        diagnosis(code = "rax_nmea::unknown_gsa_selection_mode")
+    )]
     UnknownGsaSelectionMode(String),
     #[error("Unknown GSA navigation mode: {0:?}")]
+    #[cfg_attr(
+        feature = "mischief",
+        diagnosis(code = "rax_nmea::unknown_gsa_navigation_mode")
+    )]
     UnknownGsaNavigationMode(String),
 
     #[error(transparent)]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::rule"))]
     RaxVerb(#[from] rax::error::VerbError),
     #[error(transparent)]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::parse_float"))]
     ParseFloat(#[from] ParseFloatError),
     #[error(transparent)]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::parse_int"))]
     ParseInt(#[from] ParseIntError),
     #[error(transparent)]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::infallible"))]
     Infallible(#[from] Infallible),
     #[error(transparent)]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::parse_enum"))]
     Strum(#[from] strum::ParseError),
+    #[cfg(feature = "tokio")]
+    #[error(transparent)]
+    #[cfg_attr(feature = "mischief", diagnosis(code = "rax_nmea::io"))]
+    Io(#[from] std::io::Error),
 }
@@ -0,0 +1,441 @@
+//! Receiver integrity monitoring (RAIM-style) from `GBS`, `GRS` and `GST`.
+//!
+//! The receiver reports its own fault detection (`GBS`), range residuals
+//! (`GRS`) and error statistics (`GST`) for every fix. This module groups them
+//! per epoch, derives horizontal/vertical protection levels from the reported
+//! 1-sigma errors and compares them with configurable alert limits.
+//!
+//! # References
+//!
+//! * RTCA DO-229, section 2.1.1.4 (protection levels)
+//! * <https://gpsd.gitlab.io/gpsd/NMEA.html#_gbs_gps_satellite_fault_detection>
+//! * <https://gpsd.gitlab.io/gpsd/NMEA.html#_grs_gps_range_residuals>
+//! * <https://gpsd.gitlab.io/gpsd/NMEA.html#_gst_gps_pseudorange_noise_statistics>
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use derive_getters::Getters;
+use jiff::civil::Time;
+
+use crate::common::SystemId;
+use crate::sentence::{Gbs, Grs, Gst, NmeaSentence};
+
+/// Thresholds used to evaluate an epoch.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntegrityConfig {
+    /// Horizontal alert limit (meters).
+    pub horizontal_alert_limit: f64,
+
+    /// Vertical alert limit (meters).
+    pub vertical_alert_limit: f64,
+
+    /// Multiplier turning the horizontal 1-sigma error into a protection
+    /// level.
+    pub k_horizontal: f64,
+
+    /// Multiplier turning the vertical 1-sigma error into a protection level.
+    pub k_vertical: f64,
+
+    /// Absolute range residual above which a satellite is suspected faulty
+    /// (meters).
+    pub max_residual: f64,
+
+    /// Bias of the `GBS` most likely failed satellite, in units of its
+    /// standard deviation, above which the satellite is suspected faulty.
+    pub max_normalized_bias: f64,
+}
+
+impl IntegrityConfig {
+    /// Creates a config with the given alert limits and default multipliers
+    /// and fault thresholds.
+    pub fn new(horizontal_alert_limit: f64, vertical_alert_limit: f64) -> Self {
+        Self {
+            horizontal_alert_limit,
+            vertical_alert_limit,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for IntegrityConfig {
+    /// Alert limits of an LPV approach, `K` factors of DO-229 precision
+    /// approach.
+    fn default() -> Self {
+        Self {
+            horizontal_alert_limit: 40.0,
+            vertical_alert_limit: 50.0,
+            k_horizontal: 6.0,
+            k_vertical: 5.33,
+            max_residual: 30.0,
+            max_normalized_bias: 3.0,
+        }
+    }
+}
+
+/// Overall integrity of an epoch.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord, strum::AsRefStr)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntegrityState {
+    /// Protection levels are within the alert limits and no satellite is
+    /// suspected.
+    #[strum(serialize = "Nominal")]
+    Nominal,
+
+    /// No error statistics were received, protection levels are unknown.
+    #[strum(serialize = "Unknown")]
+    Unknown,
+
+    /// A protection level exceeds its alert limit.
+    #[strum(serialize = "Alert Limit Exceeded")]
+    AlertLimitExceeded,
+
+    /// At least one satellite is suspected faulty.
+    #[strum(serialize = "Fault Detected")]
+    FaultDetected,
+}
+
+/// A satellite suspected faulty, and why.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SuspectedSatellite {
+    /// Most likely failed satellite reported by `GBS`.
+    FaultDetection {
+        /// Satellite ID.
+        svid: u16,
+
+        /// System ID.
+        system_id: Option<SystemId>,
+
+        /// Probability of missed detection.
+        prob: Option<f64>,
+
+        /// Estimated bias (meters).
+        bias: Option<f64>,
+
+        /// Standard deviation of the bias estimate (meters).
+        std_dev: Option<f64>,
+    },
+
+    /// Satellite whose `GRS` range residual exceeds
+    /// [`IntegrityConfig::max_residual`].
+    Residual {
+        /// Position of the satellite in the `GSA` satellite list of the same
+        /// system; `GRS` does not carry satellite IDs.
+        index: usize,
+
+        /// System ID.
+        system_id: Option<SystemId>,
+
+        /// Range residual (meters).
+        residual: f64,
+    },
+}
+
+/// Integrity evaluation of one epoch.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct IntegrityStatus {
+    /// UTC time of the epoch.
+    time: Option<Time>,
+
+    /// Overall state.
+    state: IntegrityState,
+
+    /// Horizontal 1-sigma error (meters).
+    horizontal_sigma: Option<f64>,
+
+    /// Vertical 1-sigma error (meters).
+    vertical_sigma: Option<f64>,
+
+    /// Horizontal protection level (meters).
+    horizontal_protection_level: Option<f64>,
+
+    /// Vertical protection level (meters).
+    vertical_protection_level: Option<f64>,
+
+    /// Satellites suspected faulty.
+    suspected: Vec<SuspectedSatellite>,
+}
+
+impl IntegrityStatus {
+    /// Whether the fix of this epoch can be used.
+    pub fn is_usable(&self) -> bool { self.state == IntegrityState::Nominal }
+}
+
+/// Evaluates one epoch.
+///
+/// The horizontal 1-sigma error is the `GST` error ellipse semi-major axis, or
+/// the root sum square of the latitude/longitude errors when the ellipse is
+/// missing. When both `GBS` and `GST` are present the larger error is used.
+pub fn evaluate(
+    config: &IntegrityConfig,
+    gbs: Option<&Gbs>,
+    grs: &[Grs],
+    gst: Option<&Gst>,
+) -> IntegrityStatus {
+    let time = gbs
+        .and_then(|s| *s.time())
+        .or_else(|| gst.and_then(|s| *s.time()))
+        .or_else(|| grs.iter().find_map(|s| *s.time()));
+
+    let horizontal_sigma = max_option(
+        gst.and_then(|s| (*s.std_major()).or_else(|| root_sum_square(*s.std_lat(), *s.std_lon()))),
+        gbs.and_then(|s| root_sum_square(*s.err_lat(), *s.err_lon())),
+    );
+    let vertical_sigma = max_option(
+        gst.and_then(|s| *s.std_alt()),
+        gbs.and_then(|s| *s.err_alt()),
+    );
+    let horizontal_protection_level = horizontal_sigma.map(|s| s * config.k_horizontal);
+    let vertical_protection_level = vertical_sigma.map(|s| s * config.k_vertical);
+
+    let mut suspected = Vec::new();
+    if let Some(gbs) = gbs
+        && let Some(svid) = *gbs.svid()
+    {
+        let exceeded = match (*gbs.bias(), *gbs.std_dev()) {
+            (Some(bias), Some(std_dev)) if std_dev > 0.0 => {
+                libm::fabs(bias) > config.max_normalized_bias * std_dev
+            }
+            // Without a bias estimate the receiver's report is taken as is.
+            _ => true,
+        };
+        if exceeded {
+            suspected.push(SuspectedSatellite::FaultDetection {
+                svid,
+                system_id: *gbs.system_id(),
+                prob: *gbs.prob(),
+                bias: *gbs.bias(),
+                std_dev: *gbs.std_dev(),
+            });
+        }
+    }
+    for grs in grs {
+        for (index, residual) in grs.residual().iter().enumerate() {
+            if libm::fabs(*residual) > config.max_residual {
+                suspected.push(SuspectedSatellite::Residual {
+                    index,
+                    system_id: *grs.system_id(),
+                    residual: *residual,
+                });
+            }
+        }
+    }
+
+    let exceeds = |level: Option<f64>, limit: f64| level.is_some_and(|l| l > limit);
+    let state = if !suspected.is_empty() {
+        IntegrityState::FaultDetected
+    } else if exceeds(horizontal_protection_level, config.horizontal_alert_limit)
+        || exceeds(vertical_protection_level, config.vertical_alert_limit)
+    {
+        IntegrityState::AlertLimitExceeded
+    } else if horizontal_protection_level.is_none() || vertical_protection_level.is_none() {
+        IntegrityState::Unknown
+    } else {
+        IntegrityState::Nominal
+    };
+    clerk::debug!(
+        "integrity::evaluate: time={:?}, state={:?}, hpl={:?}, vpl={:?}, suspected={}",
+        time,
+        state,
+        horizontal_protection_level,
+        vertical_protection_level,
+        suspected.len()
+    );
+
+    IntegrityStatus {
+        time,
+        state,
+        horizontal_sigma,
+        vertical_sigma,
+        horizontal_protection_level,
+        vertical_protection_level,
+        suspected,
+    }
+}
+
+fn root_sum_square(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(libm::sqrt(a * a + b * b)),
+        _ => None,
+    }
+}
+
+fn max_option(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Groups `GBS`, `GRS` and `GST` sentences per epoch and evaluates them.
+///
+/// An epoch ends when a sentence with a different UTC time arrives, or when
+/// [`IntegrityMonitor::flush`] is called. Sentences without a time are added to
+/// the current epoch.
+#[derive(Debug, Clone)]
+pub struct IntegrityMonitor {
+    config: IntegrityConfig,
+    time: Option<Time>,
+    gbs: Option<Gbs>,
+    grs: Vec<Grs>,
+    gst: Option<Gst>,
+}
+
+impl IntegrityMonitor {
+    pub const fn new(config: IntegrityConfig) -> Self {
+        Self {
+            config,
+            time: None,
+            gbs: None,
+            grs: Vec::new(),
+            gst: None,
+        }
+    }
+
+    /// Returns the config.
+    pub const fn config(&self) -> &IntegrityConfig { &self.config }
+
+    /// Adds a sentence, returning the status of the previous epoch if this
+    /// sentence starts a new one.
+    ///
+    /// Sentences other than `GBS`, `GRS` and `GST` are ignored.
+    pub fn push(&mut self, sentence: &NmeaSentence) -> Option<IntegrityStatus> {
+        match sentence {
+            NmeaSentence::GBS(_, gbs) => self.push_gbs(gbs.clone()),
+            NmeaSentence::GRS(_, grs) => self.push_grs(grs.clone()),
+            NmeaSentence::GST(_, gst) => self.push_gst(gst.clone()),
+            _ => None,
+        }
+    }
+
+    /// Adds a `GBS`, see [`IntegrityMonitor::push`].
+    pub fn push_gbs(&mut self, gbs: Gbs) -> Option<IntegrityStatus> {
+        let status = self.advance(*gbs.time());
+        self.gbs = Some(gbs);
+        status
+    }
+
+    /// Adds a `GRS`, see [`IntegrityMonitor::push`].
+    pub fn push_grs(&mut self, grs: Grs) -> Option<IntegrityStatus> {
+        let status = self.advance(*grs.time());
+        self.grs.push(grs);
+        status
+    }
+
+    /// Adds a `GST`, see [`IntegrityMonitor::push`].
+    pub fn push_gst(&mut self, gst: Gst) -> Option<IntegrityStatus> {
+        let status = self.advance(*gst.time());
+        self.gst = Some(gst);
+        status
+    }
+
+    /// Evaluates the current epoch, if any sentence was added to it.
+    pub fn flush(&mut self) -> Option<IntegrityStatus> {
+        if self.gbs.is_none() && self.grs.is_empty() && self.gst.is_none() {
+            return None;
+        }
+        let mut status = evaluate(
+            &self.config,
+            self.gbs.as_ref(),
+            &self.grs,
+            self.gst.as_ref(),
+        );
+        status.time = status.time.or(self.time);
+        self.time = None;
+        self.gbs = None;
+        self.grs.clear();
+        self.gst = None;
+        Some(status)
+    }
+
+    fn advance(&mut self, time: Option<Time>) -> Option<IntegrityStatus> {
+        let status = match (self.time, time) {
+            (Some(current), Some(time)) if current != time => self.flush(),
+            _ => None,
+        };
+        if time.is_some() {
+            self.time = time;
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use clerk::{LevelFilter, init_log_with_level};
+    use rax::string::{Decoder, IDecode};
+    use rstest::rstest;
+
+    use super::*;
+    use crate::RaxNmeaError;
+
+    fn decode<D>(input: &str) -> D
+    where
+        D: IDecode<RaxNmeaError>,
+    {
+        D::decode(&mut Decoder::new(input)).unwrap()
+    }
+
+    #[rstest]
+    #[case("gst_nominal", None, &[], Some("$GPGST,172814.0,0.006,0.023,0.020,273.6,0.023,0.020,0.031*6A"))]
+    #[case("gst_alert_limit", None, &[], Some("$GPGST,182141.000,15.5,15.3,7.2,21.8,0.9,0.5,0.8*54"))]
+    #[case("gbs_fault", Some("$GPGBS,235458.00,1.4,1.3,3.1,03,,-21.4,3.8,1,0*5B"), &[], None)]
+    #[case("gbs_alert_limit", Some("$GPGBS,125027,23.43,M,13.91,M,34.01,M*07"), &[], None)]
+    #[case(
+        "grs_residual",
+        None,
+        &["$GPGRS,220320.0,0,-0.8,-0.2,-0.1,-0.2,0.8,0.6,,,,,,,*55", "$GPGRS,220320.0,0,-38.8,-0.2,,,,,,,,,,,*63"],
+        Some("$GPGST,220320.0,0.006,0.023,0.020,273.6,0.023,0.020,0.031*62")
+    )]
+    #[case("no_data", None, &["$GPGRS,220320.0,0,-0.8,-0.2,-0.1,-0.2,0.8,0.6,,,,,,,*55"], None)]
+    fn test_evaluate(
+        #[case] name: &str,
+        #[case] gbs: Option<&str>,
+        #[case] grs: &[&str],
+        #[case] gst: Option<&str>,
+    ) {
+        init_log_with_level(LevelFilter::TRACE);
+        let gbs = gbs.map(decode::<Gbs>);
+        let grs: Vec<Grs> = grs.iter().map(|s| decode(s)).collect();
+        let gst = gst.map(decode::<Gst>);
+        let status = evaluate(
+            &IntegrityConfig::default(),
+            gbs.as_ref(),
+            &grs,
+            gst.as_ref(),
+        );
+        insta::assert_debug_snapshot!(name, status);
+    }
+
+    #[test]
+    fn test_monitor_epochs() {
+        init_log_with_level(LevelFilter::TRACE);
+        let mut monitor = IntegrityMonitor::new(IntegrityConfig::new(10.0, 15.0));
+        let sentences = [
+            "$GPGST,172814.0,0.006,0.023,0.020,273.6,0.023,0.020,0.031*6A",
+            "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47",
+            "$GPGBS,235458.00,1.4,1.3,3.1,03,,-21.4,3.8,1,0*5A",
+            "$GPGST,235458.00,1.4,1.3,0.9,21.8,1.4,1.3,3.1*6A",
+        ];
+        let mut statuses = Vec::new();
+        for s in sentences {
+            let sentence: NmeaSentence = decode(s);
+            statuses.extend(monitor.push(&sentence));
+        }
+        statuses.extend(monitor.flush());
+        assert!(monitor.flush().is_none());
+
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(*statuses[0].state(), IntegrityState::Nominal);
+        assert!(statuses[0].is_usable());
+        assert_eq!(*statuses[1].state(), IntegrityState::FaultDetected);
+        assert_eq!(statuses[1].time(), &Some(Time::constant(23, 54, 58, 0)));
+        assert_eq!(statuses[1].vertical_sigma(), &Some(3.1));
+        assert!(!statuses[1].is_usable());
+    }
+}
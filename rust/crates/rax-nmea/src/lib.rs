@@ -2,7 +2,12 @@
 
 pub mod common;
 mod error;
+pub mod integrity;
 pub mod rules;
 pub use error::RaxNmeaError;
 pub mod sentence;
+#[cfg(feature = "tokio")]
+mod stream;
+#[cfg(feature = "tokio")]
+pub use stream::{DEFAULT_MAX_LINE_LENGTH, NmeaStream};
 mod utils;
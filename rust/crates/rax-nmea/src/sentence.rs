@@ -1,3 +1,9 @@
+use rax::string::{Decoder, IDecode};
+
+use crate::RaxNmeaError;
+use crate::common::{Identifier, Talker};
+use crate::rules::{NmeaIdentifier, NmeaTalker, NmeaValidateMultiLine};
+
 mod dhv;
 mod dtm;
 mod gbq;
@@ -39,3 +45,123 @@ pub use txt::*;
 pub use vlw::*;
 pub use vtg::*;
 pub use zda::*;
+
+/// Any decoded NMEA sentence, tagged with its talker.
+///
+/// Decoding validates every line of the input, then dispatches on the
+/// identifier. Multi-line sentences (`GSV`, `TXT`) must be passed as a whole
+/// group.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+pub enum NmeaSentence {
+    DHV(Talker, Dhv),
+    DTM(Talker, Dtm),
+    GBQ(Talker, Gbq),
+    GBS(Talker, Gbs),
+    GGA(Talker, Gga),
+    GLL(Talker, Gll),
+    GLQ(Talker, Glq),
+    GNQ(Talker, Gnq),
+    GNS(Talker, Gns),
+    GPQ(Talker, Gpq),
+    GRS(Talker, Grs),
+    GSA(Talker, Gsa),
+    GST(Talker, Gst),
+    GSV(Talker, Gsv),
+    RMC(Talker, Rmc),
+    THS(Talker, Ths),
+    TXT(Talker, Txt),
+    VLW(Talker, Vlw),
+    VTG(Talker, Vtg),
+    ZDA(Talker, Zda),
+}
+
+impl NmeaSentence {
+    /// Returns the identifier of the sentence.
+    pub const fn identifier(&self) -> Identifier {
+        match self {
+            Self::DHV(..) => Identifier::DHV,
+            Self::DTM(..) => Identifier::DTM,
+            Self::GBQ(..) => Identifier::GBQ,
+            Self::GBS(..) => Identifier::GBS,
+            Self::GGA(..) => Identifier::GGA,
+            Self::GLL(..) => Identifier::GLL,
+            Self::GLQ(..) => Identifier::GLQ,
+            Self::GNQ(..) => Identifier::GNQ,
+            Self::GNS(..) => Identifier::GNS,
+            Self::GPQ(..) => Identifier::GPQ,
+            Self::GRS(..) => Identifier::GRS,
+            Self::GSA(..) => Identifier::GSA,
+            Self::GST(..) => Identifier::GST,
+            Self::GSV(..) => Identifier::GSV,
+            Self::RMC(..) => Identifier::RMC,
+            Self::THS(..) => Identifier::THS,
+            Self::TXT(..) => Identifier::TXT,
+            Self::VLW(..) => Identifier::VLW,
+            Self::VTG(..) => Identifier::VTG,
+            Self::ZDA(..) => Identifier::ZDA,
+        }
+    }
+
+    /// Returns the talker of the sentence.
+    pub const fn talker(&self) -> Talker {
+        match self {
+            Self::DHV(t, _)
+            | Self::DTM(t, _)
+            | Self::GBQ(t, _)
+            | Self::GBS(t, _)
+            | Self::GGA(t, _)
+            | Self::GLL(t, _)
+            | Self::GLQ(t, _)
+            | Self::GNQ(t, _)
+            | Self::GNS(t, _)
+            | Self::GPQ(t, _)
+            | Self::GRS(t, _)
+            | Self::GSA(t, _)
+            | Self::GST(t, _)
+            | Self::GSV(t, _)
+            | Self::RMC(t, _)
+            | Self::THS(t, _)
+            | Self::TXT(t, _)
+            | Self::VLW(t, _)
+            | Self::VTG(t, _)
+            | Self::ZDA(t, _) => *t,
+        }
+    }
+}
+
+impl IDecode<RaxNmeaError> for NmeaSentence {
+    fn decode(parser: &mut Decoder<'_>) -> Result<Self, RaxNmeaError> {
+        parser.global(&NmeaValidateMultiLine)?;
+        let identifier = parser.global(&NmeaIdentifier)?;
+        let talker = parser.global(&NmeaTalker)?;
+        clerk::debug!(
+            "NmeaSentence::decode: identifier={:?}, talker={:?}",
+            identifier,
+            talker
+        );
+        let sentence = match identifier {
+            Identifier::DHV => Self::DHV(talker, parser.decode()?),
+            Identifier::DTM => Self::DTM(talker, parser.decode()?),
+            Identifier::GBQ => Self::GBQ(talker, parser.decode()?),
+            Identifier::GBS => Self::GBS(talker, parser.decode()?),
+            Identifier::GGA => Self::GGA(talker, parser.decode()?),
+            Identifier::GLL => Self::GLL(talker, parser.decode()?),
+            Identifier::GLQ => Self::GLQ(talker, parser.decode()?),
+            Identifier::GNQ => Self::GNQ(talker, parser.decode()?),
+            Identifier::GNS => Self::GNS(talker, parser.decode()?),
+            Identifier::GPQ => Self::GPQ(talker, parser.decode()?),
+            Identifier::GRS => Self::GRS(talker, parser.decode()?),
+            Identifier::GSA => Self::GSA(talker, parser.decode()?),
+            Identifier::GST => Self::GST(talker, parser.decode()?),
+            Identifier::GSV => Self::GSV(talker, parser.decode()?),
+            Identifier::RMC => Self::RMC(talker, parser.decode()?),
+            Identifier::THS => Self::THS(talker, parser.decode()?),
+            Identifier::TXT => Self::TXT(talker, parser.decode()?),
+            Identifier::VLW => Self::VLW(talker, parser.decode()?),
+            Identifier::VTG => Self::VTG(talker, parser.decode()?),
+            Identifier::ZDA => Self::ZDA(talker, parser.decode()?),
+        };
+        Ok(sentence)
+    }
+}
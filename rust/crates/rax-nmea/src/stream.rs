@@ -0,0 +1,428 @@
+extern crate alloc;
+extern crate std;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
+
+use futures_core::Stream;
+use rax::string::{Decoder, IGlobalRule};
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::RaxNmeaError;
+use crate::common::Identifier;
+use crate::rules::{NmeaGsvLineCount, NmeaIdentifier, NmeaTxtLineCount};
+use crate::sentence::NmeaSentence;
+
+/// Size of the chunk read from the underlying reader per poll.
+const READ_CHUNK_SIZE: usize = 256;
+
+/// Default upper bound of a single buffered line.
+///
+/// NMEA 0183 limits a sentence to 82 characters; proprietary sentences and
+/// noisy links are given some headroom.
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 1024;
+
+/// Decodes NMEA sentences from a tokio [`AsyncRead`].
+///
+/// Input is framed on CR/LF, empty lines are skipped, and the lines of a
+/// multi-line group (`GSV`, `TXT`) are aggregated before decoding so each item
+/// is one complete [`NmeaSentence`].
+///
+/// The reader is only polled when the consumer asks for the next item and no
+/// complete line is buffered, so a slow consumer applies backpressure to the
+/// source and memory stays bounded by the maximum line length plus one read
+/// chunk. A line longer than the configured maximum is dropped up to its
+/// terminator and reported once as [`RaxNmeaError::InvalidSentenceLength`].
+///
+/// A decoding error is yielded as an `Err` item and does not end the stream;
+/// only an I/O error or the end of input does.
+///
+/// # Example
+///
+/// ```
+/// use futures::StreamExt;
+/// use rax_nmea::NmeaStream;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let input: &[u8] = b"$GPGST,182141.000,15.5,15.3,7.2,21.8,0.9,0.5,0.8*54\r\n";
+/// let mut stream = NmeaStream::new(input);
+/// while let Some(s) = stream.next().await {
+///     println!("{:?}", s.unwrap());
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct NmeaStream<R> {
+    reader: R,
+    /// Bytes read from the reader that do not form a complete line yet.
+    buf: Vec<u8>,
+    /// Lines of the group being aggregated.
+    group: String,
+    /// Identifier of the group being aggregated.
+    identifier: Option<Identifier>,
+    /// Lines still expected to complete the group.
+    remaining: u8,
+    max_line_length: usize,
+    /// Whether the rest of an oversized line is being skipped.
+    discarding: bool,
+    eof: bool,
+}
+
+impl<R> NmeaStream<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Creates a stream decoding the sentences read from `reader`.
+    ///
+    /// Lines are limited to [`DEFAULT_MAX_LINE_LENGTH`] bytes, see
+    /// [`with_max_line_length`](Self::with_max_line_length).
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::with_capacity(READ_CHUNK_SIZE),
+            group: String::new(),
+            identifier: None,
+            remaining: 0,
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            discarding: false,
+            eof: false,
+        }
+    }
+
+    /// Sets the upper bound of a single buffered line.
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub const fn get_ref(&self) -> &R { &self.reader }
+
+    /// Consumes the stream, returning the underlying reader.
+    ///
+    /// Buffered input that has not been decoded yet is lost.
+    pub fn into_inner(self) -> R { self.reader }
+
+    /// Drops buffered input up to and including the next line terminator.
+    ///
+    /// Returns `false` if no terminator is buffered yet, in which case the
+    /// whole buffer is dropped.
+    fn skip_line(&mut self) -> bool {
+        match self.buf.iter().position(|b| *b == b'\n' || *b == b'\r') {
+            Some(pos) => {
+                self.buf.drain(..=pos);
+                true
+            }
+            None => {
+                self.buf.clear();
+                false
+            }
+        }
+    }
+
+    /// Splits the next complete line off the buffer, without its terminator.
+    fn next_line(&mut self) -> Option<Vec<u8>> {
+        let pos = self.buf.iter().position(|b| *b == b'\n' || *b == b'\r')?;
+        let mut line: Vec<u8> = self.buf.drain(..=pos).collect();
+        line.pop();
+        Some(line)
+    }
+
+    /// Feeds one line into the current group.
+    ///
+    /// Returns the decoded sentence once the group is complete.
+    fn push_line(&mut self, line: &str) -> Option<Result<NmeaSentence, RaxNmeaError>> {
+        let identifier = match Decoder::new(line).global(&NmeaIdentifier) {
+            Ok(identifier) => identifier,
+            Err(e) => {
+                self.reset_group();
+                return Some(Err(e.into()));
+            }
+        };
+
+        if self.remaining > 0 {
+            if self.identifier == Some(identifier) {
+                self.group.push_str("\r\n");
+                self.group.push_str(line);
+                self.remaining -= 1;
+                return (self.remaining == 0).then(|| self.decode_group());
+            }
+            // The group was cut short: report it and requeue this line so it
+            // starts a new group on the next poll.
+            clerk::warn!(
+                "NmeaStream: incomplete {:?} group, {} line(s) missing",
+                self.identifier,
+                self.remaining
+            );
+            let incomplete = core::mem::take(&mut self.group);
+            self.reset_group();
+            self.buf
+                .splice(0..0, line.bytes().chain(core::iter::once(b'\n')));
+            return Some(Err(RaxNmeaError::InvalidSentence(incomplete)));
+        }
+
+        let line_count = match identifier {
+            Identifier::GSV => NmeaGsvLineCount.apply(line),
+            Identifier::TXT => NmeaTxtLineCount.apply(line),
+            _ => Ok(1),
+        };
+        let line_count = match line_count {
+            Ok(count) => count,
+            Err(_) => return Some(Err(RaxNmeaError::InvalidSentence(line.to_string()))),
+        };
+        self.group.push_str(line);
+        self.identifier = Some(identifier);
+        self.remaining = line_count.saturating_sub(1);
+        (self.remaining == 0).then(|| self.decode_group())
+    }
+
+    fn decode_group(&mut self) -> Result<NmeaSentence, RaxNmeaError> {
+        let group = core::mem::take(&mut self.group);
+        self.reset_group();
+        clerk::trace!("NmeaStream: decoding group='{:?}'", group);
+        Decoder::new(&group).decode()
+    }
+
+    fn reset_group(&mut self) {
+        self.group.clear();
+        self.identifier = None;
+        self.remaining = 0;
+    }
+
+    /// Reads the next chunk into the buffer.
+    ///
+    /// Returns `Ready(Ok(false))` at the end of input.
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<Result<bool, RaxNmeaError>> {
+        let mut chunk = [0_u8; READ_CHUNK_SIZE];
+        let mut read_buf = ReadBuf::new(&mut chunk);
+        ready!(Pin::new(&mut self.reader).poll_read(cx, &mut read_buf))?;
+        let filled = read_buf.filled();
+        self.buf.extend_from_slice(filled);
+        Poll::Ready(Ok(!filled.is_empty()))
+    }
+}
+
+impl<R> Stream for NmeaStream<R>
+where
+    R: AsyncRead + Unpin,
+{
+    type Item = Result<NmeaSentence, RaxNmeaError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.discarding {
+                if this.skip_line() || this.eof {
+                    this.discarding = false;
+                    continue;
+                }
+                match ready!(this.poll_fill(cx)) {
+                    Ok(true) => {}
+                    Ok(false) => this.eof = true,
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                }
+                continue;
+            }
+            let line = match this.next_line() {
+                Some(line) => line,
+                None if this.eof && !this.buf.is_empty() => core::mem::take(&mut this.buf),
+                None if this.eof => {
+                    if this.group.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    let incomplete = core::mem::take(&mut this.group);
+                    this.reset_group();
+                    return Poll::Ready(Some(Err(RaxNmeaError::InvalidSentence(incomplete))));
+                }
+                None if this.buf.len() > this.max_line_length => {
+                    // The line goes on past the buffered input: skip the rest
+                    // of it so its tail is not reported as another line.
+                    let length = this.buf.len();
+                    this.buf.clear();
+                    this.discarding = true;
+                    this.reset_group();
+                    return Poll::Ready(Some(Err(RaxNmeaError::InvalidSentenceLength(length))));
+                }
+                None => {
+                    match ready!(this.poll_fill(cx)) {
+                        Ok(true) => {}
+                        Ok(false) => this.eof = true,
+                        Err(e) => return Poll::Ready(Some(Err(e))),
+                    }
+                    continue;
+                }
+            };
+
+            if line.is_empty() {
+                continue;
+            }
+            if line.len() > this.max_line_length {
+                this.reset_group();
+                return Poll::Ready(Some(Err(RaxNmeaError::InvalidSentenceLength(line.len()))));
+            }
+            let line = match String::from_utf8(line) {
+                Ok(line) => line,
+                Err(e) => {
+                    this.reset_group();
+                    let line = String::from_utf8_lossy(e.as_bytes()).into_owned();
+                    return Poll::Ready(Some(Err(RaxNmeaError::InvalidSentence(line))));
+                }
+            };
+            if let Some(item) = this.push_line(&line) {
+                return Poll::Ready(Some(item));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use clerk::{LevelFilter, init_log_with_level};
+    use futures::StreamExt;
+    use rstest::rstest;
+
+    use super::*;
+
+    async fn collect(input: &[u8]) -> Vec<Result<NmeaSentence, RaxNmeaError>> {
+        NmeaStream::new(input).collect().await
+    }
+
+    #[rstest]
+    #[case(
+        "single",
+        "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n"
+    )]
+    #[case(
+        "multi_line",
+        "$GPGST,182141.000,15.5,15.3,7.2,21.8,0.9,0.5,0.8*54\r\n\
+         $GPGSV,3,1,10,25,68,053,47,21,59,306,49,29,56,161,49,31,36,265,49*79\r\n\
+         $GPGSV,3,2,10,12,29,048,49,05,22,123,49,18,13,000,49,01,00,000,49*72\r\n\
+         $GPGSV,3,3,10,14,00,000,03,16,00,000,27*7C\r\n\
+         $GPGBS,235458.00,1.4,1.3,3.1,03,,-21.4,3.8,1,0*5A\r\n"
+    )]
+    #[case(
+        "lf_only_no_trailing_newline",
+        "$GPGST,182141.000,15.5,15.3,7.2,21.8,0.9,0.5,0.8*54\n\n\
+         $GPGBS,125027,23.43,M,13.91,M,34.01,M*07"
+    )]
+    #[case(
+        "errors_do_not_end_stream",
+        "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*00\r\n\
+         $GPXXX,1*00\r\n\
+         $GPGSV,3,1,10,25,68,053,47,21,59,306,49,29,56,161,49,31,36,265,49*79\r\n\
+         $GPGST,182141.000,15.5,15.3,7.2,21.8,0.9,0.5,0.8*54\r\n\
+         $GPGBS,235458.00,1.4,1.3,3.1,03,,-21.4,3.8,1,0*5B\r\n"
+    )]
+    #[case(
+        "truncated_group",
+        "$GPGSV,3,1,10,25,68,053,47,21,59,306,49,29,56,161,49,31,36,265,49*79\r\n"
+    )]
+    #[tokio::test]
+    async fn test_nmea_stream(#[case] name: &str, #[case] input: &str) {
+        init_log_with_level(LevelFilter::TRACE);
+        let items = collect(input.as_bytes()).await;
+        insta::assert_debug_snapshot!(name, items);
+    }
+
+    #[tokio::test]
+    async fn test_nmea_stream_max_line_length() {
+        init_log_with_level(LevelFilter::TRACE);
+        let input = b"$GPGST,182141.000,15.5,15.3,7.2,21.8,0.9,0.5,0.8*54\r\n\
+                      $GPGBS,125027,23.43,M,13.91,M,34.01,M*07\r\n";
+        let items: Vec<_> = NmeaStream::new(&input[..])
+            .with_max_line_length(45)
+            .collect()
+            .await;
+        assert_eq!(items.len(), 2);
+        assert!(matches!(
+            items[0],
+            Err(RaxNmeaError::InvalidSentenceLength(51))
+        ));
+        assert!(matches!(items[1], Ok(NmeaSentence::GBS(..))));
+    }
+
+    /// Reader that yields its input a few bytes per read.
+    struct ChunkedReader {
+        input: &'static [u8],
+        chunk: usize,
+    }
+
+    impl AsyncRead for ChunkedReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            let (head, tail) = this.input.split_at(this.chunk.min(this.input.len()));
+            buf.put_slice(head);
+            this.input = tail;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nmea_stream_oversized_line_is_skipped() {
+        init_log_with_level(LevelFilter::TRACE);
+        let input = b"$GPGST,182141.000,15.5,15.3,7.2,21.8,0.9,0.5,0.8*54\r\n\
+                      $GPGBS,125027,23.43,M,13.91,M,34.01,M*07\r\n";
+        let items: Vec<_> = NmeaStream::new(ChunkedReader { input, chunk: 8 })
+            .with_max_line_length(45)
+            .collect()
+            .await;
+        assert_eq!(items.len(), 2);
+        assert!(matches!(
+            items[0],
+            Err(RaxNmeaError::InvalidSentenceLength(48))
+        ));
+        assert!(matches!(items[1], Ok(NmeaSentence::GBS(..))));
+    }
+
+    /// Reader that yields one line per read and counts the reads.
+    struct CountingReader {
+        line: &'static [u8],
+        remaining: usize,
+        reads: usize,
+    }
+
+    impl AsyncRead for CountingReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let this = self.get_mut();
+            this.reads += 1;
+            if this.remaining > 0 {
+                this.remaining -= 1;
+                buf.put_slice(this.line);
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nmea_stream_backpressure() {
+        init_log_with_level(LevelFilter::TRACE);
+        let mut stream = NmeaStream::new(CountingReader {
+            line: b"$GPGST,182141.000,15.5,15.3,7.2,21.8,0.9,0.5,0.8*54\r\n",
+            remaining: 16,
+            reads: 0,
+        });
+        assert_eq!(stream.get_ref().reads, 0);
+        for count in 1..=16 {
+            let s = stream.next().await.unwrap();
+            assert!(matches!(s, Ok(NmeaSentence::GST(..))));
+            // Each item needs exactly one more line from the reader.
+            assert_eq!(stream.get_ref().reads, count);
+            assert!(stream.buf.len() <= DEFAULT_MAX_LINE_LENGTH);
+        }
+        assert!(stream.next().await.is_none());
+        assert_eq!(stream.get_ref().reads, 17);
+    }
+}